@@ -0,0 +1,236 @@
+//! Encoders and decoders for fixed-length arrays.
+use crate::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
+use std::array;
+use std::mem;
+
+/// Decoder for arrays that consist of exactly `N` items.
+///
+/// Unlike `DecodeExt::collectn`, this decoder requires no heap allocation.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::array::ArrayDecoder;
+/// use bytecodec::fixnum::U16beDecoder;
+/// use bytecodec::io::IoDecodeExt;
+///
+/// let mut decoder = ArrayDecoder::<_, 3>::new(U16beDecoder::new());
+/// let item = decoder.decode_exact(&[0, 1, 0, 2, 0, 3][..]).unwrap();
+/// assert_eq!(item, [1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct ArrayDecoder<D: Decode, const N: usize> {
+    inner: D,
+    items: [Option<D::Item>; N],
+    index: usize,
+}
+impl<D: Decode, const N: usize> ArrayDecoder<D, N> {
+    /// Makes a new `ArrayDecoder` instance that decodes each item by using `inner`.
+    pub fn new(inner: D) -> Self {
+        ArrayDecoder {
+            inner,
+            items: array::from_fn(|_| None),
+            index: 0,
+        }
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    fn path(&self) -> String {
+        format!("array[{}]", self.index)
+    }
+}
+impl<D: Decode, const N: usize> Decode for ArrayDecoder<D, N> {
+    type Item = [D::Item; N];
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while self.index < N {
+            let start = offset;
+            try_decode_with_path!(self.inner, offset, buf, eos, self.path());
+
+            let item = track!(self
+                .inner
+                .finish_decoding()
+                .map_err(|e| e.with_offset(start as u64).with_path(self.path())))?;
+            self.items[self.index] = Some(item);
+            self.index += 1;
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert_eq!(self.index, N, ErrorKind::IncompleteDecoding);
+        self.index = 0;
+        let items = mem::replace(&mut self.items, array::from_fn(|_| None));
+        Ok(items.map(|item| item.expect("Never fails")))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.index == N {
+            ByteCount::Finite(0)
+        } else {
+            self.inner.requiring_bytes()
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.index == N
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.items = array::from_fn(|_| None);
+        self.index = 0;
+        track!(self.inner.reset())
+    }
+}
+impl<D: Decode + Default, const N: usize> Default for ArrayDecoder<D, N> {
+    fn default() -> Self {
+        Self::new(D::default())
+    }
+}
+
+/// Encoder for arrays that consist of exactly `N` items.
+///
+/// Each item is encoded by its own encoder,
+/// so the exact number of bytes to be encoded is known if the element encoder implements `SizedEncode`.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::{Encode, SizedEncode};
+/// use bytecodec::array::ArrayEncoder;
+/// use bytecodec::fixnum::U16beEncoder;
+/// use bytecodec::io::IoEncodeExt;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = ArrayEncoder::<U16beEncoder, 3>::default();
+/// encoder.start_encoding([1, 2, 3]).unwrap();
+/// assert_eq!(encoder.exact_requiring_bytes(), 6);
+///
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 1, 0, 2, 0, 3]);
+/// ```
+#[derive(Debug)]
+pub struct ArrayEncoder<E, const N: usize> {
+    inner: [E; N],
+}
+impl<E, const N: usize> ArrayEncoder<E, N> {
+    /// Makes a new `ArrayEncoder` instance that encodes the `i`-th item by using `inner[i]`.
+    pub fn new(inner: [E; N]) -> Self {
+        ArrayEncoder { inner }
+    }
+
+    /// Returns a reference to the inner encoders.
+    pub fn inner_ref(&self) -> &[E; N] {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoders.
+    pub fn inner_mut(&mut self) -> &mut [E; N] {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoders.
+    pub fn into_inner(self) -> [E; N] {
+        self.inner
+    }
+}
+impl<E: Encode, const N: usize> Encode for ArrayEncoder<E, N> {
+    type Item = [E::Item; N];
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        for (i, encoder) in self.inner.iter_mut().enumerate() {
+            bytecodec_try_encode!(encoder, offset, buf, eos, "i={}", i);
+        }
+        Ok(offset)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        for (i, (encoder, item)) in self.inner.iter_mut().zip(item).enumerate() {
+            track!(encoder.start_encoding(item), "i={}", i)?;
+        }
+        Ok(())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.iter().fold(ByteCount::Finite(0), |n, e| {
+            n.add_for_encoding(e.requiring_bytes())
+        })
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.iter().all(Encode::is_idle)
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        for (i, encoder) in self.inner.iter_mut().enumerate() {
+            track!(encoder.reset(), "i={}", i)?;
+        }
+        Ok(())
+    }
+}
+impl<E: SizedEncode, const N: usize> SizedEncode for ArrayEncoder<E, N> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner
+            .iter()
+            .map(SizedEncode::exact_requiring_bytes)
+            .sum()
+    }
+}
+impl<E: Default, const N: usize> Default for ArrayEncoder<E, N> {
+    fn default() -> Self {
+        Self::new(array::from_fn(|_| E::default()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixnum::{U8Decoder, U8Encoder};
+    use crate::io::{IoDecodeExt, IoEncodeExt};
+    use crate::tuple::{TupleDecoder, TupleEncoder};
+
+    #[test]
+    fn nested_array_works() {
+        let mut output = Vec::new();
+        let mut encoder = ArrayEncoder::<ArrayEncoder<U8Encoder, 2>, 2>::default();
+        track_try_unwrap!(encoder.start_encoding([[1, 2], [3, 4]]));
+        assert_eq!(encoder.exact_requiring_bytes(), 4);
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [1, 2, 3, 4]);
+
+        let mut decoder = ArrayDecoder::<ArrayDecoder<U8Decoder, 2>, 2>::default();
+        let item = track_try_unwrap!(decoder.decode_exact(&output[..]));
+        assert_eq!(item, [[1, 2], [3, 4]]);
+    }
+
+    #[test]
+    fn array_edge_cases_work() {
+        let mut decoder =
+            ArrayDecoder::<_, 2>::new(TupleDecoder::new((U8Decoder::new(), U8Decoder::new())));
+        let error = decoder.decode_exact(&[1, 2, 3][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+        assert_eq!(error.path(), "array[1] / tuple.1 / U8Decoder");
+
+        let mut encoder = ArrayEncoder::<TupleEncoder<(U8Encoder, U8Encoder)>, 0>::default();
+        assert!(encoder.is_idle());
+        track_try_unwrap!(encoder.start_encoding([]));
+        assert_eq!(encoder.requiring_bytes(), ByteCount::Finite(0));
+    }
+}
@@ -0,0 +1,1002 @@
+//! Encoders and decoders that refer back to earlier bytes by their stream position.
+//!
+//! Some formats (e.g., DNS name compression) replace repeated data with a pointer to its previous
+//! occurrence. The `Decode` and `Encode` APIs don't expose stream positions, so this module
+//! provides contexts shared between an outermost positioned codec and the nested codecs that
+//! need to resolve (or emit) such pointers:
+//!
+//! - `PositionedDecoder` records every consumed byte, with its absolute position, into a `DecodeContext`
+//! - `PositionedEncoder` tracks the absolute position of every produced byte in an `EncodeContext`
+//!
+//! Nested codecs obtain the absolute position of the buffer given to them via `position_of` methods.
+//! Positions are computed from the address of the buffer, so this works only if every intermediate
+//! codec passes (sub-slices of) the buffer given to the positioned codec as is.
+//! The following codecs of this crate satisfy it:
+//!
+//! - the combinators in `bytecodec::combinator` except `Or` and `PreEncode`
+//! - tuple, array, map and sum codecs (`bytecodec::{tuple, array, map, sum}`)
+//! - `DemuxDecoder` and the codecs in `bytecodec::xdr`
+//!
+//! `Or` (which replays buffered bytes), `PreEncode` and `MuxEncoder` (which encode into their own
+//! buffers) and monolithic codecs are not supported; `position_of` returns
+//! an `ErrorKind::InvalidInput` error inside them.
+//! `BufferedIo` and the `io` helpers can be used to feed the outermost positioned codec.
+//!
+//! # Examples
+//!
+//! ```
+//! use bytecodec::backref::{
+//!     DecodeContext, DnsNameDecoder, DnsNameEncoder, EncodeContext, PositionedDecoder,
+//!     PositionedEncoder,
+//! };
+//! use bytecodec::io::IoDecodeExt;
+//! use bytecodec::tuple::{TupleDecoder, TupleEncoder};
+//! use bytecodec::EncodeExt;
+//!
+//! let context = EncodeContext::new();
+//! let mut encoder = PositionedEncoder::new(
+//!     TupleEncoder::new((
+//!         DnsNameEncoder::<&str>::new(context.clone()),
+//!         DnsNameEncoder::<&str>::new(context.clone()),
+//!     )),
+//!     context,
+//! );
+//! let bytes = encoder
+//!     .encode_into_bytes(("www.example.com", "mail.example.com"))
+//!     .unwrap();
+//! assert_eq!(
+//!     bytes,
+//!     b"\x03www\x07example\x03com\x00\x04mail\xc0\x04"
+//! );
+//!
+//! let context = DecodeContext::new();
+//! let mut decoder = PositionedDecoder::new(
+//!     TupleDecoder::new((
+//!         DnsNameDecoder::new(context.clone()),
+//!         DnsNameDecoder::new(context.clone()),
+//!     )),
+//!     context,
+//! );
+//! let item = decoder.decode_exact(&bytes[..]).unwrap();
+//! assert_eq!(item, ("www.example.com".to_owned(), "mail.example.com".to_owned()));
+//! ```
+use crate::bytes::BytesEncoder;
+use crate::{ByteCount, Decode, Encode, Eos, ErrorKind, Result};
+use std::any::Any;
+use std::cmp;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, MutexGuard};
+use trackable::error::ErrorKindExt;
+
+/// The default number of bytes retained by `DecodeContext`.
+pub const DEFAULT_WINDOW_SIZE: usize = 0x1_0000;
+
+/// The absolute position of the buffer currently handled by a positioned codec.
+#[derive(Debug, Default, Clone, Copy)]
+struct Base {
+    addr: usize,
+    len: usize,
+    position: u64,
+}
+impl Base {
+    fn position_of(&self, buf: &[u8]) -> Result<u64> {
+        let addr = buf.as_ptr() as usize;
+        track_assert!(
+            self.addr <= addr && addr + buf.len() <= self.addr + self.len,
+            ErrorKind::InvalidInput,
+            "The buffer is not a part of the one given to the positioned codec \
+             (it may be nested in an unsupported combinator; see `bytecodec::backref`)"
+        );
+        Ok(self.position + (addr - self.addr) as u64)
+    }
+
+    fn end(&self) -> u64 {
+        self.position + self.len as u64
+    }
+}
+
+struct DecodeState {
+    window_start: u64,
+    window: VecDeque<u8>,
+    window_size: usize,
+    items: BTreeMap<u64, Box<dyn Any + Send>>,
+    item_start: u64,
+    base: Base,
+}
+
+/// Decoding context that retains a window of the bytes (and items) consumed by a `PositionedDecoder`.
+///
+/// The clones of an instance share the same state.
+#[derive(Clone)]
+pub struct DecodeContext(Arc<Mutex<DecodeState>>);
+impl DecodeContext {
+    /// Makes a new `DecodeContext` instance that retains up to `DEFAULT_WINDOW_SIZE` bytes.
+    pub fn new() -> Self {
+        Self::with_window_size(DEFAULT_WINDOW_SIZE)
+    }
+
+    /// Makes a new `DecodeContext` instance that retains up to `window_size` consumed bytes.
+    pub fn with_window_size(window_size: usize) -> Self {
+        DecodeContext(Arc::new(Mutex::new(DecodeState {
+            window_start: 0,
+            window: VecDeque::new(),
+            window_size,
+            items: BTreeMap::new(),
+            item_start: 0,
+            base: Base::default(),
+        })))
+    }
+
+    /// Returns the absolute position of the next byte to be consumed.
+    pub fn position(&self) -> u64 {
+        self.state().base.position
+    }
+
+    /// Returns the absolute position at which the item currently being decoded started.
+    pub fn item_start(&self) -> u64 {
+        self.state().item_start
+    }
+
+    /// Returns the absolute position of the first byte of `buf`.
+    ///
+    /// `buf` must be (a sub-slice of) the buffer given to the `PositionedDecoder` sharing this context.
+    ///
+    /// # Errors
+    ///
+    /// If `buf` is not a part of that buffer (see the [module documentation](index.html)),
+    /// an `ErrorKind::InvalidInput` error will be returned.
+    pub fn position_of(&self, buf: &[u8]) -> Result<u64> {
+        track!(self.state().base.position_of(buf))
+    }
+
+    /// Calls `f` with a copy of the retained bytes in the given range of absolute positions.
+    ///
+    /// The range may extend up to the end of the buffer currently given to the `PositionedDecoder`
+    /// (i.e., a nested decoder can refer to the bytes preceding its own buffer).
+    /// Since `f` is called after the lock of this context is released, it may use this context.
+    ///
+    /// # Errors
+    ///
+    /// If a byte in `range` has been evicted from the window or has not been received yet,
+    /// an `ErrorKind::InvalidInput` error will be returned.
+    pub fn with_bytes<F, T>(&self, range: Range<u64>, f: F) -> Result<T>
+    where
+        F: FnOnce(&[u8]) -> Result<T>,
+    {
+        let bytes = {
+            let state = self.state();
+            let end = cmp::min(
+                state.window_start + state.window.len() as u64,
+                state.base.end(),
+            );
+            track_assert!(
+                state.window_start <= range.start && range.start <= range.end && range.end <= end,
+                ErrorKind::InvalidInput,
+                "Out of window: range={:?}, window={}..{}",
+                range,
+                state.window_start,
+                end
+            );
+            let start = (range.start - state.window_start) as usize;
+            let end = (range.end - state.window_start) as usize;
+            state.window.range(start..end).copied().collect::<Vec<_>>()
+        };
+        track!(f(&bytes))
+    }
+
+    /// Retains `item` as the one decoded at the given absolute position.
+    ///
+    /// Items are evicted along with the bytes at their positions.
+    /// If an item is already retained at the position, the existing one is kept.
+    pub fn insert_item<T: Any + Send>(&self, position: u64, item: T) {
+        let mut state = self.state();
+        if position >= state.window_start {
+            state
+                .items
+                .entry(position)
+                .or_insert_with(|| Box::new(item));
+        }
+    }
+
+    /// Returns a clone of the item retained at the given absolute position.
+    ///
+    /// If there is no such item or its type is not `T`, this returns `None`.
+    pub fn item<T: Any + Clone>(&self, position: u64) -> Option<T> {
+        let state = self.state();
+        state
+            .items
+            .get(&position)
+            .and_then(|item| item.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Discards all the retained bytes and items, and resets the position to zero.
+    pub fn reset(&self) {
+        let mut state = self.state();
+        state.window_start = 0;
+        state.window.clear();
+        state.items.clear();
+        state.item_start = 0;
+        state.base = Base::default();
+    }
+
+    fn begin(&self, buf: &[u8]) {
+        let mut state = self.state();
+        let state = &mut *state;
+        state.base.addr = buf.as_ptr() as usize;
+        state.base.len = buf.len();
+
+        // The bytes not consumed by the previous call are given again, so only the new ones are retained.
+        let received =
+            (state.window_start + state.window.len() as u64 - state.base.position) as usize;
+        if received < buf.len() {
+            state.window.extend(&buf[received..]);
+        }
+    }
+
+    fn commit(&self, size: usize) {
+        let mut state = self.state();
+        let state = &mut *state;
+        state.base.position += size as u64;
+        state.base.len = 0;
+
+        let consumed = (state.base.position - state.window_start) as usize;
+        if consumed > state.window_size {
+            let evicted = consumed - state.window_size;
+            state.window.drain(..evicted);
+            state.window_start += evicted as u64;
+            state.items = state.items.split_off(&state.window_start);
+        }
+    }
+
+    fn finish_item(&self) {
+        let mut state = self.state();
+        state.item_start = state.base.position;
+    }
+
+    fn state(&self) -> MutexGuard<'_, DecodeState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+impl Default for DecodeContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl fmt::Debug for DecodeContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state();
+        write!(
+            f,
+            "DecodeContext {{ position: {}, item_start: {}, window: {}..{}, items_len: {} }}",
+            state.base.position,
+            state.item_start,
+            state.window_start,
+            state.window_start + state.window.len() as u64,
+            state.items.len()
+        )
+    }
+}
+
+/// Decoder that records the bytes consumed by the inner decoder into a `DecodeContext`.
+///
+/// This should be the outermost decoder of the stream (or message) whose bytes are referred to.
+///
+/// Each byte of the stream is copied into the context only once:
+/// the bytes not consumed by a call of `decode` are assumed to be given again at the head of
+/// the buffer of the next call (as the `Decode` contract requires).
+#[derive(Debug)]
+pub struct PositionedDecoder<D> {
+    inner: D,
+    context: DecodeContext,
+}
+impl<D: Decode> PositionedDecoder<D> {
+    /// Makes a new `PositionedDecoder` instance.
+    pub fn new(inner: D, context: DecodeContext) -> Self {
+        PositionedDecoder { inner, context }
+    }
+
+    /// Returns a reference to the context.
+    pub fn context(&self) -> &DecodeContext {
+        &self.context
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+impl<D: Decode> Decode for PositionedDecoder<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        self.context.begin(buf);
+        let result = self.inner.decode(buf, eos);
+        self.context.commit(*result.as_ref().unwrap_or(&0));
+        track!(result)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track!(self.inner.finish_decoding())?;
+        self.context.finish_item();
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())?;
+        self.context.reset();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct EncodeState {
+    item_start: u64,
+    base: Base,
+    dictionary: HashMap<Vec<u8>, u64>,
+}
+
+/// Encoding context that tracks the position of the bytes produced by a `PositionedEncoder`.
+///
+/// It also has a dictionary that maps keys (e.g., the wire format of a DNS name suffix)
+/// to the absolute positions at which they have been emitted.
+/// The dictionary is cleared when the positioned encoder starts encoding a new item.
+///
+/// The clones of an instance share the same state.
+#[derive(Clone, Default)]
+pub struct EncodeContext(Arc<Mutex<EncodeState>>);
+impl EncodeContext {
+    /// Makes a new `EncodeContext` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the absolute position of the next byte to be produced.
+    pub fn position(&self) -> u64 {
+        self.state().base.position
+    }
+
+    /// Returns the absolute position at which the item currently being encoded started.
+    pub fn item_start(&self) -> u64 {
+        self.state().item_start
+    }
+
+    /// Returns the absolute position of the first byte of `buf`.
+    ///
+    /// `buf` must be (a sub-slice of) the buffer given to the `PositionedEncoder` sharing this context.
+    pub fn position_of(&self, buf: &[u8]) -> Result<u64> {
+        track!(self.state().base.position_of(buf))
+    }
+
+    /// Returns the absolute position associated with `key`.
+    pub fn lookup(&self, key: &[u8]) -> Option<u64> {
+        self.state().dictionary.get(key).cloned()
+    }
+
+    /// Associates `key` with the given absolute position.
+    ///
+    /// If the key is already in the dictionary, the existing position is kept.
+    pub fn insert(&self, key: Vec<u8>, position: u64) {
+        self.state().dictionary.entry(key).or_insert(position);
+    }
+
+    /// Clears the dictionary and resets the position to zero.
+    pub fn reset(&self) {
+        *self.state() = EncodeState::default();
+    }
+
+    fn begin(&self, buf: &[u8]) {
+        let mut state = self.state();
+        state.base.addr = buf.as_ptr() as usize;
+        state.base.len = buf.len();
+    }
+
+    fn commit(&self, size: usize) {
+        let mut state = self.state();
+        state.base.position += size as u64;
+        state.base.len = 0;
+    }
+
+    fn start_item(&self) {
+        let mut state = self.state();
+        state.item_start = state.base.position;
+        state.dictionary.clear();
+    }
+
+    fn state(&self) -> MutexGuard<'_, EncodeState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+impl fmt::Debug for EncodeContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state();
+        write!(
+            f,
+            "EncodeContext {{ position: {}, item_start: {}, dictionary_len: {} }}",
+            state.base.position,
+            state.item_start,
+            state.dictionary.len()
+        )
+    }
+}
+
+/// Encoder that tracks the position of the bytes produced by the inner encoder in an `EncodeContext`.
+///
+/// This should be the outermost encoder of the stream (or message) whose bytes are referred to.
+#[derive(Debug)]
+pub struct PositionedEncoder<E> {
+    inner: E,
+    context: EncodeContext,
+}
+impl<E: Encode> PositionedEncoder<E> {
+    /// Makes a new `PositionedEncoder` instance.
+    pub fn new(inner: E, context: EncodeContext) -> Self {
+        PositionedEncoder { inner, context }
+    }
+
+    /// Returns a reference to the context.
+    pub fn context(&self) -> &EncodeContext {
+        &self.context
+    }
+
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+impl<E: Encode> Encode for PositionedEncoder<E> {
+    type Item = E::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        self.context.begin(buf);
+        let result = self.inner.encode(buf, eos);
+        self.context.commit(*result.as_ref().unwrap_or(&0));
+        track!(result)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        self.context.start_item();
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())?;
+        self.context.reset();
+        Ok(())
+    }
+}
+
+const MAX_LABEL_LEN: usize = 63;
+const MAX_NAME_LEN: usize = 255;
+const MAX_POINTER_OFFSET: u64 = 0x3FFF;
+
+/// Decoder for DNS domain names which may be compressed by using pointers ([RFC 1035]).
+///
+/// The decoded item is a dot-separated name without the trailing dot (e.g., `"www.example.com"`).
+/// The root domain is decoded as the empty string.
+///
+/// Pointer offsets are interpreted relative to `DecodeContext::item_start`,
+/// so the decoder must be nested inside a `PositionedDecoder` that decodes the whole message
+/// (and not inside the unsupported combinators listed in the module documentation;
+/// decoding fails with an `ErrorKind::InvalidInput` error there).
+///
+/// Every pointer must refer to a name which lies entirely before the pointer itself,
+/// and every subsequent pointer to a position strictly before the previous target,
+/// which guarantees that resolving a name terminates (loop detection).
+///
+/// Decoded names are retained as items of the `DecodeContext` at the positions of their labels,
+/// so that pointers to them are resolved without parsing the retained bytes again.
+///
+/// [RFC 1035]: https://tools.ietf.org/html/rfc1035#section-4.1.4
+#[derive(Debug)]
+pub struct DnsNameDecoder {
+    context: DecodeContext,
+    labels: Vec<Vec<u8>>,
+    name_len: usize,
+    label_positions: Vec<(usize, u64)>,
+    label_remaining: usize,
+    pointer_head: Option<u8>,
+    done: bool,
+}
+impl DnsNameDecoder {
+    /// Makes a new `DnsNameDecoder` instance.
+    pub fn new(context: DecodeContext) -> Self {
+        DnsNameDecoder {
+            context,
+            labels: Vec::new(),
+            name_len: 0,
+            label_positions: Vec::new(),
+            label_remaining: 0,
+            pointer_head: None,
+            done: false,
+        }
+    }
+
+    fn push_label_len(&mut self, len: usize) -> Result<()> {
+        self.name_len += len + 1;
+        track_assert!(
+            self.name_len < MAX_NAME_LEN,
+            ErrorKind::InvalidInput,
+            "Too long name"
+        );
+        self.labels.push(Vec::with_capacity(len));
+        Ok(())
+    }
+
+    fn follow(&mut self, pointer_position: u64, offset: u64) -> Result<()> {
+        let item_start = self.context.item_start();
+        let mut limit = pointer_position;
+        let mut target = item_start + offset;
+        loop {
+            track_assert!(
+                target < limit,
+                ErrorKind::InvalidInput,
+                "Forward or looping pointer: target={}, limit={}",
+                target,
+                limit
+            );
+            if let Some(NameSuffix(labels)) = self.context.item(target) {
+                for label in labels {
+                    track!(self.push_label_len(label.len()))?;
+                    *self.labels.last_mut().expect("Never fails") = label;
+                }
+                return Ok(());
+            }
+
+            let end = cmp::min(pointer_position, target + MAX_NAME_LEN as u64);
+            let context = self.context.clone();
+            let next = track!(context.with_bytes(target..end, |bytes| self.read_labels(bytes)))?;
+            match next {
+                None => return Ok(()),
+                Some(offset) => {
+                    limit = target;
+                    target = item_start + offset;
+                }
+            }
+        }
+    }
+
+    fn read_labels(&mut self, bytes: &[u8]) -> Result<Option<u64>> {
+        let mut offset = 0;
+        loop {
+            let head = *track_assert_some!(bytes.get(offset), ErrorKind::InvalidInput);
+            match head >> 6 {
+                0b00 if head == 0 => return Ok(None),
+                0b00 => {
+                    let len = head as usize;
+                    let label = track_assert_some!(
+                        bytes.get(offset + 1..offset + 1 + len),
+                        ErrorKind::InvalidInput
+                    );
+                    track!(self.push_label_len(len))?;
+                    self.labels
+                        .last_mut()
+                        .expect("Never fails")
+                        .extend_from_slice(label);
+                    offset += 1 + len;
+                }
+                0b11 => {
+                    let tail = *track_assert_some!(bytes.get(offset + 1), ErrorKind::InvalidInput);
+                    return Ok(Some(pointer_offset(head, tail)));
+                }
+                _ => track_panic!(ErrorKind::InvalidInput, "Unsupported label type: {}", head),
+            }
+        }
+    }
+
+    fn retain_suffixes(&mut self) {
+        for (i, position) in self.label_positions.drain(..) {
+            let suffix = NameSuffix(self.labels[i..].to_vec());
+            self.context.insert_item(position, suffix);
+        }
+    }
+}
+impl Decode for DnsNameDecoder {
+    type Item = String;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while !self.done && offset < buf.len() {
+            if let Some(head) = self.pointer_head.take() {
+                let tail = buf[offset];
+                offset += 1;
+                let position = track!(self.context.position_of(buf))? + offset as u64 - 2;
+                track!(self.follow(position, pointer_offset(head, tail)))?;
+                self.done = true;
+            } else if self.label_remaining > 0 {
+                let size = std::cmp::min(self.label_remaining, buf.len() - offset);
+                self.labels
+                    .last_mut()
+                    .expect("Never fails")
+                    .extend_from_slice(&buf[offset..][..size]);
+                self.label_remaining -= size;
+                offset += size;
+            } else {
+                let head = buf[offset];
+                offset += 1;
+                match head >> 6 {
+                    0b00 if head == 0 => self.done = true,
+                    0b00 => {
+                        let position = track!(self.context.position_of(buf))? + offset as u64 - 1;
+                        self.label_positions.push((self.labels.len(), position));
+                        track!(self.push_label_len(head as usize))?;
+                        self.label_remaining = head as usize;
+                    }
+                    0b11 => self.pointer_head = Some(head),
+                    _ => track_panic!(ErrorKind::InvalidInput, "Unsupported label type: {}", head),
+                }
+            }
+        }
+        if self.done {
+            self.retain_suffixes();
+        } else {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos);
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(self.done, ErrorKind::IncompleteDecoding);
+        self.done = false;
+        self.name_len = 0;
+        let name = std::mem::take(&mut self.labels).join(&b'.');
+        let name = track!(String::from_utf8(name).map_err(|e| ErrorKind::InvalidInput.cause(e)))?;
+        Ok(name)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.done {
+            ByteCount::Finite(0)
+        } else if self.label_remaining > 0 {
+            ByteCount::Finite(self.label_remaining as u64)
+        } else {
+            ByteCount::Unknown
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.done
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.labels.clear();
+        self.name_len = 0;
+        self.label_positions.clear();
+        self.label_remaining = 0;
+        self.pointer_head = None;
+        self.done = false;
+        Ok(())
+    }
+}
+
+/// Encoder for DNS domain names which compresses them by using pointers ([RFC 1035]).
+///
+/// The item is a dot-separated name (a trailing dot is allowed).
+/// The root domain is represented by the empty string or `"."`.
+///
+/// Every emitted suffix is registered to the dictionary of the `EncodeContext`,
+/// and a later name ending with the same suffix is encoded as a pointer to it.
+/// The encoder must be nested inside a `PositionedEncoder` that encodes the whole message.
+///
+/// Note that suffixes are compared byte-wise (i.e., case-sensitively).
+///
+/// [RFC 1035]: https://tools.ietf.org/html/rfc1035#section-4.1.4
+#[derive(Debug)]
+pub struct DnsNameEncoder<S> {
+    context: EncodeContext,
+    labels: Option<Vec<Vec<u8>>>,
+    bytes: BytesEncoder<Vec<u8>>,
+    _item: std::marker::PhantomData<S>,
+}
+impl<S> DnsNameEncoder<S> {
+    /// Makes a new `DnsNameEncoder` instance.
+    pub fn new(context: EncodeContext) -> Self {
+        DnsNameEncoder {
+            context,
+            labels: None,
+            bytes: BytesEncoder::new(),
+            _item: std::marker::PhantomData,
+        }
+    }
+
+    fn compress(&self, labels: &[Vec<u8>], position: u64) -> Vec<u8> {
+        let item_start = self.context.item_start();
+        let mut bytes = Vec::new();
+        for i in 0..labels.len() {
+            let mut key = Vec::new();
+            for label in &labels[i..] {
+                key.push(label.len() as u8);
+                key.extend_from_slice(label);
+            }
+            if let Some(target) = self.context.lookup(&key) {
+                let offset = target - item_start;
+                if offset <= MAX_POINTER_OFFSET {
+                    bytes.push(0xC0 | (offset >> 8) as u8);
+                    bytes.push(offset as u8);
+                    return bytes;
+                }
+            }
+
+            let label_position = position + bytes.len() as u64;
+            if label_position - item_start <= MAX_POINTER_OFFSET {
+                self.context.insert(key, label_position);
+            }
+            bytes.push(labels[i].len() as u8);
+            bytes.extend_from_slice(&labels[i]);
+        }
+        bytes.push(0);
+        bytes
+    }
+}
+impl<S: AsRef<str>> Encode for DnsNameEncoder<S> {
+    type Item = S;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        if let Some(labels) = self.labels.take() {
+            let position = track!(self.context.position_of(buf))?;
+            let bytes = self.compress(&labels, position);
+            track!(self.bytes.start_encoding(bytes))?;
+        }
+        track!(self.bytes.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        let name = item.as_ref();
+        let name = name.strip_suffix('.').unwrap_or(name);
+        let mut labels = Vec::new();
+        if !name.is_empty() {
+            for label in name.split('.') {
+                track_assert!(
+                    !label.is_empty() && label.len() <= MAX_LABEL_LEN,
+                    ErrorKind::InvalidInput,
+                    "Invalid label: {:?}",
+                    label
+                );
+                labels.push(label.as_bytes().to_owned());
+            }
+        }
+        track_assert!(
+            name.len() + 2 <= MAX_NAME_LEN,
+            ErrorKind::InvalidInput,
+            "Too long name"
+        );
+        self.labels = Some(labels);
+        Ok(())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.labels.is_some() {
+            ByteCount::Unknown
+        } else {
+            self.bytes.requiring_bytes()
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.labels.is_none() && self.bytes.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.labels = None;
+        track!(self.bytes.reset())
+    }
+}
+
+/// The labels of a decoded DNS name starting at a retained position.
+#[derive(Debug, Clone)]
+struct NameSuffix(Vec<Vec<u8>>);
+
+fn pointer_offset(head: u8, tail: u8) -> u64 {
+    (u64::from(head & 0x3F) << 8) | u64::from(tail)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bytes::CopyableBytesDecoder;
+    use crate::fixnum::{U32beDecoder, U8Decoder, U8Encoder};
+    use crate::io::IoDecodeExt;
+    use crate::tuple::{TupleDecoder, TupleEncoder};
+    use crate::{DecodeExt, EncodeExt};
+
+    #[test]
+    fn dns_name_compression_works() {
+        let context = EncodeContext::new();
+        let mut encoder = PositionedEncoder::new(
+            TupleEncoder::new((
+                U8Encoder::new(),
+                DnsNameEncoder::new(context.clone()),
+                DnsNameEncoder::new(context.clone()),
+                DnsNameEncoder::new(context.clone()),
+            )),
+            context,
+        );
+        let item = (7, "example.com", "a.b.example.com.", "x.b.example.com");
+        let bytes = track_try_unwrap!(encoder.encode_into_bytes(item));
+        assert_eq!(
+            bytes,
+            &b"\x07\x07example\x03com\x00\x01a\x01b\xc0\x01\x01x\xc0\x10"[..]
+        );
+
+        // The second message is encoded from scratch.
+        let bytes2 = track_try_unwrap!(encoder.encode_into_bytes(item));
+        assert_eq!(bytes2, bytes);
+
+        let context = DecodeContext::new();
+        let mut decoder = PositionedDecoder::new(
+            TupleDecoder::new((
+                U8Decoder::new(),
+                DnsNameDecoder::new(context.clone()),
+                DnsNameDecoder::new(context.clone()),
+                DnsNameDecoder::new(context.clone()),
+            )),
+            context,
+        );
+        let mut input = bytes.clone();
+        input.extend_from_slice(&bytes);
+        for _ in 0..2 {
+            // `decode_exact` feeds the message byte-by-byte since the required size is unknown.
+            let item = track_try_unwrap!(decoder.decode_exact(&input[..bytes.len()]));
+            assert_eq!(item.0, 7);
+            assert_eq!(item.1, "example.com");
+            assert_eq!(item.2, "a.b.example.com");
+            assert_eq!(item.3, "x.b.example.com");
+            input.drain(..bytes.len());
+        }
+        assert_eq!(decoder.context().position(), bytes.len() as u64 * 2);
+    }
+
+    #[test]
+    fn dns_name_loop_is_detected() {
+        let context = DecodeContext::new();
+        let mut decoder = PositionedDecoder::new(
+            TupleDecoder::new((
+                DnsNameDecoder::new(context.clone()),
+                DnsNameDecoder::new(context.clone()),
+            )),
+            context,
+        );
+        // The first pointer refers forward to the second name, whose pointer refers back to the first.
+        let bytes = b"\x01a\xc0\x04\x01b\xc0\x02";
+        let error = decoder.decode_from_bytes(bytes).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        // A pointer referring to itself.
+        let context = DecodeContext::new();
+        let mut decoder = PositionedDecoder::new(DnsNameDecoder::new(context.clone()), context);
+        let error = decoder.decode_from_bytes(b"\xc0\x00").err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn dns_name_in_unsupported_combinator_fails() {
+        // `Or` replays the bytes consumed by the failed first decoder from its own buffer
+        let context = DecodeContext::new();
+        let first = U8Decoder::new().try_map(|_| Err::<(), _>(ErrorKind::InvalidInput));
+        let mut decoder =
+            PositionedDecoder::new(first.or(DnsNameDecoder::new(context.clone())), context);
+        let error = decoder.decode_from_bytes(b"\x03www\x00").err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        // `PreEncode` encodes the item into its own buffer
+        let context = EncodeContext::new();
+        let mut encoder = PositionedEncoder::new(
+            DnsNameEncoder::<&str>::new(context.clone()).pre_encode(),
+            context,
+        );
+        let error = encoder.encode_into_bytes("www").err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn dns_name_pointer_chain_works() {
+        // "www" -> "example" (at 5) -> "com" (at 0)
+        let bytes = b"\x03com\x00\x07example\xc0\x00\x03www\xc0\x05";
+
+        // Resolved by parsing the retained bytes.
+        let context = DecodeContext::new();
+        let mut decoder = PositionedDecoder::new(
+            TupleDecoder::new((
+                CopyableBytesDecoder::new([0; 15]),
+                DnsNameDecoder::new(context.clone()),
+            )),
+            context,
+        );
+        let item = track_try_unwrap!(decoder.decode_from_bytes(bytes));
+        assert_eq!(item.1, "www.example.com");
+
+        // Resolved by using the names retained as items.
+        let context = DecodeContext::new();
+        let mut decoder = PositionedDecoder::new(
+            TupleDecoder::new((
+                DnsNameDecoder::new(context.clone()),
+                DnsNameDecoder::new(context.clone()),
+                DnsNameDecoder::new(context.clone()),
+            )),
+            context,
+        );
+        let item = track_try_unwrap!(decoder.decode_from_bytes(bytes));
+        assert_eq!(item.0, "com");
+        assert_eq!(item.1, "example.com");
+        assert_eq!(item.2, "www.example.com");
+        let suffix: Option<NameSuffix> = decoder.context().item(5);
+        assert_eq!(suffix.unwrap().0, [b"example".to_vec(), b"com".to_vec()]);
+    }
+
+    #[test]
+    fn decode_context_window_works() {
+        let context = DecodeContext::with_window_size(4);
+        let mut decoder = PositionedDecoder::new(
+            TupleDecoder::new((U32beDecoder::new(), U32beDecoder::new())),
+            context.clone(),
+        );
+        let input = (0..16).collect::<Vec<u8>>();
+        track_try_unwrap!(decoder.decode_exact(&input[..8]));
+        assert_eq!(context.position(), 8);
+
+        // The closure can use the context.
+        let bytes = track_try_unwrap!(context.with_bytes(4..8, |b| {
+            assert_eq!(context.position(), 8);
+            Ok(b.to_owned())
+        }));
+        assert_eq!(bytes, [4, 5, 6, 7]);
+
+        let error = context.with_bytes(3..8, |_| Ok(())).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+        let error = context.with_bytes(4..9, |_| Ok(())).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        context.insert_item(5, "foo".to_owned());
+        context.insert_item(2, "bar".to_owned());
+        assert_eq!(context.item::<String>(5), Some("foo".to_owned()));
+        assert_eq!(context.item::<u32>(5), None);
+        assert_eq!(context.item::<String>(2), None);
+
+        track_try_unwrap!(decoder.decode_exact(&input[8..]));
+        assert_eq!(context.item::<String>(5), None);
+        let bytes = track_try_unwrap!(context.with_bytes(12..16, |b| Ok(b.to_owned())));
+        assert_eq!(bytes, [12, 13, 14, 15]);
+
+        let error = context.position_of(&input[..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -106,6 +106,7 @@ mod test {
     use super::*;
 
     #[test]
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
     fn it_works() {
         assert_eq!(ByteCount::default(), ByteCount::Unknown);
 
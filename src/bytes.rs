@@ -45,7 +45,7 @@ impl<B: AsRef<[u8]>> Encode for BytesEncoder<B> {
         let mut size = 0;
         let drop_item = if let Some(ref b) = self.bytes {
             size = cmp::min(buf.len(), b.as_ref().len() - self.offset);
-            (&mut buf[..size]).copy_from_slice(&b.as_ref()[self.offset..][..size]);
+            buf[..size].copy_from_slice(&b.as_ref()[self.offset..][..size]);
             self.offset += size;
             if self.offset == b.as_ref().len() {
                 true
@@ -77,6 +77,12 @@ impl<B: AsRef<[u8]>> Encode for BytesEncoder<B> {
     fn is_idle(&self) -> bool {
         self.bytes.is_none()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.bytes = None;
+        self.offset = 0;
+        Ok(())
+    }
 }
 impl<B: AsRef<[u8]>> SizedEncode for BytesEncoder<B> {
     fn exact_requiring_bytes(&self) -> u64 {
@@ -146,7 +152,7 @@ impl<B: AsRef<[u8]> + AsMut<[u8]> + Copy> Decode for CopyableBytesDecoder<B> {
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
         let size = cmp::min(buf.len(), self.bytes.as_ref().len() - self.offset);
-        (&mut self.bytes.as_mut()[self.offset..][..size]).copy_from_slice(&buf[..size]);
+        self.bytes.as_mut()[self.offset..][..size].copy_from_slice(&buf[..size]);
         self.offset += size;
         if self.offset != self.bytes.as_mut().len() {
             track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos;
@@ -172,6 +178,11 @@ impl<B: AsRef<[u8]> + AsMut<[u8]> + Copy> Decode for CopyableBytesDecoder<B> {
     fn is_idle(&self) -> bool {
         self.offset == self.bytes.as_ref().len()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.offset = 0;
+        Ok(())
+    }
 }
 
 /// `BytesDecoder` copies bytes from an input sequence to a slice.
@@ -237,7 +248,7 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> Decode for BytesDecoder<B> {
         let size = {
             let bytes = track_assert_some!(self.bytes.as_mut(), ErrorKind::DecoderTerminated);
             let size = cmp::min(buf.len(), bytes.as_ref().len() - self.offset);
-            (&mut bytes.as_mut()[self.offset..][..size]).copy_from_slice(&buf[..size]);
+            bytes.as_mut()[self.offset..][..size].copy_from_slice(&buf[..size]);
             self.offset += size;
             size
         };
@@ -264,6 +275,11 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> Decode for BytesDecoder<B> {
     fn is_idle(&self) -> bool {
         self.exact_requiring_bytes() == 0
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.offset = 0;
+        Ok(())
+    }
 }
 
 /// `RemainingBytesDecoder` reads all the bytes from a input sequence until it reaches EOS.
@@ -316,7 +332,7 @@ impl Decode for RemainingBytesDecoder {
     fn finish_decoding(&mut self) -> Result<Self::Item> {
         track_assert!(self.eos, ErrorKind::IncompleteDecoding);
         self.eos = false;
-        let bytes = mem::replace(&mut self.buf, Vec::new());
+        let bytes = mem::take(&mut self.buf);
         Ok(bytes)
     }
 
@@ -331,6 +347,12 @@ impl Decode for RemainingBytesDecoder {
     fn is_idle(&self) -> bool {
         self.eos
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.buf.clear();
+        self.eos = false;
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -387,6 +409,10 @@ impl<S: AsRef<str>> Encode for Utf8Encoder<S> {
     fn is_idle(&self) -> bool {
         self.0.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
 }
 impl<S: AsRef<str>> SizedEncode for Utf8Encoder<S> {
     fn exact_requiring_bytes(&self) -> u64 {
@@ -462,6 +488,341 @@ where
     fn is_idle(&self) -> bool {
         self.0.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+
+/// Character set of fixed-width text fields.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Charset {
+    /// Any valid UTF-8 string is allowed.
+    #[default]
+    Utf8,
+
+    /// Only ASCII characters are allowed.
+    Ascii,
+}
+impl Charset {
+    fn validate(self, s: &str) -> Result<()> {
+        if self == Charset::Ascii {
+            track_assert!(s.is_ascii(), ErrorKind::InvalidInput, "Not an ASCII string");
+        }
+        Ok(())
+    }
+}
+
+/// `FixedBytesDecoder` decodes a byte field of exactly `N` bytes and trims the trailing padding.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::bytes::FixedBytesDecoder;
+/// use bytecodec::io::IoDecodeExt;
+///
+/// let mut decoder = FixedBytesDecoder::<6>::new(0);
+/// let item = decoder.decode_exact(b"foo\x00\x00\x00".as_ref()).unwrap();
+/// assert_eq!(item, b"foo");
+/// ```
+#[derive(Debug)]
+pub struct FixedBytesDecoder<const N: usize> {
+    inner: CopyableBytesDecoder<[u8; N]>,
+    padding: u8,
+}
+impl<const N: usize> FixedBytesDecoder<N> {
+    /// Makes a new `FixedBytesDecoder` instance that trims trailing `padding` bytes.
+    pub fn new(padding: u8) -> Self {
+        FixedBytesDecoder {
+            inner: CopyableBytesDecoder::new([0; N]),
+            padding,
+        }
+    }
+
+    /// Returns the padding byte.
+    pub fn padding(&self) -> u8 {
+        self.padding
+    }
+}
+impl<const N: usize> Default for FixedBytesDecoder<N> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+impl<const N: usize> Decode for FixedBytesDecoder<N> {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let bytes = track!(self.inner.finish_decoding())?;
+        let len = bytes
+            .iter()
+            .rposition(|&b| b != self.padding)
+            .map_or(0, |i| i + 1);
+        Ok(bytes[..len].to_vec())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+
+/// `FixedBytesEncoder` writes the given bytes followed by padding so that exactly `N` bytes are written.
+///
+/// If the given bytes are longer than `N`, an `ErrorKind::InvalidInput` error will be returned
+/// when `start_encoding` is called.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::{Encode, SizedEncode};
+/// use bytecodec::bytes::FixedBytesEncoder;
+/// use bytecodec::io::IoEncodeExt;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = FixedBytesEncoder::<_, 6>::new(b' ');
+/// encoder.start_encoding(&b"foo"[..]).unwrap();
+/// assert_eq!(encoder.exact_requiring_bytes(), 6);
+///
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, b"foo   ");
+/// assert!(encoder.start_encoding(&b"foobarbaz"[..]).is_err());
+/// ```
+#[derive(Debug)]
+pub struct FixedBytesEncoder<B, const N: usize> {
+    bytes: BytesEncoder<B>,
+    padding: u8,
+    remaining_padding: usize,
+}
+impl<B, const N: usize> FixedBytesEncoder<B, N> {
+    /// Makes a new `FixedBytesEncoder` instance that pads fields with `padding` bytes.
+    pub fn new(padding: u8) -> Self {
+        FixedBytesEncoder {
+            bytes: BytesEncoder::new(),
+            padding,
+            remaining_padding: 0,
+        }
+    }
+
+    /// Returns the padding byte.
+    pub fn padding(&self) -> u8 {
+        self.padding
+    }
+}
+impl<B, const N: usize> Default for FixedBytesEncoder<B, N> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+impl<B: AsRef<[u8]>, const N: usize> Encode for FixedBytesEncoder<B, N> {
+    type Item = B;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        bytecodec_try_encode!(self.bytes, offset, buf, eos);
+
+        let size = cmp::min(buf.len() - offset, self.remaining_padding);
+        for b in &mut buf[offset..][..size] {
+            *b = self.padding;
+        }
+        offset += size;
+        self.remaining_padding -= size;
+        if self.remaining_padding != 0 {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos;
+                          buf.len(), self.remaining_padding);
+        }
+        Ok(offset)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        let len = item.as_ref().len();
+        track_assert!(len <= N, ErrorKind::InvalidInput, "Too long field"; len, N);
+        track!(self.bytes.start_encoding(item))?;
+        self.remaining_padding = N - len;
+        Ok(())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        ByteCount::Finite(self.exact_requiring_bytes())
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle() && self.remaining_padding == 0
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.remaining_padding = 0;
+        track!(self.bytes.reset())
+    }
+}
+impl<B: AsRef<[u8]>, const N: usize> SizedEncode for FixedBytesEncoder<B, N> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.bytes.exact_requiring_bytes() + self.remaining_padding as u64
+    }
+}
+
+/// `FixedStrDecoder` decodes a text field of exactly `N` bytes and trims the trailing padding.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::bytes::{Charset, FixedStrDecoder};
+/// use bytecodec::io::IoDecodeExt;
+///
+/// let mut decoder = FixedStrDecoder::<6>::new(b' ');
+/// decoder.set_charset(Charset::Ascii);
+///
+/// let item = decoder.decode_exact(b"foo   ".as_ref()).unwrap();
+/// assert_eq!(item, "foo");
+/// ```
+#[derive(Debug, Default)]
+pub struct FixedStrDecoder<const N: usize> {
+    bytes: FixedBytesDecoder<N>,
+    charset: Charset,
+}
+impl<const N: usize> FixedStrDecoder<N> {
+    /// Makes a new `FixedStrDecoder` instance that trims trailing `padding` bytes.
+    pub fn new(padding: u8) -> Self {
+        FixedStrDecoder {
+            bytes: FixedBytesDecoder::new(padding),
+            charset: Charset::default(),
+        }
+    }
+
+    /// Returns the padding byte.
+    pub fn padding(&self) -> u8 {
+        self.bytes.padding()
+    }
+
+    /// Returns the character set that decoded strings must conform to.
+    pub fn charset(&self) -> Charset {
+        self.charset
+    }
+
+    /// Sets the character set that decoded strings must conform to.
+    pub fn set_charset(&mut self, charset: Charset) {
+        self.charset = charset;
+    }
+}
+impl<const N: usize> Decode for FixedStrDecoder<N> {
+    type Item = String;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.bytes.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let b = track!(self.bytes.finish_decoding())?;
+        let s = track!(String::from_utf8(b).map_err(|e| ErrorKind::InvalidInput.cause(e)))?;
+        track!(self.charset.validate(&s))?;
+        Ok(s)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.bytes.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.bytes.reset())
+    }
+}
+
+/// `FixedStrEncoder` writes the given string followed by padding so that exactly `N` bytes are written.
+///
+/// If the given string is longer than `N` bytes or does not conform to the character set,
+/// an `ErrorKind::InvalidInput` error will be returned when `start_encoding` is called.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::bytes::FixedStrEncoder;
+/// use bytecodec::io::IoEncodeExt;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = FixedStrEncoder::<_, 6>::new(0);
+/// encoder.start_encoding("foo").unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, b"foo\x00\x00\x00");
+/// ```
+#[derive(Debug)]
+pub struct FixedStrEncoder<S, const N: usize> {
+    bytes: FixedBytesEncoder<Utf8Bytes<S>, N>,
+    charset: Charset,
+}
+impl<S, const N: usize> FixedStrEncoder<S, N> {
+    /// Makes a new `FixedStrEncoder` instance that pads fields with `padding` bytes.
+    pub fn new(padding: u8) -> Self {
+        FixedStrEncoder {
+            bytes: FixedBytesEncoder::new(padding),
+            charset: Charset::default(),
+        }
+    }
+
+    /// Returns the padding byte.
+    pub fn padding(&self) -> u8 {
+        self.bytes.padding()
+    }
+
+    /// Returns the character set that encoded strings must conform to.
+    pub fn charset(&self) -> Charset {
+        self.charset
+    }
+
+    /// Sets the character set that encoded strings must conform to.
+    pub fn set_charset(&mut self, charset: Charset) {
+        self.charset = charset;
+    }
+}
+impl<S, const N: usize> Default for FixedStrEncoder<S, N> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+impl<S: AsRef<str>, const N: usize> Encode for FixedStrEncoder<S, N> {
+    type Item = S;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.bytes.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.charset.validate(item.as_ref()))?;
+        track!(self.bytes.start_encoding(Utf8Bytes(item)))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.bytes.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.bytes.reset())
+    }
+}
+impl<S: AsRef<str>, const N: usize> SizedEncode for FixedStrEncoder<S, N> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.bytes.exact_requiring_bytes()
+    }
 }
 
 #[cfg(test)]
@@ -494,4 +855,34 @@ mod test {
         assert!(encoder.is_idle());
         assert_eq!(buf, b"foo");
     }
+
+    #[test]
+    fn fixed_width_codecs_work() {
+        let mut output = Vec::new();
+        let mut encoder = FixedStrEncoder::<_, 4>::new(b' ');
+        encoder.set_charset(Charset::Ascii);
+        track_try_unwrap!(encoder.start_encoding("ab"));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        track_try_unwrap!(encoder.start_encoding("abcd"));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, b"ab  abcd");
+
+        let error = encoder.start_encoding("abcde").err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+        let error = encoder.start_encoding("\u{e9}").err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut decoder = FixedStrDecoder::<4>::new(b' ');
+        let mut input = &output[..];
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), "ab");
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), "abcd");
+
+        decoder.set_charset(Charset::Ascii);
+        let error = decoder.decode_exact("\u{e9}  ".as_bytes()).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut decoder = FixedBytesDecoder::<3>::default();
+        let item = track_try_unwrap!(decoder.decode_exact(&[0, 1, 0][..]));
+        assert_eq!(item, [0, 1]);
+    }
 }
@@ -0,0 +1,98 @@
+//! Clocks used by time-aware combinators (e.g., `RateLimited`).
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// This trait allows for obtaining the current time.
+pub trait Clock {
+    /// Returns the time elapsed since an arbitrary (but fixed) epoch of this clock.
+    ///
+    /// The returned value must be monotonically non-decreasing.
+    fn now(&self) -> Duration;
+}
+
+/// Clock based on `std::time::Instant`.
+///
+/// The epoch of this clock is the time when it was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    epoch: Instant,
+}
+impl SystemClock {
+    /// Makes a new `SystemClock` instance.
+    pub fn new() -> Self {
+        SystemClock {
+            epoch: Instant::now(),
+        }
+    }
+}
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.epoch.elapsed()
+    }
+}
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Clock that advances only when it is explicitly requested.
+///
+/// The clones of an instance share the same time.
+/// This is mainly intended to be used in tests.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::clock::{Clock, ManualClock};
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let cloned = clock.clone();
+/// assert_eq!(cloned.now(), Duration::from_secs(0));
+///
+/// clock.advance(Duration::from_millis(10));
+/// assert_eq!(cloned.now(), Duration::from_millis(10));
+/// ```
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+impl ManualClock {
+    /// Makes a new `ManualClock` instance whose current time is zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the clock by the given duration.
+    pub fn advance(&self, duration: Duration) {
+        let nanos = to_nanos(duration);
+        let _ = self
+            .nanos
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                Some(n.saturating_add(nanos))
+            });
+    }
+
+    /// Sets the current time of the clock.
+    pub fn set(&self, now: Duration) {
+        self.nanos.store(to_nanos(now), Ordering::SeqCst);
+    }
+}
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+impl fmt::Debug for ManualClock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ManualClock {{ now: {:?} }}", self.now())
+    }
+}
+
+fn to_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
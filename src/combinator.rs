@@ -2,13 +2,20 @@
 //!
 //! These are mainly created via the methods provided by `EncodeExt` or `DecodeExt` traits.
 use crate::bytes::BytesEncoder;
+use crate::clock::{Clock, SystemClock};
 use crate::marker::Never;
+use crate::metrics::Recorder;
 use crate::{ByteCount, Decode, Encode, EncodeExt, Eos, Error, ErrorKind, Result, SizedEncode};
 use std::cmp;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
 use std::fmt;
 use std::iter;
 use std::marker::PhantomData;
 use std::mem;
+use std::time::Duration;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
 
 /// Combinator for converting decoded items to other values.
 ///
@@ -69,6 +76,10 @@ where
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
 }
 
 /// Combinator for modifying encoding/decoding errors.
@@ -135,6 +146,10 @@ where
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
 }
 impl<C, E, F> Encode for MapErr<C, E, F>
 where
@@ -163,6 +178,10 @@ where
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
 }
 impl<C, E, F> SizedEncode for MapErr<C, E, F>
 where
@@ -210,19 +229,22 @@ where
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
         let mut offset = 0;
         if self.inner1.is_none() {
-            bytecodec_try_decode!(self.inner0, offset, buf, eos);
-            let item = track!(self.inner0.finish_decoding())?;
+            try_decode_with_path!(self.inner0, offset, buf, eos, "and_then.0");
+            let item = track!(self
+                .inner0
+                .finish_decoding()
+                .map_err(|e| e.with_path("and_then.0")))?;
             self.inner1 = Some((self.and_then)(item));
         }
 
         let inner1 = self.inner1.as_mut().expect("Never fails");
-        bytecodec_try_decode!(inner1, offset, buf, eos);
+        try_decode_with_path!(inner1, offset, buf, eos, "and_then.1");
         Ok(offset)
     }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
         let mut d = track_assert_some!(self.inner1.take(), ErrorKind::IncompleteDecoding);
-        track!(d.finish_decoding())
+        track!(d.finish_decoding().map_err(|e| e.with_path("and_then.1")))
     }
 
     fn requiring_bytes(&self) -> ByteCount {
@@ -234,7 +256,12 @@ where
     }
 
     fn is_idle(&self) -> bool {
-        self.inner1.as_ref().map_or(false, Decode::is_idle)
+        self.inner1.as_ref().is_some_and(Decode::is_idle)
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.inner1 = None;
+        track!(self.inner0.reset())
     }
 }
 
@@ -294,6 +321,10 @@ where
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
 }
 impl<E, T, F> SizedEncode for MapFrom<E, T, F>
 where
@@ -363,6 +394,10 @@ where
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
 }
 impl<C, T, E, F> SizedEncode for TryMapFrom<C, T, E, F>
 where
@@ -447,6 +482,11 @@ where
     fn is_idle(&self) -> bool {
         self.items.is_none()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.items = None;
+        track!(self.inner.reset())
+    }
 }
 impl<E: Default, I> Default for Repeat<E, I> {
     fn default() -> Self {
@@ -523,6 +563,10 @@ impl<D: Decode> Decode for Omittable<D> {
     fn is_idle(&self) -> bool {
         self.do_omit || self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
 }
 
 /// Combinator for representing an optional encoder.
@@ -569,6 +613,10 @@ impl<E: Encode> Encode for Optional<E> {
     fn is_idle(&self) -> bool {
         self.0.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
 }
 impl<E: SizedEncode> SizedEncode for Optional<E> {
     fn exact_requiring_bytes(&self) -> u64 {
@@ -586,6 +634,7 @@ impl<E: SizedEncode> SizedEncode for Optional<E> {
 pub struct Collect<D, T> {
     inner: D,
     items: T,
+    index: usize,
     eos: bool,
 }
 impl<D, T: Default> Collect<D, T> {
@@ -604,10 +653,15 @@ impl<D, T: Default> Collect<D, T> {
         self.inner
     }
 
+    fn path(&self) -> String {
+        format!("collect[{}]", self.index)
+    }
+
     pub(crate) fn new(inner: D) -> Self {
         Collect {
             inner,
             items: T::default(),
+            index: 0,
             eos: false,
         }
     }
@@ -626,10 +680,15 @@ where
 
         let mut offset = 0;
         while offset < buf.len() {
-            bytecodec_try_decode!(self.inner, offset, buf, eos);
+            let start = offset;
+            try_decode_with_path!(self.inner, offset, buf, eos, self.path());
 
-            let item = track!(self.inner.finish_decoding())?;
+            let item = track!(self
+                .inner
+                .finish_decoding()
+                .map_err(|e| e.with_offset(start as u64).with_path(self.path())))?;
             self.items.extend(iter::once(item));
+            self.index += 1;
         }
         if eos.is_reached() {
             self.eos = true;
@@ -640,6 +699,7 @@ where
     fn finish_decoding(&mut self) -> Result<Self::Item> {
         track_assert!(self.eos, ErrorKind::IncompleteDecoding);
         self.eos = false;
+        self.index = 0;
         let items = mem::take(&mut self.items);
         Ok(items)
     }
@@ -655,6 +715,13 @@ where
     fn is_idle(&self) -> bool {
         self.eos
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.items = T::default();
+        self.index = 0;
+        self.eos = false;
+        track!(self.inner.reset())
+    }
 }
 
 /// Combinator for consuming the specified number of bytes exactly.
@@ -729,7 +796,10 @@ impl<D: Decode> Decode for Length<D> {
             track_assert!(remaining >= required, ErrorKind::UnexpectedEos; remaining, required);
         }
 
-        let size = track!(self.inner.decode(&buf[..limit], expected_eos))?;
+        let size = track!(self
+            .inner
+            .decode(&buf[..limit], expected_eos)
+            .map_err(|e| e.with_path("length")))?;
         self.remaining_bytes -= size as u64;
         Ok(size)
     }
@@ -738,7 +808,10 @@ impl<D: Decode> Decode for Length<D> {
         track_assert_eq!(self.remaining_bytes, 0, ErrorKind::IncompleteDecoding);
         self.remaining_bytes = self.expected_bytes;
 
-        track!(self.inner.finish_decoding())
+        track!(self
+            .inner
+            .finish_decoding()
+            .map_err(|e| e.with_path("length")))
     }
 
     fn requiring_bytes(&self) -> ByteCount {
@@ -748,6 +821,11 @@ impl<D: Decode> Decode for Length<D> {
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.remaining_bytes = self.expected_bytes;
+        track!(self.inner.reset())
+    }
 }
 impl<E: Encode> Encode for Length<E> {
     type Item = E::Item;
@@ -792,6 +870,11 @@ impl<E: Encode> Encode for Length<E> {
     fn is_idle(&self) -> bool {
         self.remaining_bytes == 0
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.remaining_bytes = self.expected_bytes;
+        track!(self.inner.reset())
+    }
 }
 impl<E: Encode> SizedEncode for Length<E> {
     fn exact_requiring_bytes(&self) -> u64 {
@@ -799,193 +882,223 @@ impl<E: Encode> SizedEncode for Length<E> {
     }
 }
 
-/// Combinator for decoding the specified number of items and collecting the result.
+/// Combinator for padding (or skipping) bytes after each item
+/// so that the size of the item becomes a multiple of the specified alignment.
 ///
-/// This is created by calling `DecodeExt::collectn` method.
-#[derive(Debug, Default)]
-pub struct CollectN<D, T> {
-    inner: D,
-    remaining_items: usize,
-    items: T,
-}
-impl<D, T: Default> CollectN<D, T> {
-    /// Returns the number of remaining items expected to be decoded.
-    pub fn remaining_items(&self) -> usize {
-        self.remaining_items
+/// This is created by calling `DecodeExt::align` or `EncodeExt::align` method.
+#[derive(Debug)]
+pub struct Align<C> {
+    inner: C,
+    alignment: u64,
+    pad_byte: u8,
+    position: u64,
+    remaining_padding: Option<u64>,
+}
+impl<C> Align<C> {
+    pub(crate) fn new(inner: C, alignment: u64, pad_byte: u8) -> Self {
+        assert_ne!(alignment, 0);
+        Align {
+            inner,
+            alignment,
+            pad_byte,
+            position: 0,
+            remaining_padding: None,
+        }
     }
 
-    /// Sets the number of remaining items expected to be decoded.
-    pub fn set_remaining_items(&mut self, n: usize) {
-        self.remaining_items = n;
+    /// Returns the alignment of items.
+    pub fn alignment(&self) -> u64 {
+        self.alignment
     }
 
-    /// Returns a reference to the inner decoder.
-    pub fn inner_ref(&self) -> &D {
+    /// Returns the byte used for padding.
+    pub fn pad_byte(&self) -> u8 {
+        self.pad_byte
+    }
+
+    /// Returns a reference to the inner encoder or decoder.
+    pub fn inner_ref(&self) -> &C {
         &self.inner
     }
 
-    /// Returns a mutable reference to the inner decoder.
-    pub fn inner_mut(&mut self) -> &mut D {
+    /// Returns a mutable reference to the inner encoder or decoder.
+    pub fn inner_mut(&mut self) -> &mut C {
         &mut self.inner
     }
 
-    /// Takes ownership of this instance and returns the inner decoder.
-    pub fn into_inner(self) -> D {
+    /// Takes ownership of this instance and returns the inner encoder or decoder.
+    pub fn into_inner(self) -> C {
         self.inner
     }
 
-    pub(crate) fn new(inner: D, count: usize) -> Self {
-        CollectN {
-            inner,
-            remaining_items: count,
-            items: T::default(),
+    fn padding(&self, size: u64) -> u64 {
+        (self.alignment - size % self.alignment) % self.alignment
+    }
+
+    fn padding_requiring_bytes(&self, inner: ByteCount) -> ByteCount {
+        match (self.remaining_padding, inner) {
+            (Some(n), _) => ByteCount::Finite(n),
+            (None, ByteCount::Finite(n)) => ByteCount::Finite(n + self.padding(self.position + n)),
+            (None, n) => n,
         }
     }
 }
-impl<D, T> Decode for CollectN<D, T>
-where
-    D: Decode,
-    T: Default + Extend<D::Item>,
-{
-    type Item = T;
+impl<D: Decode> Decode for Align<D> {
+    type Item = D::Item;
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
         let mut offset = 0;
-        while self.remaining_items != 0 && offset < buf.len() {
-            bytecodec_try_decode!(self.inner, offset, buf, eos);
-
-            let item = track!(self.inner.finish_decoding())?;
-            self.items.extend(iter::once(item));
-            self.remaining_items -= 1;
+        if self.remaining_padding.is_none() {
+            if !self.inner.is_idle() {
+                offset = track!(self
+                    .inner
+                    .decode(buf, eos)
+                    .map_err(|e| e.with_path("align")))?;
+                self.position += offset as u64;
+                if !self.inner.is_idle() {
+                    return Ok(offset);
+                }
+            }
+            self.remaining_padding = Some(self.padding(self.position));
         }
-        if self.remaining_items != 0 {
-            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos);
+
+        let remaining = self.remaining_padding.expect("Never fails");
+        let size = cmp::min((buf.len() - offset) as u64, remaining);
+        self.remaining_padding = Some(remaining - size);
+        if remaining != size {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos; remaining, size);
         }
-        Ok(offset)
+        Ok(offset + size as usize)
     }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
-        track_assert_eq!(self.remaining_items, 0, ErrorKind::IncompleteDecoding);
-        let items = mem::take(&mut self.items);
-        Ok(items)
+        track_assert_eq!(
+            self.remaining_padding,
+            Some(0),
+            ErrorKind::IncompleteDecoding
+        );
+        self.position = 0;
+        self.remaining_padding = None;
+        track!(self
+            .inner
+            .finish_decoding()
+            .map_err(|e| e.with_path("align")))
     }
 
     fn requiring_bytes(&self) -> ByteCount {
-        if self.remaining_items == 0 {
-            ByteCount::Finite(0)
-        } else {
-            self.inner.requiring_bytes()
-        }
+        self.padding_requiring_bytes(self.inner.requiring_bytes())
     }
 
     fn is_idle(&self) -> bool {
-        self.remaining_items == 0
+        self.remaining_padding == Some(0)
     }
-}
 
-/// Combinator which tries to convert decoded values by calling the specified function.
-///
-/// This is created by calling `DecodeExt::try_map` method.
-#[derive(Debug)]
-pub struct TryMap<D, T, E, F> {
-    inner: D,
-    try_map: F,
-    _phantom: PhantomData<(T, E)>,
-}
-impl<D, T, E, F> TryMap<D, T, E, F> {
-    /// Returns a reference to the inner decoder.
-    pub fn inner_ref(&self) -> &D {
-        &self.inner
+    fn reset(&mut self) -> Result<()> {
+        self.position = 0;
+        self.remaining_padding = None;
+        track!(self.inner.reset())
     }
+}
+impl<E: Encode> Encode for Align<E> {
+    type Item = E::Item;
 
-    /// Returns a mutable reference to the inner decoder.
-    pub fn inner_mut(&mut self) -> &mut D {
-        &mut self.inner
-    }
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        if self.remaining_padding.is_none() {
+            offset = track!(self.inner.encode(buf, eos))?;
+            self.position += offset as u64;
+            if !self.inner.is_idle() {
+                return Ok(offset);
+            }
+            self.remaining_padding = Some(self.padding(self.position));
+        }
 
-    /// Takes ownership of this instance and returns the inner decoder.
-    pub fn into_inner(self) -> D {
-        self.inner
+        let remaining = self.remaining_padding.expect("Never fails");
+        let size = cmp::min((buf.len() - offset) as u64, remaining);
+        for b in &mut buf[offset..][..size as usize] {
+            *b = self.pad_byte;
+        }
+        self.remaining_padding = Some(remaining - size);
+        if remaining != size {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos; remaining, size);
+        }
+        Ok(offset + size as usize)
     }
 
-    pub(crate) fn new(inner: D, try_map: F) -> Self {
-        TryMap {
-            inner,
-            try_map,
-            _phantom: PhantomData,
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        track!(self.inner.start_encoding(item))?;
+        self.position = 0;
+        self.remaining_padding = None;
+        if self.inner.is_idle() {
+            self.remaining_padding = Some(0);
         }
+        Ok(())
     }
-}
-impl<D, T, E, F> Decode for TryMap<D, T, E, F>
-where
-    D: Decode,
-    F: Fn(D::Item) -> std::result::Result<T, E>,
-    Error: From<E>,
-{
-    type Item = T;
 
-    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
-        track!(self.inner.decode(buf, eos))
+    fn requiring_bytes(&self) -> ByteCount {
+        self.padding_requiring_bytes(self.inner.requiring_bytes())
     }
 
-    fn finish_decoding(&mut self) -> Result<Self::Item> {
-        let item = track!(self.inner.finish_decoding())?;
-        track!((self.try_map)(item).map_err(Error::from))
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle() && self.remaining_padding.unwrap_or(0) == 0
     }
 
-    fn requiring_bytes(&self) -> ByteCount {
-        self.inner.requiring_bytes()
+    fn reset(&mut self) -> Result<()> {
+        self.position = 0;
+        self.remaining_padding = None;
+        track!(self.inner.reset())
     }
-
-    fn is_idle(&self) -> bool {
-        self.inner.is_idle()
+}
+impl<E: SizedEncode> SizedEncode for Align<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        let n = ByteCount::Finite(self.inner.exact_requiring_bytes());
+        self.padding_requiring_bytes(n)
+            .to_u64()
+            .expect("Never fails")
     }
 }
 
-/// Combinator that will fail if the number of consumed bytes exceeds the specified size.
+/// Combinator for padding (or skipping) bytes after each item
+/// so that the size of the item becomes the specified number of bytes.
 ///
-/// This is created by calling `{DecodeExt, EncodeExt}::max_bytes` method.
+/// Unlike `Length`, the inner encoder or decoder may produce or consume fewer bytes than the specified size.
 ///
-/// Note that `MaxBytes` assumes the inner decoder will consume all the bytes in the target stream.
-#[derive(Debug, Default)]
-pub struct MaxBytes<C> {
+/// This is created by calling `DecodeExt::pad_to` or `EncodeExt::pad_to` method.
+#[derive(Debug)]
+pub struct PadTo<C> {
     inner: C,
-    consumed_bytes: u64,
-    max_bytes: u64,
-}
-impl<C> MaxBytes<C> {
-    pub(crate) fn new(inner: C, max_bytes: u64) -> Self {
-        MaxBytes {
+    total_size: u64,
+    pad_byte: u8,
+    position: u64,
+    remaining_padding: Option<u64>,
+}
+impl<C> PadTo<C> {
+    pub(crate) fn new(inner: C, total_size: u64) -> Self {
+        PadTo {
             inner,
-            consumed_bytes: 0,
-            max_bytes,
+            total_size,
+            pad_byte: 0,
+            position: 0,
+            remaining_padding: None,
         }
     }
 
-    /// Returns the number of bytes consumed for encoding/decoding the current item.
-    pub fn consumed_bytes(&self) -> u64 {
-        self.consumed_bytes
+    /// Returns the size of each item including padding.
+    pub fn total_size(&self) -> u64 {
+        self.total_size
     }
 
-    /// Returns the maximum number of bytes that can be consumed for encoding/decoding an item.
-    pub fn max_bytes(&self) -> u64 {
-        self.max_bytes
+    /// Returns the byte used for padding.
+    pub fn pad_byte(&self) -> u8 {
+        self.pad_byte
     }
 
-    /// Sets the maximum number of bytes that can be consumed for encoding/decoding an item.
+    /// Sets the byte used for padding.
     ///
-    /// # Error
-    ///
-    /// If `n` is smaller than `self.consumed_bytes()`, an `ErrorKind::InvalidInput` error will be returned.
-    pub fn set_max_bytes(&mut self, n: u64) -> Result<()> {
-        track_assert!(
-            self.consumed_bytes <= n,
-            ErrorKind::InvalidInput;
-            self.consumed_bytes,
-            n
-        );
-        self.max_bytes = n;
-        Ok(())
+    /// The default value is `0`.
+    pub fn set_pad_byte(&mut self, b: u8) {
+        self.pad_byte = b;
     }
 
     /// Returns a reference to the inner encoder or decoder.
@@ -1003,197 +1116,1686 @@ impl<C> MaxBytes<C> {
         self.inner
     }
 }
-impl<D: Decode> Decode for MaxBytes<D> {
+impl<D: Decode> Decode for PadTo<D> {
     type Item = D::Item;
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
-        match eos.remaining_bytes() {
-            ByteCount::Infinite => {
-                track_panic!(ErrorKind::InvalidInput, "Max bytes limit exceeded";
-                             self.consumed_bytes, self.max_bytes)
-            }
-            ByteCount::Unknown => {
-                let consumable_bytes = self.max_bytes - self.consumed_bytes;
-                track_assert!((buf.len() as u64) <= consumable_bytes,
-                              ErrorKind::InvalidInput, "Max bytes limit exceeded";
-                              buf.len(), self.consumed_bytes, self.max_bytes);
-            }
-            ByteCount::Finite(remaining_bytes) => {
-                let consumable_bytes = self.max_bytes - self.consumed_bytes;
-                track_assert!((buf.len() as u64) + remaining_bytes <= consumable_bytes,
-                              ErrorKind::InvalidInput, "Max bytes limit exceeded";
-                              buf.len(), remaining_bytes, self.consumed_bytes, self.max_bytes)
+        let mut offset = 0;
+        if self.remaining_padding.is_none() {
+            if !self.inner.is_idle() {
+                let remaining_bytes = self.total_size - self.position;
+                let limit = cmp::min(buf.len() as u64, remaining_bytes) as usize;
+                let required = remaining_bytes - limit as u64;
+                let expected_eos = Eos::with_remaining_bytes(ByteCount::Finite(required));
+                if let Some(mut remaining) = eos.remaining_bytes().to_u64() {
+                    remaining += buf.len() as u64;
+                    track_assert!(remaining >= required, ErrorKind::UnexpectedEos; remaining, required);
+                }
+
+                offset = track!(self
+                    .inner
+                    .decode(&buf[..limit], expected_eos)
+                    .map_err(|e| e.with_path("pad_to")))?;
+                self.position += offset as u64;
+                if !self.inner.is_idle() {
+                    return Ok(offset);
+                }
             }
+            self.remaining_padding = Some(self.total_size - self.position);
         }
 
-        let size = track!(self.inner.decode(buf, eos))?;
-        self.consumed_bytes += size as u64;
-        Ok(size)
+        let remaining = self.remaining_padding.expect("Never fails");
+        let size = cmp::min((buf.len() - offset) as u64, remaining);
+        self.remaining_padding = Some(remaining - size);
+        if remaining != size {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos; remaining, size);
+        }
+        Ok(offset + size as usize)
     }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
-        self.consumed_bytes = 0;
-        track!(self.inner.finish_decoding())
+        track_assert_eq!(
+            self.remaining_padding,
+            Some(0),
+            ErrorKind::IncompleteDecoding
+        );
+        self.position = 0;
+        self.remaining_padding = None;
+        track!(self
+            .inner
+            .finish_decoding()
+            .map_err(|e| e.with_path("pad_to")))
     }
 
     fn requiring_bytes(&self) -> ByteCount {
-        self.inner.requiring_bytes()
+        let n = self
+            .remaining_padding
+            .unwrap_or(self.total_size - self.position);
+        ByteCount::Finite(n)
     }
 
     fn is_idle(&self) -> bool {
-        self.inner.is_idle()
+        self.remaining_padding == Some(0)
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.position = 0;
+        self.remaining_padding = None;
+        track!(self.inner.reset())
     }
 }
-impl<E: Encode> Encode for MaxBytes<E> {
+impl<E: Encode> Encode for PadTo<E> {
     type Item = E::Item;
 
     fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
-        let size = track!(self.inner.encode(buf, eos))?;
-        self.consumed_bytes += size as u64;
-        track_assert!(self.consumed_bytes <= self.max_bytes,
-                      ErrorKind::InvalidInput, "Max bytes limit exceeded";
-                      self.consumed_bytes, self.max_bytes);
-        if self.is_idle() {
-            self.consumed_bytes = 0;
+        let mut offset = 0;
+        if self.remaining_padding.is_none() {
+            let remaining_bytes = self.total_size - self.position;
+            let limit = cmp::min(buf.len() as u64, remaining_bytes) as usize;
+            offset = track!(self.inner.encode(&mut buf[..limit], eos))?;
+            self.position += offset as u64;
+            if !self.inner.is_idle() {
+                track_assert!(self.position < self.total_size, ErrorKind::InvalidInput,
+                              "Too large item"; self.position, self.total_size);
+                return Ok(offset);
+            }
+            self.remaining_padding = Some(self.total_size - self.position);
         }
-        Ok(size)
+
+        let remaining = self.remaining_padding.expect("Never fails");
+        let size = cmp::min((buf.len() - offset) as u64, remaining);
+        for b in &mut buf[offset..][..size as usize] {
+            *b = self.pad_byte;
+        }
+        self.remaining_padding = Some(remaining - size);
+        if remaining != size {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos; remaining, size);
+        }
+        Ok(offset + size as usize)
     }
 
     fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
-        track!(self.inner.start_encoding(item))
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        track!(self.inner.start_encoding(item))?;
+        self.position = 0;
+        self.remaining_padding = None;
+        if self.inner.is_idle() {
+            self.remaining_padding = Some(self.total_size);
+        }
+        Ok(())
     }
 
     fn requiring_bytes(&self) -> ByteCount {
-        self.inner.requiring_bytes()
+        ByteCount::Finite(self.exact_requiring_bytes())
     }
 
     fn is_idle(&self) -> bool {
-        self.inner.is_idle()
+        self.inner.is_idle() && self.remaining_padding.unwrap_or(0) == 0
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.position = 0;
+        self.remaining_padding = None;
+        track!(self.inner.reset())
     }
 }
-impl<E: SizedEncode> SizedEncode for MaxBytes<E> {
+impl<E: Encode> SizedEncode for PadTo<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        match self.remaining_padding {
+            Some(n) => n,
+            None if self.inner.is_idle() => 0,
+            None => self.total_size - self.position,
+        }
+    }
+}
+
+/// Combinator for decoding the specified number of items and collecting the result.
+///
+/// This is created by calling `DecodeExt::collectn` method.
+#[derive(Debug, Default)]
+pub struct CollectN<D, T> {
+    inner: D,
+    expected_items: usize,
+    remaining_items: usize,
+    index: usize,
+    items: T,
+}
+impl<D, T: Default> CollectN<D, T> {
+    /// Returns the number of remaining items expected to be decoded.
+    pub fn remaining_items(&self) -> usize {
+        self.remaining_items
+    }
+
+    /// Sets the number of remaining items expected to be decoded.
+    ///
+    /// The value is also used as the number of remaining items after `reset` is called.
+    pub fn set_remaining_items(&mut self, n: usize) {
+        self.expected_items = n;
+        self.remaining_items = n;
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    pub(crate) fn new(inner: D, count: usize) -> Self {
+        CollectN {
+            inner,
+            expected_items: count,
+            remaining_items: count,
+            index: 0,
+            items: T::default(),
+        }
+    }
+
+    fn path(&self) -> String {
+        format!("collectn[{}]", self.index)
+    }
+}
+impl<D, T> Decode for CollectN<D, T>
+where
+    D: Decode,
+    T: Default + Extend<D::Item>,
+{
+    type Item = T;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while self.remaining_items != 0 && offset < buf.len() {
+            let start = offset;
+            try_decode_with_path!(self.inner, offset, buf, eos, self.path());
+
+            let item = track!(self
+                .inner
+                .finish_decoding()
+                .map_err(|e| e.with_offset(start as u64).with_path(self.path())))?;
+            self.items.extend(iter::once(item));
+            self.remaining_items -= 1;
+            self.index += 1;
+        }
+        if self.remaining_items != 0 {
+            track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos);
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert_eq!(self.remaining_items, 0, ErrorKind::IncompleteDecoding);
+        self.index = 0;
+        let items = mem::take(&mut self.items);
+        Ok(items)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.remaining_items == 0 {
+            ByteCount::Finite(0)
+        } else {
+            self.inner.requiring_bytes()
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.remaining_items == 0
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.items = T::default();
+        self.index = 0;
+        self.remaining_items = self.expected_items;
+        track!(self.inner.reset())
+    }
+}
+
+/// The default maximum number of items allowed in a sequence decoded by `CountPrefixed`.
+pub const DEFAULT_MAX_ITEMS: usize = 1024;
+
+/// Combinator for prefixing a sequence of items with the number of the items.
+///
+/// This is created by calling `DecodeExt::count_prefixed` or `EncodeExt::count_prefixed` method.
+#[derive(Debug)]
+pub struct CountPrefixed<C, N> {
+    inner: C,
+    count: N,
+    counted: bool,
+    max_items: usize,
+}
+impl<C, N> CountPrefixed<C, N> {
+    pub(crate) fn new(inner: C, count: N, max_items: usize) -> Self {
+        CountPrefixed {
+            inner,
+            count,
+            counted: false,
+            max_items,
+        }
+    }
+
+    /// Returns the maximum number of items allowed in a sequence.
+    pub fn max_items(&self) -> usize {
+        self.max_items
+    }
+
+    /// Sets the maximum number of items allowed in a sequence.
+    ///
+    /// If the number of items exceeds this value, an `ErrorKind::InvalidInput` error will be returned
+    /// before decoding (or encoding) any items.
+    ///
+    /// The default value is `DEFAULT_MAX_ITEMS` for decoders and `usize::MAX` (i.e., unlimited) for encoders.
+    pub fn set_max_items(&mut self, n: usize) {
+        self.max_items = n;
+    }
+
+    /// Returns a reference to the count encoder or decoder.
+    pub fn count_ref(&self) -> &N {
+        &self.count
+    }
+
+    /// Returns a mutable reference to the count encoder or decoder.
+    pub fn count_mut(&mut self) -> &mut N {
+        &mut self.count
+    }
+
+    /// Returns a reference to the inner encoder or decoder.
+    pub fn inner_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder or decoder.
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder or decoder.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+impl<D, T, N> Decode for CountPrefixed<CollectN<D, T>, N>
+where
+    D: Decode,
+    T: Default + Extend<D::Item>,
+    N: Decode,
+    N::Item: Into<u64>,
+{
+    type Item = T;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        if !self.counted {
+            try_decode_with_path!(self.count, offset, buf, eos, "count_prefixed.count");
+            let n = track!(self.count.finish_decoding())?.into();
+            track_assert!(n <= self.max_items as u64, ErrorKind::InvalidInput,
+                          "Too many items"; n, self.max_items);
+            self.inner.set_remaining_items(n as usize);
+            self.counted = true;
+        }
+        bytecodec_try_decode!(self.inner, offset, buf, eos);
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(self.counted, ErrorKind::IncompleteDecoding);
+        let items = track!(self.inner.finish_decoding())?;
+        self.counted = false;
+        Ok(items)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.counted {
+            self.inner.requiring_bytes()
+        } else {
+            self.count.requiring_bytes()
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.counted && self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.count.reset())?;
+        track!(self.inner.reset())?;
+        self.counted = false;
+        Ok(())
+    }
+}
+impl<E, I, N> Encode for CountPrefixed<Repeat<E, I>, N>
+where
+    E: Encode,
+    I: ExactSizeIterator<Item = E::Item>,
+    N: Encode,
+    N::Item: TryFrom<usize>,
+{
+    type Item = I;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        bytecodec_try_encode!(self.count, offset, buf, eos);
+        offset += track!(self.inner.encode(&mut buf[offset..], eos))?;
+        Ok(offset)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        let n = item.len();
+        track_assert!(n <= self.max_items, ErrorKind::InvalidInput,
+                      "Too many items"; n, self.max_items);
+        let count = track_assert_some!(N::Item::try_from(n).ok(), ErrorKind::InvalidInput,
+                                       "Too many items"; n);
+        track!(self.count.start_encoding(count))?;
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.is_idle() {
+            ByteCount::Finite(0)
+        } else {
+            ByteCount::Unknown
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.count.is_idle() && self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.count.reset())?;
+        track!(self.inner.reset())
+    }
+}
+
+/// Combinator which tries to convert decoded values by calling the specified function.
+///
+/// This is created by calling `DecodeExt::try_map` method.
+#[derive(Debug)]
+pub struct TryMap<D, T, E, F> {
+    inner: D,
+    try_map: F,
+    _phantom: PhantomData<(T, E)>,
+}
+impl<D, T, E, F> TryMap<D, T, E, F> {
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    pub(crate) fn new(inner: D, try_map: F) -> Self {
+        TryMap {
+            inner,
+            try_map,
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<D, T, E, F> Decode for TryMap<D, T, E, F>
+where
+    D: Decode,
+    F: Fn(D::Item) -> std::result::Result<T, E>,
+    Error: From<E>,
+{
+    type Item = T;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track!(self.inner.finish_decoding())?;
+        track!((self.try_map)(item).map_err(Error::from))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+
+/// Combinator that will fail if the number of consumed bytes exceeds the specified size.
+///
+/// This is created by calling `{DecodeExt, EncodeExt}::max_bytes` method.
+///
+/// Note that `MaxBytes` assumes the inner decoder will consume all the bytes in the target stream.
+#[derive(Debug, Default)]
+pub struct MaxBytes<C> {
+    inner: C,
+    consumed_bytes: u64,
+    max_bytes: u64,
+}
+impl<C> MaxBytes<C> {
+    pub(crate) fn new(inner: C, max_bytes: u64) -> Self {
+        MaxBytes {
+            inner,
+            consumed_bytes: 0,
+            max_bytes,
+        }
+    }
+
+    /// Returns the number of bytes consumed for encoding/decoding the current item.
+    pub fn consumed_bytes(&self) -> u64 {
+        self.consumed_bytes
+    }
+
+    /// Returns the maximum number of bytes that can be consumed for encoding/decoding an item.
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Sets the maximum number of bytes that can be consumed for encoding/decoding an item.
+    ///
+    /// # Error
+    ///
+    /// If `n` is smaller than `self.consumed_bytes()`, an `ErrorKind::InvalidInput` error will be returned.
+    pub fn set_max_bytes(&mut self, n: u64) -> Result<()> {
+        track_assert!(
+            self.consumed_bytes <= n,
+            ErrorKind::InvalidInput;
+            self.consumed_bytes,
+            n
+        );
+        self.max_bytes = n;
+        Ok(())
+    }
+
+    /// Returns a reference to the inner encoder or decoder.
+    pub fn inner_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder or decoder.
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder or decoder.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+impl<D: Decode> Decode for MaxBytes<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        match eos.remaining_bytes() {
+            ByteCount::Infinite => {
+                track_panic!(ErrorKind::InvalidInput, "Max bytes limit exceeded";
+                             self.consumed_bytes, self.max_bytes)
+            }
+            ByteCount::Unknown => {
+                let consumable_bytes = self.max_bytes - self.consumed_bytes;
+                track_assert!((buf.len() as u64) <= consumable_bytes,
+                              ErrorKind::InvalidInput, "Max bytes limit exceeded";
+                              buf.len(), self.consumed_bytes, self.max_bytes);
+            }
+            ByteCount::Finite(remaining_bytes) => {
+                let consumable_bytes = self.max_bytes - self.consumed_bytes;
+                track_assert!((buf.len() as u64) + remaining_bytes <= consumable_bytes,
+                              ErrorKind::InvalidInput, "Max bytes limit exceeded";
+                              buf.len(), remaining_bytes, self.consumed_bytes, self.max_bytes)
+            }
+        }
+
+        let size = track!(self.inner.decode(buf, eos))?;
+        self.consumed_bytes += size as u64;
+        Ok(size)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        self.consumed_bytes = 0;
+        track!(self.inner.finish_decoding())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.consumed_bytes = 0;
+        track!(self.inner.reset())
+    }
+}
+impl<E: Encode> Encode for MaxBytes<E> {
+    type Item = E::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let size = track!(self.inner.encode(buf, eos))?;
+        self.consumed_bytes += size as u64;
+        track_assert!(self.consumed_bytes <= self.max_bytes,
+                      ErrorKind::InvalidInput, "Max bytes limit exceeded";
+                      self.consumed_bytes, self.max_bytes);
+        if self.is_idle() {
+            self.consumed_bytes = 0;
+        }
+        Ok(size)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.consumed_bytes = 0;
+        track!(self.inner.reset())
+    }
+}
+impl<E: SizedEncode> SizedEncode for MaxBytes<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+
+/// Combinator for pre-encoding items when `start_encoding` method is called.
+///
+/// This is created by calling `EncodeExt::pre_encode` method.
+#[derive(Debug, Default)]
+pub struct PreEncode<E> {
+    inner: E,
+    pre_encoded: BytesEncoder<Vec<u8>>,
+}
+impl<E> PreEncode<E> {
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    pub(crate) fn new(inner: E) -> Self {
+        PreEncode {
+            inner,
+            pre_encoded: BytesEncoder::new(),
+        }
+    }
+}
+impl<E: Encode> Encode for PreEncode<E> {
+    type Item = E::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.pre_encoded.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let buf = track!(self.inner.encode_into_bytes(item))?;
+        track!(self.pre_encoded.start_encoding(buf))?;
+        Ok(())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.pre_encoded.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.pre_encoded.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())?;
+        track!(self.pre_encoded.reset())
+    }
+}
+impl<E: Encode> SizedEncode for PreEncode<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.pre_encoded.exact_requiring_bytes()
+    }
+}
+
+/// Combinator for slicing an input/output byte sequence by the specified number of bytes.
+///
+/// This is created by calling `{DecodeExt, EncodeExt}::slice`.
+#[derive(Debug, Default)]
+pub struct Slice<T> {
+    inner: T,
+    consumable_bytes: u64,
+}
+impl<T> Slice<T> {
+    pub(crate) fn new(inner: T) -> Self {
+        Slice {
+            inner,
+            consumable_bytes: 0,
+        }
+    }
+
+    /// Returns the number of remaining bytes consumable in this slice.
+    ///
+    /// The inner decoder or encoder will be suspended if the consumable bytes reaches to `0`.
+    pub fn consumable_bytes(&self) -> u64 {
+        self.consumable_bytes
+    }
+
+    /// Set the number of remaining bytes consumable in this slice.
+    pub fn set_consumable_bytes(&mut self, n: u64) {
+        self.consumable_bytes = n;
+    }
+
+    /// Returns `true` if the encoder or decoder cannot consume any more bytes, otherwise `false`.
+    ///
+    /// To resume its works, it is needed to reset the value of consumable bytes
+    /// by calling `set_consumable_bytes` method.
+    pub fn is_suspended(&self) -> bool {
+        self.consumable_bytes == 0
+    }
+
+    /// Returns a reference to the inner encoder or decoder.
+    pub fn inner_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder or decoder.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder or decoder.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+impl<D: Decode> Decode for Slice<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let limit = cmp::min(buf.len() as u64, self.consumable_bytes) as usize;
+        let eos = eos.back((buf.len() - limit) as u64);
+        let size = track!(self.inner.decode(&buf[..limit], eos))?;
+        self.consumable_bytes -= size as u64;
+        Ok(size)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track!(self.inner.finish_decoding())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.consumable_bytes = 0;
+        track!(self.inner.reset())
+    }
+}
+impl<E: Encode> Encode for Slice<E> {
+    type Item = E::Item;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let limit = cmp::min(buf.len() as u64, self.consumable_bytes) as usize;
+        let eos = eos.back((buf.len() - limit) as u64);
+        let size = track!(self.inner.encode(&mut buf[..limit], eos))?;
+        self.consumable_bytes -= size as u64;
+        Ok(size)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.inner.start_encoding(item))
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.consumable_bytes = 0;
+        track!(self.inner.reset())
+    }
+}
+impl<E: SizedEncode> SizedEncode for Slice<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+
+/// Combinator for representing encoders that accepts only one additional item.
+///
+/// This is created by calling `EncodeExt::last`.
+#[derive(Debug, Default)]
+pub struct Last<E: Encode> {
+    inner: E,
+    item: Option<E::Item>,
+}
+impl<E: Encode> Last<E> {
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    pub(crate) fn new(inner: E, item: E::Item) -> Self {
+        Last {
+            inner,
+            item: Some(item),
+        }
+    }
+}
+impl<E: Encode> Encode for Last<E> {
+    type Item = Never;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        if self.inner.is_idle() {
+            if let Some(item) = self.item.take() {
+                track!(self.inner.start_encoding(item))?;
+            }
+        }
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, _item: Self::Item) -> Result<()> {
+        unreachable!()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.item.is_none() && self.inner.is_idle()
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.item.is_some() {
+            ByteCount::Unknown
+        } else {
+            self.inner.requiring_bytes()
+        }
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.item = None;
+        track!(self.inner.reset())
+    }
+}
+impl<E: SizedEncode> SizedEncode for Last<E> {
     fn exact_requiring_bytes(&self) -> u64 {
         self.inner.exact_requiring_bytes()
     }
 }
 
-/// Combinator for pre-encoding items when `start_encoding` method is called.
+/// Combinator that enables to peek decoded items before calling `finish_decoding` method.
+///
+/// This is created by calling `DecodeExt::peekable` method.
+pub struct Peekable<D: Decode> {
+    inner: D,
+    item: Option<D::Item>,
+}
+impl<D: Decode> Peekable<D> {
+    /// Returns a reference to the last item decoded (but not `finish_decoding` called yet) by the decoder.
+    pub fn peek(&self) -> Option<&D::Item> {
+        self.item.as_ref()
+    }
+
+    /// Returns a mutable reference to the last item decoded (but not `finish_decoding` called yet) by the decoder.
+    pub fn peek_mut(&mut self) -> Option<&mut D::Item> {
+        self.item.as_mut()
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    pub(crate) fn new(inner: D) -> Self {
+        Peekable { inner, item: None }
+    }
+}
+impl<D: Decode + fmt::Debug> fmt::Debug for Peekable<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Peekable {{ inner: {:?}, item.is_some(): {:?} }}",
+            self.inner,
+            self.item.is_some()
+        )
+    }
+}
+impl<D: Decode + Default> Default for Peekable<D> {
+    fn default() -> Self {
+        Peekable {
+            inner: D::default(),
+            item: None,
+        }
+    }
+}
+impl<D: Decode> Decode for Peekable<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        if self.item.is_none() {
+            let size = track!(self.inner.decode(buf, eos))?;
+            if self.inner.is_idle() {
+                self.item = Some(track!(self.inner.finish_decoding())?);
+            }
+            Ok(size)
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track_assert_some!(self.item.take(), ErrorKind::IncompleteDecoding);
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.item.is_some() {
+            ByteCount::Finite(0)
+        } else {
+            self.inner.requiring_bytes()
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.item.is_some()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.item = None;
+        track!(self.inner.reset())
+    }
+}
+
+/// Combinator for ignoring EOS if there is no item being decoded.
+///
+/// This is created by calling `DecodeExt::maybe_eos`.
+#[derive(Debug, Default)]
+pub struct MaybeEos<D> {
+    inner: D,
+    started: bool,
+}
+impl<D> MaybeEos<D> {
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    pub(crate) fn new(inner: D) -> Self {
+        MaybeEos {
+            inner,
+            started: false,
+        }
+    }
+}
+impl<D: Decode> Decode for MaybeEos<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], mut eos: Eos) -> Result<usize> {
+        if !self.started && buf.is_empty() && eos.is_reached() {
+            eos = Eos::new(false);
+        }
+
+        let size = track!(self.inner.decode(buf, eos))?;
+        if size != 0 {
+            self.started = true;
+        }
+        Ok(size)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        self.started = false;
+        track!(self.inner.finish_decoding())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.started = false;
+        track!(self.inner.reset())
+    }
+}
+
+/// Combinator for resynchronizing the input stream after the inner decoder detected an invalid input.
+///
+/// If the inner decoder returns an `ErrorKind::InvalidInput` error,
+/// this decoder discards the subsequent bytes until the sync pattern is found,
+/// resets the inner decoder (see `Decode::reset`), and resumes decoding.
+/// The sync pattern itself is fed to the new inner decoder (i.e., it is regarded as the head of an item).
+///
+/// Note that the decoded items are finished eagerly to detect errors reported by `finish_decoding`.
+///
+/// This is created by calling `DecodeExt::resync` method.
+pub struct Resync<D: Decode> {
+    inner: D,
+    pattern: Vec<u8>,
+    window: VecDeque<u8>,
+    scanning: bool,
+    replayed_bytes: usize,
+    skipped_bytes: u64,
+    resync_count: u64,
+    item: Option<D::Item>,
+}
+impl<D: Decode + fmt::Debug> fmt::Debug for Resync<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Resync {{ inner: {:?}, pattern: {:?}, scanning: {:?}, skipped_bytes: {:?}, \
+             resync_count: {:?}, item.is_some(): {:?} }}",
+            self.inner,
+            self.pattern,
+            self.scanning,
+            self.skipped_bytes,
+            self.resync_count,
+            self.item.is_some()
+        )
+    }
+}
+impl<D: Decode> Resync<D> {
+    pub(crate) fn new(inner: D, pattern: Vec<u8>) -> Self {
+        let replayed_bytes = pattern.len();
+        Resync {
+            inner,
+            pattern,
+            window: VecDeque::new(),
+            scanning: false,
+            replayed_bytes,
+            skipped_bytes: 0,
+            resync_count: 0,
+            item: None,
+        }
+    }
+
+    /// Returns the sync pattern.
+    pub fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    /// Returns the total number of bytes discarded to search the sync pattern.
+    pub fn skipped_bytes(&self) -> u64 {
+        self.skipped_bytes
+    }
+
+    /// Returns the number of times the decoder has started resynchronization.
+    pub fn resync_count(&self) -> u64 {
+        self.resync_count
+    }
+
+    /// Returns `true` if the decoder is searching the sync pattern, otherwise `false`.
+    pub fn is_scanning(&self) -> bool {
+        self.scanning
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    fn start_scanning(&mut self) {
+        self.scanning = true;
+        self.window.clear();
+        self.resync_count += 1;
+    }
+
+    fn scan(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut offset = 0;
+        while !self.window.iter().eq(self.pattern.iter()) {
+            if offset == buf.len() {
+                return Ok(offset);
+            }
+            self.window.push_back(buf[offset]);
+            offset += 1;
+            if self.window.len() > self.pattern.len() {
+                self.window.pop_front();
+                self.skipped_bytes += 1;
+            }
+        }
+        track!(self.inner.reset())?;
+        self.scanning = false;
+        self.window.clear();
+        self.replayed_bytes = 0;
+        Ok(offset)
+    }
+
+    fn decode_inner(&mut self, buf: &[u8], eos: Eos) -> Result<(usize, bool)> {
+        let (size, progressed) = if self.replayed_bytes < self.pattern.len() {
+            let pattern = &self.pattern[self.replayed_bytes..];
+            let size = track!(self.inner.decode(pattern, Eos::new(false)))?;
+            self.replayed_bytes += size;
+            (0, size != 0)
+        } else {
+            let size = track!(self.inner.decode(buf, eos))?;
+            (size, size != 0)
+        };
+        if self.inner.is_idle() {
+            self.item = Some(track!(self.inner.finish_decoding())?);
+        }
+        Ok((size, progressed))
+    }
+}
+impl<D: Decode> Decode for Resync<D> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while self.item.is_none() {
+            if self.scanning {
+                offset += track!(self.scan(&buf[offset..]))?;
+                if self.scanning {
+                    track_assert!(!eos.is_reached(), ErrorKind::UnexpectedEos;
+                                  self.skipped_bytes);
+                    break;
+                }
+            }
+
+            match self.decode_inner(&buf[offset..], eos) {
+                Err(e) => {
+                    if *e.kind() != ErrorKind::InvalidInput {
+                        return Err(track!(e));
+                    }
+                    self.start_scanning();
+                    if offset < buf.len() {
+                        // Skips at least one byte to avoid detecting the same sync pattern again
+                        offset += 1;
+                        self.skipped_bytes += 1;
+                    }
+                }
+                Ok((size, progressed)) => {
+                    offset += size;
+                    if !progressed && self.item.is_none() {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track_assert_some!(self.item.take(), ErrorKind::IncompleteDecoding);
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.item.is_some() {
+            ByteCount::Finite(0)
+        } else if self.scanning || self.replayed_bytes < self.pattern.len() {
+            ByteCount::Unknown
+        } else {
+            self.inner.requiring_bytes()
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.item.is_some()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())?;
+        self.window.clear();
+        self.scanning = false;
+        self.replayed_bytes = self.pattern.len();
+        self.item = None;
+        Ok(())
+    }
+}
+
+/// A value of one of two types.
+///
+/// This is used as the item type of `Or`.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+/// The default value of the lookahead limit of `Or`.
+pub const DEFAULT_LOOKAHEAD_LIMIT: usize = 4096;
+
+/// Combinator for decoding items by using the second decoder if the first one fails.
+///
+/// The bytes consumed by the first decoder are buffered until it decodes an item.
+/// If the first decoder returns an `ErrorKind::InvalidInput` error,
+/// the buffered bytes are replayed into the second decoder (i.e., the input is backtracked).
+/// After that, the first decoder is reset by calling `Decode::reset` for the next item.
+///
+/// If the first decoder has consumed more bytes than the lookahead limit,
+/// the buffer is discarded and the decoder is committed to the first alternative
+/// (i.e., the subsequent errors of the first decoder are returned as they are).
+///
+/// Note that the decoded items are finished eagerly to detect errors reported by `finish_decoding`.
+///
+/// This is created by calling `DecodeExt::or` method.
+#[derive(Debug)]
+pub struct Or<D0: Decode, D1: Decode> {
+    first: D0,
+    second: D1,
+    lookahead_limit: usize,
+    history: Vec<u8>,
+    pending: Vec<u8>,
+    pending_offset: usize,
+    committed: bool,
+    fallback: bool,
+    item: Option<Either<D0::Item, D1::Item>>,
+}
+impl<D0: Decode, D1: Decode> Or<D0, D1> {
+    pub(crate) fn new(first: D0, second: D1) -> Self {
+        Or {
+            first,
+            second,
+            lookahead_limit: DEFAULT_LOOKAHEAD_LIMIT,
+            history: Vec::new(),
+            pending: Vec::new(),
+            pending_offset: 0,
+            committed: false,
+            fallback: false,
+            item: None,
+        }
+    }
+
+    /// Returns the maximum number of bytes that can be backtracked.
+    ///
+    /// The default value is `DEFAULT_LOOKAHEAD_LIMIT`.
+    pub fn lookahead_limit(&self) -> usize {
+        self.lookahead_limit
+    }
+
+    /// Sets the maximum number of bytes that can be backtracked.
+    pub fn set_lookahead_limit(&mut self, limit: usize) {
+        self.lookahead_limit = limit;
+    }
+
+    /// Returns a reference to the first decoder.
+    pub fn first_ref(&self) -> &D0 {
+        &self.first
+    }
+
+    /// Returns a mutable reference to the first decoder.
+    pub fn first_mut(&mut self) -> &mut D0 {
+        &mut self.first
+    }
+
+    /// Returns a reference to the second decoder.
+    pub fn second_ref(&self) -> &D1 {
+        &self.second
+    }
+
+    /// Returns a mutable reference to the second decoder.
+    pub fn second_mut(&mut self) -> &mut D1 {
+        &mut self.second
+    }
+
+    /// Takes ownership of this instance and returns the first and the second decoders.
+    pub fn into_inner(self) -> (D0, D1) {
+        (self.first, self.second)
+    }
+
+    // Returns the number of consumed bytes and whether the decoder has fallen back to the second one.
+    fn feed(&mut self, buf: &[u8], eos: Eos) -> Result<(usize, bool)> {
+        if self.fallback {
+            let size = track!(self.second.decode(buf, eos))?;
+            if self.second.is_idle() {
+                let item = track!(self.second.finish_decoding())?;
+                self.item = Some(Either::Right(item));
+                self.fallback = false;
+            }
+            return Ok((size, false));
+        }
+
+        let size = match self.first.decode(buf, eos) {
+            Err(e) => return track!(self.fall_back(e)).map(|()| (0, true)),
+            Ok(size) => size,
+        };
+        if !self.committed {
+            if self.history.len() + size <= self.lookahead_limit {
+                self.history.extend_from_slice(&buf[..size]);
+            } else {
+                self.history.clear();
+                self.committed = true;
+            }
+        }
+        if self.first.is_idle() {
+            match self.first.finish_decoding() {
+                Err(e) => return track!(self.fall_back(e)).map(|()| (size, true)),
+                Ok(item) => {
+                    self.item = Some(Either::Left(item));
+                    self.history.clear();
+                    self.committed = false;
+                }
+            }
+        }
+        Ok((size, false))
+    }
+
+    fn fall_back(&mut self, e: Error) -> Result<()> {
+        if self.committed || *e.kind() != ErrorKind::InvalidInput {
+            return Err(track!(e));
+        }
+        track!(self.first.reset())?;
+        self.fallback = true;
+        Ok(())
+    }
+
+    fn replay_history(&mut self) {
+        let mut pending = mem::take(&mut self.history);
+        pending.extend_from_slice(&self.pending[self.pending_offset..]);
+        self.pending = pending;
+        self.pending_offset = 0;
+    }
+}
+impl<D0: Decode, D1: Decode> Decode for Or<D0, D1> {
+    type Item = Either<D0::Item, D1::Item>;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while self.item.is_none() {
+            if self.pending_offset < self.pending.len() {
+                let pending = mem::take(&mut self.pending);
+                let result = self.feed(
+                    &pending[self.pending_offset..],
+                    eos.back((buf.len() - offset) as u64),
+                );
+                self.pending = pending;
+                let (size, fell_back) = track!(result)?;
+                self.pending_offset += size;
+                if fell_back {
+                    self.replay_history();
+                } else if self.pending_offset == self.pending.len() {
+                    self.pending.clear();
+                    self.pending_offset = 0;
+                } else if self.item.is_none() {
+                    break;
+                }
+            } else {
+                let (size, fell_back) = track!(self
+                    .feed(&buf[offset..], eos)
+                    .map_err(|e| e.with_offset(offset as u64)))?;
+                offset += size;
+                if fell_back {
+                    self.replay_history();
+                } else {
+                    break;
+                }
+            }
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track_assert_some!(self.item.take(), ErrorKind::IncompleteDecoding);
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.item.is_some() {
+            ByteCount::Finite(0)
+        } else if self.pending_offset < self.pending.len() {
+            ByteCount::Unknown
+        } else if self.fallback {
+            self.second.requiring_bytes()
+        } else if self.committed {
+            self.first.requiring_bytes()
+        } else {
+            ByteCount::Unknown
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.item.is_some()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.first.reset())?;
+        track!(self.second.reset())?;
+        self.history.clear();
+        self.pending.clear();
+        self.pending_offset = 0;
+        self.committed = false;
+        self.fallback = false;
+        self.item = None;
+        Ok(())
+    }
+}
+
+/// Combinator for capturing the raw bytes of each item.
 ///
-/// This is created by calling `EncodeExt::pre_encode` method.
-#[derive(Debug, Default)]
-pub struct PreEncode<E> {
-    inner: E,
-    pre_encoded: BytesEncoder<Vec<u8>>,
+/// When encoding, an item that produces no bytes (i.e., the inner encoder is idle right after
+/// `start_encoding`) is reported to the callback with an empty slice in `start_encoding`.
+///
+/// This is created by calling `DecodeExt::inspect_bytes` or `EncodeExt::inspect_bytes` method.
+#[derive(Debug)]
+pub struct InspectBytes<C, F> {
+    inner: C,
+    inspect: F,
+    bytes: Vec<u8>,
+    in_progress: bool,
 }
-impl<E> PreEncode<E> {
-    /// Returns a reference to the inner encoder.
-    pub fn inner_ref(&self) -> &E {
+impl<C, F> InspectBytes<C, F> {
+    pub(crate) fn new(inner: C, inspect: F) -> Self {
+        InspectBytes {
+            inner,
+            inspect,
+            bytes: Vec::new(),
+            in_progress: false,
+        }
+    }
+
+    /// Returns the bytes of the item currently being decoded or encoded.
+    ///
+    /// If the decoding or encoding process has failed,
+    /// this contains the bytes processed successfully until the failure.
+    pub fn captured_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns a reference to the inner encoder or decoder.
+    pub fn inner_ref(&self) -> &C {
         &self.inner
     }
 
-    /// Returns a mutable reference to the inner encoder.
-    pub fn inner_mut(&mut self) -> &mut E {
+    /// Returns a mutable reference to the inner encoder or decoder.
+    pub fn inner_mut(&mut self) -> &mut C {
         &mut self.inner
     }
 
-    /// Takes ownership of this instance and returns the inner encoder.
-    pub fn into_inner(self) -> E {
+    /// Takes ownership of this instance and returns the inner encoder or decoder.
+    pub fn into_inner(self) -> C {
         self.inner
     }
+}
+impl<D, F> Decode for InspectBytes<D, F>
+where
+    D: Decode,
+    F: FnMut(&D::Item, &[u8]),
+{
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let size = track!(self.inner.decode(buf, eos))?;
+        self.bytes.extend_from_slice(&buf[..size]);
+        Ok(size)
+    }
 
-    pub(crate) fn new(inner: E) -> Self {
-        PreEncode {
-            inner,
-            pre_encoded: BytesEncoder::new(),
-        }
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track!(self.inner.finish_decoding())?;
+        (self.inspect)(&item, &self.bytes);
+        self.bytes.clear();
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.bytes.clear();
+        track!(self.inner.reset())
     }
 }
-impl<E: Encode> Encode for PreEncode<E> {
+impl<E, F> Encode for InspectBytes<E, F>
+where
+    E: Encode,
+    F: FnMut(&[u8]),
+{
     type Item = E::Item;
 
     fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
-        track!(self.pre_encoded.encode(buf, eos))
+        let size = track!(self.inner.encode(buf, eos))?;
+        self.bytes.extend_from_slice(&buf[..size]);
+        if self.in_progress && self.inner.is_idle() {
+            (self.inspect)(&self.bytes);
+            self.bytes.clear();
+            self.in_progress = false;
+        }
+        Ok(size)
     }
 
     fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
-        let buf = track!(self.inner.encode_into_bytes(item))?;
-        track!(self.pre_encoded.start_encoding(buf))?;
+        track!(self.inner.start_encoding(item))?;
+        self.bytes.clear();
+        if self.inner.is_idle() {
+            (self.inspect)(&[]);
+        } else {
+            self.in_progress = true;
+        }
         Ok(())
     }
 
     fn requiring_bytes(&self) -> ByteCount {
-        self.pre_encoded.requiring_bytes()
+        self.inner.requiring_bytes()
     }
 
     fn is_idle(&self) -> bool {
-        self.pre_encoded.is_idle()
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.bytes.clear();
+        self.in_progress = false;
+        track!(self.inner.reset())
     }
 }
-impl<E: Encode> SizedEncode for PreEncode<E> {
+impl<E, F> SizedEncode for InspectBytes<E, F>
+where
+    E: SizedEncode,
+    F: FnMut(&[u8]),
+{
     fn exact_requiring_bytes(&self) -> u64 {
-        self.pre_encoded.exact_requiring_bytes()
+        self.inner.exact_requiring_bytes()
     }
 }
 
-/// Combinator for slicing an input/output byte sequence by the specified number of bytes.
+/// Combinator for limiting the throughput of the inner encoder or decoder by using a token bucket.
 ///
-/// This is created by calling `{DecodeExt, EncodeExt}::slice`.
-#[derive(Debug, Default)]
-pub struct Slice<T> {
-    inner: T,
-    consumable_bytes: u64,
-}
-impl<T> Slice<T> {
-    pub(crate) fn new(inner: T) -> Self {
-        Slice {
+/// The bucket is refilled at the rate of `bytes_per_second` and
+/// can hold at most `burst_size` bytes (initially, the bucket is full).
+/// The inner encoder or decoder will be suspended if the bucket is empty.
+///
+/// This is created by calling `DecodeExt::rate_limited` or `EncodeExt::rate_limited` method.
+#[derive(Debug)]
+pub struct RateLimited<C, K> {
+    inner: C,
+    clock: K,
+    bytes_per_second: u64,
+    burst_size: u64,
+    nano_tokens: u128,
+    last_refill: Duration,
+}
+impl<C, K: Clock> RateLimited<C, K> {
+    pub(crate) fn new(inner: C, bytes_per_second: u64, clock: K) -> Self {
+        let last_refill = clock.now();
+        RateLimited {
             inner,
-            consumable_bytes: 0,
+            clock,
+            bytes_per_second,
+            burst_size: bytes_per_second,
+            nano_tokens: u128::from(bytes_per_second) * NANOS_PER_SEC,
+            last_refill,
         }
     }
 
-    /// Returns the number of remaining bytes consumable in this slice.
+    /// Returns the number of bytes refilled per second.
+    pub fn bytes_per_second(&self) -> u64 {
+        self.bytes_per_second
+    }
+
+    /// Sets the number of bytes refilled per second.
+    pub fn set_bytes_per_second(&mut self, n: u64) {
+        self.refill();
+        self.bytes_per_second = n;
+    }
+
+    /// Returns the maximum number of bytes that can be consumed in a burst.
     ///
-    /// The inner decoder or encoder will be suspended if the consumable bytes reaches to `0`.
-    pub fn consumable_bytes(&self) -> u64 {
-        self.consumable_bytes
+    /// The default value is equal to `bytes_per_second`.
+    pub fn burst_size(&self) -> u64 {
+        self.burst_size
     }
 
-    /// Set the number of remaining bytes consumable in this slice.
-    pub fn set_consumable_bytes(&mut self, n: u64) {
-        self.consumable_bytes = n;
+    /// Sets the maximum number of bytes that can be consumed in a burst.
+    pub fn set_burst_size(&mut self, n: u64) {
+        self.refill();
+        self.burst_size = n;
+        self.nano_tokens = cmp::min(self.nano_tokens, u128::from(n) * NANOS_PER_SEC);
     }
 
-    /// Returns `true` if the encoder or decoder cannot consume any more bytes, otherwise `false`.
+    /// Returns the number of bytes that can be consumed immediately.
+    pub fn available_bytes(&mut self) -> u64 {
+        self.refill();
+        (self.nano_tokens / NANOS_PER_SEC) as u64
+    }
+
+    /// Returns the duration until the next byte becomes available.
     ///
-    /// To resume its works, it is needed to reset the value of consumable bytes
-    /// by calling `set_consumable_bytes` method.
-    pub fn is_suspended(&self) -> bool {
-        self.consumable_bytes == 0
+    /// `Duration::from_secs(0)` means that some bytes are available immediately.
+    /// If the bucket will never be refilled, `None` will be returned.
+    pub fn next_available(&mut self) -> Option<Duration> {
+        self.refill();
+        if self.nano_tokens >= NANOS_PER_SEC {
+            Some(Duration::from_secs(0))
+        } else if self.bytes_per_second == 0 || self.burst_size == 0 {
+            None
+        } else {
+            let rate = u128::from(self.bytes_per_second);
+            let nanos = (NANOS_PER_SEC - self.nano_tokens).div_ceil(rate);
+            Some(Duration::from_nanos(nanos as u64))
+        }
+    }
+
+    /// Returns a reference to the clock.
+    pub fn clock_ref(&self) -> &K {
+        &self.clock
     }
 
     /// Returns a reference to the inner encoder or decoder.
-    pub fn inner_ref(&self) -> &T {
+    pub fn inner_ref(&self) -> &C {
         &self.inner
     }
 
     /// Returns a mutable reference to the inner encoder or decoder.
-    pub fn inner_mut(&mut self) -> &mut T {
+    pub fn inner_mut(&mut self) -> &mut C {
         &mut self.inner
     }
 
     /// Takes ownership of this instance and returns the inner encoder or decoder.
-    pub fn into_inner(self) -> T {
+    pub fn into_inner(self) -> C {
         self.inner
     }
+
+    fn refill(&mut self) {
+        let now = self.clock.now();
+        let elapsed = now.checked_sub(self.last_refill).unwrap_or_default();
+        self.last_refill = cmp::max(now, self.last_refill);
+
+        let max = u128::from(self.burst_size) * NANOS_PER_SEC;
+        let refilled = elapsed
+            .as_nanos()
+            .saturating_mul(u128::from(self.bytes_per_second));
+        self.nano_tokens = cmp::min(self.nano_tokens.saturating_add(refilled), max);
+    }
+
+    fn limit(&mut self, len: usize) -> usize {
+        self.refill();
+        cmp::min(len as u128, self.nano_tokens / NANOS_PER_SEC) as usize
+    }
+
+    fn consume(&mut self, size: usize) {
+        self.nano_tokens -= size as u128 * NANOS_PER_SEC;
+    }
 }
-impl<D: Decode> Decode for Slice<D> {
+impl<D: Decode, K: Clock> Decode for RateLimited<D, K> {
     type Item = D::Item;
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
-        let limit = cmp::min(buf.len() as u64, self.consumable_bytes) as usize;
+        let limit = self.limit(buf.len());
         let eos = eos.back((buf.len() - limit) as u64);
         let size = track!(self.inner.decode(&buf[..limit], eos))?;
-        self.consumable_bytes -= size as u64;
+        self.consume(size);
         Ok(size)
     }
 
@@ -1208,15 +2810,19 @@ impl<D: Decode> Decode for Slice<D> {
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
 }
-impl<E: Encode> Encode for Slice<E> {
+impl<E: Encode, K: Clock> Encode for RateLimited<E, K> {
     type Item = E::Item;
 
     fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
-        let limit = cmp::min(buf.len() as u64, self.consumable_bytes) as usize;
+        let limit = self.limit(buf.len());
         let eos = eos.back((buf.len() - limit) as u64);
         let size = track!(self.inner.encode(&mut buf[..limit], eos))?;
-        self.consumable_bytes -= size as u64;
+        self.consume(size);
         Ok(size)
     }
 
@@ -1224,222 +2830,325 @@ impl<E: Encode> Encode for Slice<E> {
         track!(self.inner.start_encoding(item))
     }
 
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
 
-    fn requiring_bytes(&self) -> ByteCount {
-        self.inner.requiring_bytes()
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
     }
 }
-impl<E: SizedEncode> SizedEncode for Slice<E> {
+impl<E: SizedEncode, K: Clock> SizedEncode for RateLimited<E, K> {
     fn exact_requiring_bytes(&self) -> u64 {
         self.inner.exact_requiring_bytes()
     }
 }
 
-/// Combinator for representing encoders that accepts only one additional item.
+/// Combinator for recording metrics of the inner encoder or decoder.
 ///
-/// This is created by calling `EncodeExt::last`.
-#[derive(Debug, Default)]
-pub struct Last<E: Encode> {
-    inner: E,
-    item: Option<E::Item>,
+/// The following metrics are reported to the recorder:
+///
+/// - The number of `decode` or `encode` calls
+/// - The number of bytes consumed by the decoder or produced by the encoder
+/// - The number of completed items and the time spent to decode or encode them
+/// - The number of errors by `ErrorKind`
+///
+/// The item times are measured by the clock `K`.
+///
+/// This is created by calling `DecodeExt::instrumented` or `EncodeExt::instrumented` method
+/// (or `instrumented_with_clock` to supply a clock other than `SystemClock`).
+#[derive(Debug)]
+pub struct Instrumented<C, R, K = SystemClock> {
+    inner: C,
+    recorder: R,
+    clock: K,
+    item_started_at: Option<Duration>,
 }
-impl<E: Encode> Last<E> {
-    /// Returns a reference to the inner encoder.
-    pub fn inner_ref(&self) -> &E {
+impl<C, R: Recorder, K: Clock> Instrumented<C, R, K> {
+    pub(crate) fn new(inner: C, recorder: R, clock: K) -> Self {
+        Instrumented {
+            inner,
+            recorder,
+            clock,
+            item_started_at: None,
+        }
+    }
+
+    /// Returns a reference to the recorder.
+    pub fn recorder_ref(&self) -> &R {
+        &self.recorder
+    }
+
+    /// Returns a reference to the clock.
+    pub fn clock_ref(&self) -> &K {
+        &self.clock
+    }
+
+    /// Returns a reference to the inner encoder or decoder.
+    pub fn inner_ref(&self) -> &C {
         &self.inner
     }
 
-    /// Returns a mutable reference to the inner encoder.
-    pub fn inner_mut(&mut self) -> &mut E {
+    /// Returns a mutable reference to the inner encoder or decoder.
+    pub fn inner_mut(&mut self) -> &mut C {
         &mut self.inner
     }
 
-    /// Takes ownership of this instance and returns the inner encoder.
-    pub fn into_inner(self) -> E {
+    /// Takes ownership of this instance and returns the inner encoder or decoder.
+    pub fn into_inner(self) -> C {
         self.inner
     }
 
-    pub(crate) fn new(inner: E, item: E::Item) -> Self {
-        Last {
-            inner,
-            item: Some(item),
+    fn record<T>(&mut self, result: Result<T>) -> Result<T> {
+        if let Err(ref e) = result {
+            self.recorder.record_error(*e.kind());
+        }
+        result
+    }
+
+    fn record_item(&mut self) {
+        if let Some(started_at) = self.item_started_at.take() {
+            let elapsed = self.clock.now().checked_sub(started_at);
+            self.recorder.record_item(elapsed.unwrap_or_default());
         }
     }
 }
-impl<E: Encode> Encode for Last<E> {
-    type Item = Never;
+impl<D: Decode, R: Recorder, K: Clock> Decode for Instrumented<D, R, K> {
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        self.recorder.record_call();
+        if self.item_started_at.is_none() {
+            self.item_started_at = Some(self.clock.now());
+        }
+        let result = self.inner.decode(buf, eos);
+        if result.is_err() {
+            self.item_started_at = None;
+        }
+        let size = track!(self.record(result))?;
+        self.recorder.record_bytes(size as u64);
+        Ok(size)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let result = self.inner.finish_decoding();
+        if result.is_err() {
+            self.item_started_at = None;
+        }
+        let item = track!(self.record(result))?;
+        self.record_item();
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.item_started_at = None;
+        track!(self.inner.reset())
+    }
+}
+impl<E: Encode, R: Recorder, K: Clock> Encode for Instrumented<E, R, K> {
+    type Item = E::Item;
 
     fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        self.recorder.record_call();
+        let result = self.inner.encode(buf, eos);
+        if result.is_err() {
+            self.item_started_at = None;
+        }
+        let size = track!(self.record(result))?;
+        self.recorder.record_bytes(size as u64);
         if self.inner.is_idle() {
-            if let Some(item) = self.item.take() {
-                track!(self.inner.start_encoding(item))?;
-            }
+            self.record_item();
         }
-        track!(self.inner.encode(buf, eos))
+        Ok(size)
     }
 
-    fn start_encoding(&mut self, _item: Self::Item) -> Result<()> {
-        unreachable!()
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let result = self.inner.start_encoding(item);
+        track!(self.record(result))?;
+        self.item_started_at = Some(self.clock.now());
+        Ok(())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
     }
 
     fn is_idle(&self) -> bool {
-        self.item.is_none() && self.inner.is_idle()
+        self.inner.is_idle()
     }
 
-    fn requiring_bytes(&self) -> ByteCount {
-        if self.item.is_some() {
-            ByteCount::Unknown
-        } else {
-            self.inner.requiring_bytes()
-        }
+    fn reset(&mut self) -> Result<()> {
+        self.item_started_at = None;
+        track!(self.inner.reset())
     }
 }
-impl<E: SizedEncode> SizedEncode for Last<E> {
+impl<E: SizedEncode, R: Recorder, K: Clock> SizedEncode for Instrumented<E, R, K> {
     fn exact_requiring_bytes(&self) -> u64 {
         self.inner.exact_requiring_bytes()
     }
 }
 
-/// Combinator that enables to peek decoded items before calling `finish_decoding` method.
+/// Combinator for emitting [tracing] spans and events about the inner encoder or decoder.
 ///
-/// This is created by calling `DecodeExt::peekable` method.
-pub struct Peekable<D: Decode> {
-    inner: D,
-    item: Option<D::Item>,
+/// A span is entered for each item, and an event is emitted for each `decode` or `encode` call.
+/// The events include the number of consumed or produced bytes,
+/// the state of `Eos` and the result of `requiring_bytes`.
+/// Errors are recorded as events together with their trackable history.
+///
+/// This is created by calling `DecodeExt::traced` or `EncodeExt::traced` method.
+///
+/// [tracing]: https://crates.io/crates/tracing
+#[cfg(feature = "tracing")]
+#[derive(Debug)]
+pub struct Traced<C> {
+    inner: C,
+    name: &'static str,
+    span: Option<tracing::Span>,
 }
-impl<D: Decode> Peekable<D> {
-    /// Returns a reference to the last item decoded (but not `finish_decoding` called yet) by the decoder.
-    pub fn peek(&self) -> Option<&D::Item> {
-        self.item.as_ref()
+#[cfg(feature = "tracing")]
+impl<C> Traced<C> {
+    pub(crate) fn new(inner: C, name: &'static str) -> Self {
+        Traced {
+            inner,
+            name,
+            span: None,
+        }
     }
 
-    /// Returns a mutable reference to the last item decoded (but not `finish_decoding` called yet) by the decoder.
-    pub fn peek_mut(&mut self) -> Option<&mut D::Item> {
-        self.item.as_mut()
+    /// Returns the name of the encoder or decoder used in the spans.
+    pub fn name(&self) -> &'static str {
+        self.name
     }
 
-    /// Returns a reference to the inner decoder.
-    pub fn inner_ref(&self) -> &D {
+    /// Returns a reference to the inner encoder or decoder.
+    pub fn inner_ref(&self) -> &C {
         &self.inner
     }
 
-    /// Returns a mutable reference to the inner decoder.
-    pub fn inner_mut(&mut self) -> &mut D {
+    /// Returns a mutable reference to the inner encoder or decoder.
+    pub fn inner_mut(&mut self) -> &mut C {
         &mut self.inner
     }
 
-    /// Takes ownership of this instance and returns the inner decoder.
-    pub fn into_inner(self) -> D {
+    /// Takes ownership of this instance and returns the inner encoder or decoder.
+    pub fn into_inner(self) -> C {
         self.inner
     }
 
-    pub(crate) fn new(inner: D) -> Self {
-        Peekable { inner, item: None }
-    }
-}
-impl<D: Decode + fmt::Debug> fmt::Debug for Peekable<D> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Peekable {{ inner: {:?}, item.is_some(): {:?} }}",
-            self.inner,
-            self.item.is_some()
-        )
-    }
-}
-impl<D: Decode + Default> Default for Peekable<D> {
-    fn default() -> Self {
-        Peekable {
-            inner: D::default(),
-            item: None,
+    fn record_error<T>(&self, result: Result<T>) -> Result<T> {
+        if let Err(ref e) = result {
+            let _enter = self.span.as_ref().map(|span| span.enter());
+            tracing::warn!(kind = ?e.kind(), error = %e, "codec error");
         }
+        result
     }
 }
-impl<D: Decode> Decode for Peekable<D> {
+#[cfg(feature = "tracing")]
+impl<D: Decode> Decode for Traced<D> {
     type Item = D::Item;
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
-        if self.item.is_none() {
-            let size = track!(self.inner.decode(buf, eos))?;
-            if self.inner.is_idle() {
-                self.item = Some(track!(self.inner.finish_decoding())?);
-            }
-            Ok(size)
-        } else {
-            Ok(0)
+        let name = self.name;
+        let span = self
+            .span
+            .get_or_insert_with(|| tracing::debug_span!("decode", codec = name))
+            .clone();
+        let _enter = span.enter();
+
+        let result = self.inner.decode(buf, eos);
+        let result = self.record_error(result);
+        if result.is_err() {
+            self.span = None;
         }
+        let size = track!(result)?;
+        tracing::trace!(
+            buf_len = buf.len(),
+            consumed = size,
+            eos = eos.is_reached(),
+            eos_remaining = ?eos.remaining_bytes(),
+            requiring_bytes = ?self.inner.requiring_bytes(),
+            "decode"
+        );
+        Ok(size)
     }
 
     fn finish_decoding(&mut self) -> Result<Self::Item> {
-        let item = track_assert_some!(self.item.take(), ErrorKind::IncompleteDecoding);
+        let result = self.inner.finish_decoding();
+        let result = self.record_error(result);
+        if result.is_err() {
+            self.span = None;
+        }
+        let item = track!(result)?;
+        if let Some(span) = self.span.take() {
+            span.in_scope(|| tracing::debug!("item decoded"));
+        }
         Ok(item)
     }
 
     fn requiring_bytes(&self) -> ByteCount {
-        if self.item.is_some() {
-            ByteCount::Finite(0)
-        } else {
-            self.inner.requiring_bytes()
-        }
+        self.inner.requiring_bytes()
     }
 
     fn is_idle(&self) -> bool {
-        self.item.is_some()
-    }
-}
-
-/// Combinator for ignoring EOS if there is no item being decoded.
-///
-/// This is created by calling `DecodeExt::maybe_eos`.
-#[derive(Debug, Default)]
-pub struct MaybeEos<D> {
-    inner: D,
-    started: bool,
-}
-impl<D> MaybeEos<D> {
-    /// Returns a reference to the inner decoder.
-    pub fn inner_ref(&self) -> &D {
-        &self.inner
-    }
-
-    /// Returns a mutable reference to the inner decoder.
-    pub fn inner_mut(&mut self) -> &mut D {
-        &mut self.inner
-    }
-
-    /// Takes ownership of this instance and returns the inner decoder.
-    pub fn into_inner(self) -> D {
-        self.inner
+        self.inner.is_idle()
     }
 
-    pub(crate) fn new(inner: D) -> Self {
-        MaybeEos {
-            inner,
-            started: false,
-        }
+    fn reset(&mut self) -> Result<()> {
+        self.span = None;
+        track!(self.inner.reset())
     }
 }
-impl<D: Decode> Decode for MaybeEos<D> {
-    type Item = D::Item;
+#[cfg(feature = "tracing")]
+impl<E: Encode> Encode for Traced<E> {
+    type Item = E::Item;
 
-    fn decode(&mut self, buf: &[u8], mut eos: Eos) -> Result<usize> {
-        if !self.started && buf.is_empty() && eos.is_reached() {
-            eos = Eos::new(false);
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let name = self.name;
+        let span = self
+            .span
+            .get_or_insert_with(|| tracing::debug_span!("encode", codec = name))
+            .clone();
+        let _enter = span.enter();
+
+        let buf_len = buf.len();
+        let result = self.inner.encode(buf, eos);
+        let result = self.record_error(result);
+        if result.is_err() {
+            self.span = None;
         }
-
-        let size = track!(self.inner.decode(buf, eos))?;
-        if size != 0 {
-            self.started = true;
+        let size = track!(result)?;
+        tracing::trace!(
+            buf_len,
+            produced = size,
+            eos = eos.is_reached(),
+            eos_remaining = ?eos.remaining_bytes(),
+            requiring_bytes = ?self.inner.requiring_bytes(),
+            "encode"
+        );
+        if self.inner.is_idle() && self.span.take().is_some() {
+            tracing::debug!("item encoded");
         }
         Ok(size)
     }
 
-    fn finish_decoding(&mut self) -> Result<Self::Item> {
-        self.started = false;
-        track!(self.inner.finish_decoding())
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let result = self.inner.start_encoding(item);
+        track!(self.record_error(result))?;
+        let name = self.name;
+        self.span = Some(tracing::debug_span!("encode", codec = name));
+        Ok(())
     }
 
     fn requiring_bytes(&self) -> ByteCount {
@@ -1449,15 +3158,35 @@ impl<D: Decode> Decode for MaybeEos<D> {
     fn is_idle(&self) -> bool {
         self.inner.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.span = None;
+        track!(self.inner.reset())
+    }
+}
+#[cfg(feature = "tracing")]
+impl<E: SizedEncode> SizedEncode for Traced<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use super::Either;
     use crate::bytes::{Utf8Decoder, Utf8Encoder};
-    use crate::fixnum::{U16beDecoder, U8Decoder, U8Encoder};
+    use crate::clock::{Clock, ManualClock};
+    use crate::fixnum::{U16beDecoder, U32beDecoder, U8Decoder, U8Encoder};
     use crate::io::{IoDecodeExt, IoEncodeExt};
+    use crate::metrics::Counters;
+    use crate::null::NullDecoder;
     use crate::tuple::TupleDecoder;
-    use crate::{Decode, DecodeExt, Encode, EncodeExt, Eos, ErrorKind};
+    use crate::{ByteCount, Decode, DecodeExt, Encode, EncodeExt, Eos, ErrorKind};
+    use std::cell::Cell;
+    use std::cmp;
+    #[cfg(feature = "tracing")]
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     #[test]
     fn collect_works() {
@@ -1519,16 +3248,45 @@ mod test {
         let mut output = Vec::new();
         let mut encoder = Utf8Encoder::new().length(3);
         encoder.start_encoding("hello").unwrap(); // Error (too long)
-        let error = encoder.encode_all(&mut output).err().expect("too long");
+        let error = encoder.encode_all(&mut output).expect_err("too long");
         assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
 
         let mut output = Vec::new();
         let mut encoder = Utf8Encoder::new().length(3);
         encoder.start_encoding("hi").unwrap(); // Error (too short)
-        let error = encoder.encode_all(&mut output).err().expect("too short");
+        let error = encoder.encode_all(&mut output).expect_err("too short");
         assert_eq!(*error.kind(), ErrorKind::InvalidInput);
     }
 
+    #[test]
+    fn reset_works() {
+        // Decoders
+        let mut decoder = Utf8Decoder::new().length(3);
+        assert_eq!(track_try_unwrap!(decoder.decode(b"fo", Eos::new(false))), 2);
+        track_try_unwrap!(decoder.reset());
+        assert_eq!(decoder.remaining_bytes(), 3);
+        let item = track_try_unwrap!(decoder.decode_exact(b"bar".as_ref()));
+        assert_eq!(item, "bar");
+
+        let mut decoder = U8Decoder::new().collectn::<Vec<_>>(3);
+        assert_eq!(track_try_unwrap!(decoder.decode(b"fo", Eos::new(false))), 2);
+        track_try_unwrap!(decoder.reset());
+        let item = track_try_unwrap!(decoder.decode_exact(b"bar".as_ref()));
+        assert_eq!(item, b"bar");
+
+        // Encoders
+        let mut output = Vec::new();
+        let mut encoder = Utf8Encoder::new().length(3);
+        track_try_unwrap!(encoder.start_encoding("hello"));
+        assert!(encoder.encode_all(&mut output).is_err());
+
+        output.clear();
+        track_try_unwrap!(encoder.reset());
+        track_try_unwrap!(encoder.start_encoding("hey"));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, b"hey");
+    }
+
     #[test]
     fn repeat_works() {
         let mut output = Vec::new();
@@ -1598,7 +3356,7 @@ mod test {
 
         offset += track_try_unwrap!(encoder.encode(&mut output[offset..], eos));
         assert_eq!(offset, 3);
-        assert_eq!(encoder.is_suspended(), true);
+        assert!(encoder.is_suspended());
 
         encoder.set_consumable_bytes(3);
         offset += track_try_unwrap!(encoder.encode(&mut output[offset..], eos));
@@ -1643,4 +3401,445 @@ mod test {
         assert_eq!(decoder.finish_decoding().unwrap(), (b'f', b'o', b'o'));
         assert_eq!(decoder.peek(), None);
     }
+
+    #[test]
+    fn error_offset_and_path_work() {
+        let mut decoder = TupleDecoder::new((U8Decoder::new(), U16beDecoder::new()));
+        let error = decoder.decode(&[1, 0], Eos::new(true)).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+        assert_eq!(error.offset(), Some(1));
+        assert_eq!(error.path(), "tuple.1 / U16beDecoder");
+        let message = error.to_string();
+        let first_line = message.lines().next().unwrap();
+        assert!(first_line.starts_with("UnexpectedEos"));
+        assert!(first_line.ends_with(" (at offset 1, path: tuple.1 / U16beDecoder)"));
+
+        let mut decoder = TupleDecoder::new((
+            U8Decoder::new(),
+            Utf8Decoder::new().length(2).collectn::<Vec<_>>(3),
+        ));
+        let input = [0, b'a', b'b', b'c', b'd', 0xff, b'e'];
+        let error = decoder.decode_exact(&input[..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.offset(), Some(5));
+        assert_eq!(error.path(), "tuple.1 / collectn[2] / length");
+
+        let mut decoder = TupleDecoder::new((
+            U8Decoder::new(),
+            U8Decoder::new().count_prefixed::<Vec<_>, _>(U16beDecoder::new()),
+        ));
+        let error = decoder.decode(&[0, 0], Eos::new(true)).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+        assert_eq!(error.offset(), Some(1));
+        assert_eq!(
+            error.path(),
+            "tuple.1 / count_prefixed.count / U16beDecoder"
+        );
+    }
+
+    #[test]
+    fn resync_works() {
+        fn new_decoder() -> impl Decode<Item = String> {
+            Utf8Decoder::new().length(4)
+        }
+        let mut decoder = new_decoder().resync(b"#!".to_vec());
+
+        // The invalid item (`\xff...`) and the following garbage are skipped
+        let input = b"#!ab\xff#!c-x#-#";
+        let mut items = Vec::new();
+        let mut offset = 0;
+        while offset < input.len() {
+            let end = cmp::min(offset + 3, input.len());
+            offset += track_try_unwrap!(decoder.decode(&input[offset..end], Eos::new(false)));
+            if decoder.is_idle() {
+                items.push(track_try_unwrap!(decoder.finish_decoding()));
+            }
+        }
+        assert_eq!(items, ["#!ab"]);
+        assert!(decoder.is_scanning());
+
+        let size = track_try_unwrap!(decoder.decode(b"!yz", Eos::new(false)));
+        assert_eq!(size, 3);
+        assert_eq!(track_try_unwrap!(decoder.finish_decoding()), "#!yz");
+        assert_eq!(decoder.resync_count(), 1);
+        assert_eq!(decoder.skipped_bytes(), 5); // "c-x#-"
+
+        // EOS is reached while scanning
+        let error = decoder.decode(b"\xff...", Eos::new(true)).err();
+        assert_eq!(error.map(|e| *e.kind()), Some(ErrorKind::UnexpectedEos));
+    }
+
+    #[test]
+    fn or_works() {
+        fn v2() -> impl Decode<Item = u8> {
+            TupleDecoder::new((U8Decoder::new(), U8Decoder::new(), U8Decoder::new())).try_map(
+                |(a, b, c)| {
+                    if (a, b) == (0xFF, 0xFE) {
+                        Ok(c)
+                    } else {
+                        Err(ErrorKind::InvalidInput)
+                    }
+                },
+            )
+        }
+        let input = [0xFF, 0xFE, 1, 0xFF, 0x00, 0xFF, 0xFE, 2];
+
+        // Feeds the input byte by byte
+        let mut decoder = v2().or(U16beDecoder::new());
+        let mut items = Vec::new();
+        let mut offset = 0;
+        while offset < input.len() {
+            offset += track_try_unwrap!(decoder.decode(&input[offset..][..1], Eos::new(false)));
+            if decoder.is_idle() {
+                items.push(track_try_unwrap!(decoder.finish_decoding()));
+            }
+        }
+        assert_eq!(
+            items,
+            [Either::Left(1), Either::Right(0xFF00), Either::Left(2)]
+        );
+
+        // Exceeds the lookahead limit
+        let mut decoder = v2().or(U16beDecoder::new());
+        decoder.set_lookahead_limit(2);
+        let mut input = &input[..];
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_exact(&mut input)),
+            Either::Left(1)
+        );
+        let error = decoder.decode_exact(&mut input).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn inspect_bytes_works() {
+        let mut captured = Vec::new();
+        {
+            let mut decoder =
+                Utf8Decoder::new()
+                    .length(3)
+                    .inspect_bytes(|item: &String, bytes: &[u8]| {
+                        captured.push((item.clone(), bytes.to_owned()))
+                    });
+            track_try_unwrap!(decoder.decode(b"f", Eos::new(false)));
+            assert_eq!(decoder.captured_bytes(), b"f");
+            track_try_unwrap!(decoder.decode(b"oobar", Eos::new(false)));
+            assert_eq!(track_try_unwrap!(decoder.finish_decoding()), "foo");
+            assert!(decoder.captured_bytes().is_empty());
+        }
+        assert_eq!(captured, [("foo".to_owned(), b"foo".to_vec())]);
+
+        let mut captured = Vec::new();
+        {
+            let mut encoder = Utf8Encoder::new().optional().inspect_bytes(|bytes: &[u8]| {
+                captured.push(bytes.to_owned());
+            });
+            let mut output = [0; 3];
+            track_try_unwrap!(encoder.encode(&mut output, Eos::new(false)));
+            track_try_unwrap!(encoder.start_encoding(Some("bar")));
+            track_try_unwrap!(encoder.encode(&mut output[..1], Eos::new(false)));
+            track_try_unwrap!(encoder.encode(&mut output[1..], Eos::new(false)));
+            track_try_unwrap!(encoder.encode(&mut output, Eos::new(false)));
+
+            track_try_unwrap!(encoder.start_encoding(None));
+            assert!(encoder.is_idle());
+            track_try_unwrap!(encoder.start_encoding(None));
+            track_try_unwrap!(encoder.encode(&mut output, Eos::new(false)));
+        }
+        assert_eq!(captured, [b"bar".to_vec(), Vec::new(), Vec::new()]);
+    }
+
+    #[test]
+    fn rate_limited_works() {
+        let clock = ManualClock::new();
+        let mut encoder = Utf8Encoder::new().rate_limited(10, clock.clone());
+        encoder.set_burst_size(4);
+        track_try_unwrap!(encoder.start_encoding("0123456789"));
+
+        let mut output = [0; 10];
+        let mut offset = track_try_unwrap!(encoder.encode(&mut output, Eos::new(false)));
+        assert_eq!(offset, 4);
+        assert_eq!(encoder.next_available(), Some(Duration::from_millis(100)));
+
+        // The bucket never holds more than `burst_size` bytes
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(encoder.available_bytes(), 4);
+        offset += track_try_unwrap!(encoder.encode(&mut output[offset..], Eos::new(false)));
+        assert_eq!(offset, 8);
+
+        clock.advance(Duration::from_millis(250));
+        offset += track_try_unwrap!(encoder.encode(&mut output[offset..], Eos::new(false)));
+        assert_eq!(offset, 10);
+        assert!(encoder.is_idle());
+        assert_eq!(&output, b"0123456789");
+
+        encoder.set_bytes_per_second(0);
+        assert_eq!(encoder.next_available(), None);
+    }
+
+    #[test]
+    fn rate_limited_refill_saturates() {
+        // Jumps to the far future after the first call.
+        struct JumpingClock(std::cell::Cell<bool>);
+        impl Clock for JumpingClock {
+            fn now(&self) -> Duration {
+                if self.0.replace(true) {
+                    Duration::MAX
+                } else {
+                    Duration::ZERO
+                }
+            }
+        }
+
+        let clock = JumpingClock(Default::default());
+        let mut encoder = Utf8Encoder::<&str>::new().rate_limited(u64::MAX, clock);
+        encoder.set_burst_size(4);
+        assert_eq!(encoder.available_bytes(), 4);
+    }
+
+    #[test]
+    fn instrumented_works() {
+        let clock = ManualClock::new();
+        let counters = Counters::new();
+        let mut encoder = Utf8Encoder::new().instrumented_with_clock(&counters, clock.clone());
+        clock.advance(Duration::from_secs(1));
+        track_try_unwrap!(encoder.start_encoding("foo"));
+        assert_eq!(
+            encoder.start_encoding("bar").err().map(|e| *e.kind()),
+            Some(ErrorKind::EncoderFull)
+        );
+
+        let mut output = [0; 3];
+        track_try_unwrap!(encoder.encode(&mut output[..1], Eos::new(false)));
+        clock.advance(Duration::from_millis(30));
+        track_try_unwrap!(encoder.encode(&mut output[1..], Eos::new(false)));
+        assert_eq!(&output, b"foo");
+        clock.advance(Duration::from_secs(1));
+
+        assert_eq!(counters.calls(), 2);
+        assert_eq!(counters.bytes(), 3);
+        assert_eq!(counters.items(), 1);
+        assert_eq!(counters.item_time(), Duration::from_millis(30));
+        assert_eq!(counters.errors(ErrorKind::EncoderFull), 1);
+        assert_eq!(counters.total_errors(), 1);
+
+        let counters = Counters::new();
+        let mut decoder = Utf8Decoder::new()
+            .length(3)
+            .instrumented_with_clock(&counters, clock.clone());
+        track_try_unwrap!(decoder.decode(b"f", Eos::new(false)));
+        clock.advance(Duration::from_millis(20));
+        track_try_unwrap!(decoder.decode(b"oo", Eos::new(false)));
+        clock.advance(Duration::from_millis(5));
+        assert_eq!(track_try_unwrap!(decoder.finish_decoding()), "foo");
+
+        assert_eq!(counters.items(), 1);
+        assert_eq!(counters.item_time(), Duration::from_millis(25));
+    }
+
+    /// Subscriber that records the events as `"{span}: {level}{fields}"` lines.
+    #[cfg(feature = "tracing")]
+    #[derive(Default, Clone)]
+    struct CapturingSubscriber {
+        spans: Arc<Mutex<Vec<String>>>,
+        entered: Arc<Mutex<Vec<usize>>>,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for CapturingSubscriber {
+        fn enabled(&self, _metadata: &tracing::Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes) -> tracing::span::Id {
+            let mut fields = FieldsCollector::default();
+            span.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(format!("{}{{{}}}", span.metadata().name(), fields.0.trim()));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record) {}
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event) {
+            let mut fields = FieldsCollector::default();
+            event.record(&mut fields);
+            let span = self
+                .entered
+                .lock()
+                .unwrap()
+                .last()
+                .map(|&i| self.spans.lock().unwrap()[i].clone())
+                .unwrap_or_default();
+            let line = format!("{}: {}{}", span, event.metadata().level(), fields.0);
+            self.events.lock().unwrap().push(line);
+        }
+
+        fn enter(&self, span: &tracing::span::Id) {
+            let index = span.into_u64() as usize - 1;
+            self.entered.lock().unwrap().push(index);
+        }
+
+        fn exit(&self, _span: &tracing::span::Id) {
+            self.entered.lock().unwrap().pop();
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct FieldsCollector(String);
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for FieldsCollector {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0 += &format!(" {}={:?}", field.name(), value);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn traced_works() {
+        let subscriber = CapturingSubscriber::default();
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            let mut decoder = Utf8Decoder::new().length(3).traced("utf8");
+            assert_eq!(track_try_unwrap!(decoder.decode_exact(&b"foo"[..])), "foo");
+            assert!(decoder.decode_exact(&b"fo"[..]).is_err());
+
+            let mut encoder = Utf8Encoder::new().traced("utf8");
+            let mut output = Vec::new();
+            track_try_unwrap!(encoder.start_encoding("bar"));
+            track_try_unwrap!(encoder.encode_all(&mut output));
+            assert_eq!(output, b"bar");
+        });
+
+        let events = subscriber.events.lock().unwrap();
+        assert_eq!(
+            events[0],
+            "decode{codec=\"utf8\"}: TRACE message=decode buf_len=3 consumed=3 eos=false \
+             eos_remaining=Unknown requiring_bytes=Finite(0)"
+        );
+        assert_eq!(
+            events[1],
+            "decode{codec=\"utf8\"}: DEBUG message=item decoded"
+        );
+        assert!(events[3]
+            .starts_with("decode{codec=\"utf8\"}: WARN message=codec error kind=UnexpectedEos"));
+        assert_eq!(
+            events[4],
+            "encode{codec=\"utf8\"}: TRACE message=encode buf_len=1024 produced=3 eos=false \
+             eos_remaining=Unknown requiring_bytes=Finite(0)"
+        );
+        assert_eq!(
+            events[5],
+            "encode{codec=\"utf8\"}: DEBUG message=item encoded"
+        );
+        assert_eq!(events.len(), 6);
+    }
+
+    #[test]
+    fn count_prefixed_works() {
+        let mut output = Vec::new();
+        let mut encoder = U8Encoder::new().count_prefixed(U8Encoder::new());
+        track_try_unwrap!(encoder.start_encoding(vec![3, 4, 5].into_iter()));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [3, 3, 4, 5]);
+
+        let mut decoder = U8Decoder::new().count_prefixed::<Vec<_>, _>(U8Decoder::new());
+        let item = track_try_unwrap!(decoder.decode_exact(&[3, 3, 4, 5, 0][..]));
+        assert_eq!(item, [3, 4, 5]);
+        let item = track_try_unwrap!(decoder.decode_exact(&[0][..]));
+        assert!(item.is_empty());
+
+        decoder.set_max_items(2);
+        let error = decoder.decode_exact(&output[..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        // An oversized count is rejected before any element is decoded
+        let decoded = Cell::new(0);
+        let mut decoder = NullDecoder
+            .map(|()| decoded.set(decoded.get() + 1))
+            .count_prefixed::<Vec<_>, _>(U32beDecoder::new());
+        assert_eq!(decoder.max_items(), super::DEFAULT_MAX_ITEMS);
+        let error = decoder.decode_exact(&[0xFF; 4][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(decoded.get(), 0);
+
+        encoder.set_max_items(2);
+        let error = encoder
+            .start_encoding(vec![3, 4, 5].into_iter())
+            .err()
+            .unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn align_works() {
+        let mut encoder = Utf8Encoder::new().align(4, 0xFF);
+        track_try_unwrap!(encoder.start_encoding("hello"));
+        assert_eq!(encoder.requiring_bytes(), ByteCount::Finite(8));
+
+        let mut buf = [0; 8];
+        let mut offset = 0;
+        while !encoder.is_idle() {
+            offset += track_try_unwrap!(
+                encoder.encode(&mut buf[offset..][..3.min(8 - offset)], Eos::new(false))
+            );
+        }
+        assert_eq!(&buf[..], b"hello\xFF\xFF\xFF");
+
+        // The item and its padding are split across buffer boundaries
+        let mut decoder = Utf8Decoder::new().length(5).align(4);
+        let input = b"hello\xFF\xFF\xFF";
+        let mut offset = 0;
+        for chunk in input.chunks(3) {
+            assert!(!decoder.is_idle());
+            offset += track_try_unwrap!(decoder.decode(chunk, Eos::new(false)));
+        }
+        assert_eq!(offset, 8);
+        assert!(decoder.is_idle());
+        assert_eq!(track_try_unwrap!(decoder.finish_decoding()), "hello");
+
+        let mut decoder = U8Decoder::new().align(4);
+        let mut input = &[1, 0, 0, 0, 2, 0][..];
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), 1);
+        let error = decoder.decode_exact(&mut input).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+
+        let mut decoder = Utf8Decoder::new().length(2).align(4);
+        let error = decoder.decode_exact(&[0xFF, 0xFF, 0, 0][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.path_segments().next(), Some("align"));
+    }
+
+    #[test]
+    fn pad_to_works() {
+        let mut output = Vec::new();
+        let mut encoder = Utf8Encoder::new().pad_to(4);
+        track_try_unwrap!(encoder.start_encoding(""));
+        assert_eq!(encoder.requiring_bytes(), ByteCount::Finite(4));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [0; 4]);
+
+        // An item which exactly fills the fixed size needs no padding
+        output.clear();
+        track_try_unwrap!(encoder.start_encoding("abcd"));
+        assert_eq!(encoder.requiring_bytes(), ByteCount::Finite(4));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, b"abcd");
+
+        track_try_unwrap!(encoder.start_encoding("hello"));
+        let error = encoder.encode_all(&mut output).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut decoder = Utf8Decoder::new().pad_to(4);
+        let item = track_try_unwrap!(decoder.decode_exact(&b"foo!bar"[..]));
+        assert_eq!(item, "foo!");
+        let error = decoder.decode_exact(&b"bar"[..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+
+        let mut decoder = Utf8Decoder::new().length(4).pad_to(4);
+        let item = track_try_unwrap!(decoder.decode_exact(&b"abcd"[..]));
+        assert_eq!(item, "abcd");
+    }
 }
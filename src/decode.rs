@@ -1,7 +1,12 @@
+use crate::clock::{Clock, SystemClock};
+#[cfg(feature = "tracing")]
+use crate::combinator::Traced;
 use crate::combinator::{
-    AndThen, Collect, CollectN, Length, Map, MapErr, MaxBytes, MaybeEos, Omittable, Peekable,
-    Slice, TryMap,
+    Align, AndThen, Collect, CollectN, CountPrefixed, InspectBytes, Instrumented, Length, Map,
+    MapErr, MaxBytes, MaybeEos, Omittable, Or, PadTo, Peekable, RateLimited, Resync, Slice, TryMap,
+    DEFAULT_MAX_ITEMS,
 };
+use crate::metrics::Recorder;
 use crate::tuple::TupleDecoder;
 use crate::{ByteCount, Eos, Error, ErrorKind, Result};
 
@@ -81,8 +86,26 @@ pub trait Decode {
     fn is_idle(&self) -> bool {
         self.requiring_bytes() == ByteCount::Finite(0)
     }
+
+    /// Resets the decoder to its initial state.
+    ///
+    /// This discards any partially decoded item (e.g., after an error or an abandoned input stream)
+    /// so that the decoder can be reused for decoding a new byte sequence from the beginning.
+    ///
+    /// If this method returns `Ok(())`, the decoder behaves as if it were newly created,
+    /// except that its settings (e.g., the limit of `MaxBytes` or the length of `Length`) are preserved.
+    /// Decoders that have terminated (e.g., `BytesDecoder` after its item has been taken)
+    /// remain terminated because there is no way to restore the consumed resources.
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::Other`:
+    ///   - The decoder does not support resetting (this is the default implementation)
+    fn reset(&mut self) -> Result<()> {
+        track_panic!(ErrorKind::Other, "This decoder does not support `reset`")
+    }
 }
-impl<'a, D: ?Sized + Decode> Decode for &'a mut D {
+impl<D: ?Sized + Decode> Decode for &mut D {
     type Item = D::Item;
 
     fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
@@ -100,6 +123,10 @@ impl<'a, D: ?Sized + Decode> Decode for &'a mut D {
     fn is_idle(&self) -> bool {
         (**self).is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        (**self).reset()
+    }
 }
 impl<D: ?Sized + Decode> Decode for Box<D> {
     type Item = D::Item;
@@ -119,6 +146,10 @@ impl<D: ?Sized + Decode> Decode for Box<D> {
     fn is_idle(&self) -> bool {
         (**self).is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        (**self).reset()
+    }
 }
 
 /// An extension of `Decode` trait.
@@ -179,10 +210,10 @@ pub trait DecodeExt: Decode + Sized {
     /// The following code shows the idiomatic way to track decoding errors:
     ///
     /// ```
-    /// use bytecodec::{Decode, DecodeExt};
+    /// use bytecodec::{Decode, DecodeExt, ErrorKind};
     /// use bytecodec::fixnum::U16beDecoder;
     /// use bytecodec::io::IoDecodeExt;
-    /// use trackable::track;
+    /// use trackable::{track, Trackable};
     ///
     /// let mut decoder =
     ///     U16beDecoder::new().map_err(|e| track!(e, "oops!"));
@@ -191,15 +222,13 @@ pub trait DecodeExt: Decode + Sized {
     /// let input = [0]; // Insufficient bytes
     /// let error = track!(decoder.decode_exact(input.as_ref())).err().unwrap();
     ///
-    /// assert_eq!(error.to_string(), "\
-    /// UnexpectedEos (cause; assertion failed: `!eos.is_reached()`; \
-    ///                self.offset=1, self.bytes.as_ref().len()=2)
-    /// HISTORY:
-    ///   [0] at src/bytes.rs:152
-    ///   [1] at src/fixnum.rs:200
-    ///   [2] at src/decode.rs:10 -- oops!
-    ///   [3] at src/io.rs:45
-    ///   [4] at src/decode.rs:14\n");
+    /// assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+    ///
+    /// // The history records every location the error passed through.
+    /// let events = error.history().unwrap().events();
+    /// let files = events.iter().map(|e| e.file()).collect::<Vec<_>>();
+    /// assert_eq!(files, ["src/bytes.rs", "src/fixnum.rs", "src/decode.rs", "src/io.rs", "src/decode.rs"]);
+    /// assert_eq!(events[2].message(), "oops!");
     /// ```
     fn map_err<E, F>(self, f: F) -> MapErr<Self, E, F>
     where
@@ -276,6 +305,37 @@ pub trait DecodeExt: Decode + Sized {
         CollectN::new(self, n)
     }
 
+    /// Creates a decoder that decodes the number of items by using `count_decoder`
+    /// and then decodes that number of items by using `self` and collecting the result.
+    ///
+    /// By default, the number of items is limited to `combinator::DEFAULT_MAX_ITEMS`
+    /// (see `CountPrefixed::set_max_items`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Decode, DecodeExt};
+    /// use bytecodec::fixnum::{U16beDecoder, U8Decoder};
+    /// use bytecodec::io::IoDecodeExt;
+    ///
+    /// let mut decoder = U8Decoder::new().count_prefixed::<Vec<_>, _>(U16beDecoder::new());
+    /// decoder.set_max_items(8);
+    ///
+    /// let item = decoder.decode_exact(b"\x00\x02foo".as_ref()).unwrap();
+    /// assert_eq!(item, vec![b'f', b'o']);
+    ///
+    /// let error = decoder.decode_exact(b"\x00\x09foo".as_ref()).err().unwrap();
+    /// assert_eq!(*error.kind(), bytecodec::ErrorKind::InvalidInput);
+    /// ```
+    fn count_prefixed<T, N>(self, count_decoder: N) -> CountPrefixed<CollectN<Self, T>, N>
+    where
+        T: Extend<Self::Item> + Default,
+        N: Decode,
+        N::Item: Into<u64>,
+    {
+        CountPrefixed::new(CollectN::new(self, 0), count_decoder, DEFAULT_MAX_ITEMS)
+    }
+
     /// Creates a decoder that consumes the specified number of bytes exactly.
     ///
     /// # Examples
@@ -301,6 +361,53 @@ pub trait DecodeExt: Decode + Sized {
         Length::new(self, expected_bytes)
     }
 
+    /// Creates a decoder that skips padding bytes after each item
+    /// so that the number of consumed bytes becomes a multiple of `alignment`.
+    ///
+    /// # Panics
+    ///
+    /// If `alignment` is `0`, this method will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::DecodeExt;
+    /// use bytecodec::bytes::Utf8Decoder;
+    /// use bytecodec::io::IoDecodeExt;
+    ///
+    /// let mut decoder = Utf8Decoder::new().length(3).align(4);
+    /// let mut input = &b"foo\x00bar\x00"[..];
+    ///
+    /// assert_eq!(decoder.decode_exact(&mut input).unwrap(), "foo");
+    /// assert_eq!(decoder.decode_exact(&mut input).unwrap(), "bar");
+    /// ```
+    fn align(self, alignment: u64) -> Align<Self> {
+        Align::new(self, alignment, 0)
+    }
+
+    /// Creates a decoder that skips padding bytes after each item
+    /// so that exactly `total_size` bytes are consumed per item.
+    ///
+    /// If the inner decoder requires more than `total_size` bytes,
+    /// an `ErrorKind::UnexpectedEos` error will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::DecodeExt;
+    /// use bytecodec::fixnum::U16beDecoder;
+    /// use bytecodec::io::IoDecodeExt;
+    ///
+    /// let mut decoder = U16beDecoder::new().pad_to(4);
+    /// let mut input = &[0, 1, 0xFF, 0xFF, 0, 2, 0xFF, 0xFF][..];
+    ///
+    /// assert_eq!(decoder.decode_exact(&mut input).unwrap(), 1);
+    /// assert_eq!(decoder.decode_exact(&mut input).unwrap(), 2);
+    /// ```
+    fn pad_to(self, total_size: u64) -> PadTo<Self> {
+        PadTo::new(self, total_size)
+    }
+
     /// Creates a decoder that will omit decoding items if `do_omit = true` is specified.
     ///
     /// # Examples
@@ -430,6 +537,192 @@ pub trait DecodeExt: Decode + Sized {
         MaybeEos::new(self)
     }
 
+    /// Creates a decoder that passes each decoded item and the exact bytes it spanned to `f`.
+    ///
+    /// The bytes are captured across multiple `decode` calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::DecodeExt;
+    /// use bytecodec::fixnum::U16beDecoder;
+    /// use bytecodec::hexdump::HexDump;
+    /// use bytecodec::io::IoDecodeExt;
+    ///
+    /// let mut dumps = Vec::new();
+    /// let mut decoder = U16beDecoder::new().inspect_bytes(|item, bytes| {
+    ///     dumps.push(format!("{}: {}", item, HexDump::new(bytes)));
+    /// });
+    /// assert_eq!(decoder.decode_exact(&[1, 2][..]).unwrap(), 0x0102);
+    ///
+    /// drop(decoder);
+    /// assert_eq!(
+    ///     dumps,
+    ///     ["258: 00000000  01 02                                             |..|\n"]
+    /// );
+    /// ```
+    fn inspect_bytes<F>(self, f: F) -> InspectBytes<Self, F>
+    where
+        F: FnMut(&Self::Item, &[u8]),
+    {
+        InspectBytes::new(self, f)
+    }
+
+    /// Creates a decoder that resynchronizes the input stream by using `pattern`
+    /// after the decoder detected an invalid input.
+    ///
+    /// The decoder is reset by `Decode::reset` when the pattern is found,
+    /// so it must support resetting.
+    /// See the documentation of `Resync` for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Decode, DecodeExt, ErrorKind};
+    /// use bytecodec::fixnum::U8Decoder;
+    /// use bytecodec::io::IoDecodeExt;
+    ///
+    /// // Each item consists of a magic byte `0xAA` and a payload byte
+    /// fn new_decoder() -> impl Decode<Item = u8> {
+    ///     U8Decoder::new()
+    ///         .try_map(|magic| if magic == 0xAA { Ok(()) } else { Err(ErrorKind::InvalidInput) })
+    ///         .and_then(|()| U8Decoder::new())
+    /// }
+    ///
+    /// let mut decoder = new_decoder().resync(vec![0xAA]);
+    /// let mut input = &[0xAA, 1, 0xFF, 0xFE, 0xAA, 2][..];
+    /// assert_eq!(decoder.decode_exact(&mut input).unwrap(), 1);
+    /// assert_eq!(decoder.decode_exact(&mut input).unwrap(), 2);
+    /// assert_eq!(decoder.skipped_bytes(), 2);
+    /// ```
+    fn resync(self, pattern: Vec<u8>) -> Resync<Self> {
+        Resync::new(self, pattern)
+    }
+
+    /// Creates a decoder that uses `other` if `self` fails to decode an item because of an invalid input.
+    ///
+    /// The input bytes consumed by `self` are replayed into `other` (up to the lookahead limit).
+    /// See the documentation of `Or` for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{DecodeExt, ErrorKind};
+    /// use bytecodec::combinator::Either;
+    /// use bytecodec::fixnum::{U16beDecoder, U8Decoder};
+    /// use bytecodec::io::IoDecodeExt;
+    ///
+    /// // v2 layout: `0xFF` followed by a 16-bit value, v1 layout: an 8-bit value
+    /// let v2 = U8Decoder::new()
+    ///     .try_map(|b| if b == 0xFF { Ok(()) } else { Err(ErrorKind::InvalidInput) })
+    ///     .and_then(|()| U16beDecoder::new());
+    /// let mut decoder = v2.or(U8Decoder::new());
+    ///
+    /// let mut input = &[0xFF, 1, 2, 3][..];
+    /// assert_eq!(decoder.decode_exact(&mut input).unwrap(), Either::Left(0x0102));
+    /// assert_eq!(decoder.decode_exact(&mut input).unwrap(), Either::Right(3));
+    /// ```
+    fn or<D: Decode>(self, other: D) -> Or<Self, D> {
+        Or::new(self, other)
+    }
+
+    /// Creates a decoder that limits the decoding throughput to `bytes_per_second`.
+    ///
+    /// See the documentation of `RateLimited` for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Decode, DecodeExt, Eos};
+    /// use bytecodec::bytes::Utf8Decoder;
+    /// use bytecodec::clock::ManualClock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// let mut decoder = Utf8Decoder::new().length(6).rate_limited(3, clock.clone());
+    ///
+    /// let mut offset = decoder.decode(b"foobar", Eos::new(true)).unwrap();
+    /// assert_eq!(offset, 3);
+    /// assert_eq!(decoder.next_available(), Some(Duration::from_nanos(333_333_334)));
+    ///
+    /// clock.advance(Duration::from_secs(1));
+    /// offset += decoder.decode(&b"foobar"[offset..], Eos::new(true)).unwrap();
+    /// assert_eq!(offset, 6);
+    /// assert_eq!(decoder.finish_decoding().unwrap(), "foobar");
+    /// ```
+    fn rate_limited<K: Clock>(self, bytes_per_second: u64, clock: K) -> RateLimited<Self, K> {
+        RateLimited::new(self, bytes_per_second, clock)
+    }
+
+    /// Creates a decoder that records the metrics of the decoding processes to the given recorder.
+    ///
+    /// See the documentation of `Instrumented` for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::DecodeExt;
+    /// use bytecodec::bytes::Utf8Decoder;
+    /// use bytecodec::io::IoDecodeExt;
+    /// use bytecodec::metrics::Counters;
+    ///
+    /// let counters = Counters::new();
+    /// let mut decoder = Utf8Decoder::new().instrumented(&counters);
+    /// assert_eq!(decoder.decode_exact(&b"foo"[..]).unwrap(), "foo");
+    ///
+    /// assert_eq!(counters.bytes(), 3);
+    /// assert_eq!(counters.items(), 1);
+    /// ```
+    fn instrumented<R: Recorder>(self, recorder: R) -> Instrumented<Self, R> {
+        Instrumented::new(self, recorder, SystemClock::new())
+    }
+
+    /// Creates a decoder that records the metrics of the decoding processes to the given recorder,
+    /// measuring the item times by `clock`.
+    ///
+    /// See the documentation of `Instrumented` for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Decode, DecodeExt, Eos};
+    /// use bytecodec::bytes::Utf8Decoder;
+    /// use bytecodec::clock::ManualClock;
+    /// use bytecodec::metrics::Counters;
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// let counters = Counters::new();
+    /// let mut decoder = Utf8Decoder::new()
+    ///     .length(6)
+    ///     .instrumented_with_clock(&counters, clock.clone());
+    ///
+    /// decoder.decode(b"foo", Eos::new(false)).unwrap();
+    /// clock.advance(Duration::from_millis(5));
+    /// decoder.decode(b"bar", Eos::new(false)).unwrap();
+    /// assert_eq!(decoder.finish_decoding().unwrap(), "foobar");
+    ///
+    /// assert_eq!(counters.items(), 1);
+    /// assert_eq!(counters.item_time(), Duration::from_millis(5));
+    /// ```
+    fn instrumented_with_clock<R: Recorder, K: Clock>(
+        self,
+        recorder: R,
+        clock: K,
+    ) -> Instrumented<Self, R, K> {
+        Instrumented::new(self, recorder, clock)
+    }
+
+    /// Creates a decoder that emits [tracing] spans and events about the decoding processes.
+    ///
+    /// See the documentation of `Traced` for the details.
+    ///
+    /// [tracing]: https://crates.io/crates/tracing
+    #[cfg(feature = "tracing")]
+    fn traced(self, name: &'static str) -> Traced<Self> {
+        Traced::new(self, name)
+    }
+
     /// Decodes an item by consuming the whole part of the given bytes.
     ///
     /// # Examples
@@ -472,7 +765,7 @@ pub trait TaggedDecode: Decode {
     ///   - Other errors has occurred
     fn start_decoding(&mut self, tag: Self::Tag) -> Result<()>;
 }
-impl<'a, D: ?Sized + TaggedDecode> TaggedDecode for &'a mut D {
+impl<D: ?Sized + TaggedDecode> TaggedDecode for &mut D {
     type Tag = D::Tag;
 
     fn start_decoding(&mut self, tag: Self::Tag) -> Result<()> {
@@ -507,7 +800,7 @@ pub trait TryTaggedDecode: Decode {
     ///   - Other errors has occurred
     fn try_start_decoding(&mut self, tag: Self::Tag) -> Result<bool>;
 }
-impl<'a, D: ?Sized + TryTaggedDecode> TryTaggedDecode for &'a mut D {
+impl<D: ?Sized + TryTaggedDecode> TryTaggedDecode for &mut D {
     type Tag = D::Tag;
 
     fn try_start_decoding(&mut self, tag: Self::Tag) -> Result<bool> {
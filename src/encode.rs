@@ -1,9 +1,15 @@
+use crate::clock::{Clock, SystemClock};
+#[cfg(feature = "tracing")]
+use crate::combinator::Traced;
 use crate::combinator::{
-    Last, Length, MapErr, MapFrom, MaxBytes, Optional, PreEncode, Repeat, Slice, TryMapFrom,
+    Align, CountPrefixed, InspectBytes, Instrumented, Last, Length, MapErr, MapFrom, MaxBytes,
+    Optional, PadTo, PreEncode, RateLimited, Repeat, Slice, TryMapFrom,
 };
 use crate::io::IoEncodeExt;
+use crate::metrics::Recorder;
 use crate::tuple::TupleEncoder;
 use crate::{ByteCount, Eos, Error, ErrorKind, Result};
+use std::convert::TryFrom;
 
 /// This trait allows for encoding items into a byte sequence incrementally.
 pub trait Encode {
@@ -69,8 +75,24 @@ pub trait Encode {
     fn is_idle(&self) -> bool {
         self.requiring_bytes() == ByteCount::Finite(0)
     }
+
+    /// Resets the encoder to its initial state.
+    ///
+    /// This discards the items (and the encoded bytes) held by the encoder
+    /// so that the encoder can accept a new item (e.g., after an error or an abandoned output stream).
+    ///
+    /// If this method returns `Ok(())`, the encoder behaves as if it were newly created,
+    /// except that its settings (e.g., the limit of `MaxBytes` or the length of `Length`) are preserved.
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::Other`:
+    ///   - The encoder does not support resetting (this is the default implementation)
+    fn reset(&mut self) -> Result<()> {
+        track_panic!(ErrorKind::Other, "This encoder does not support `reset`")
+    }
 }
-impl<'a, E: ?Sized + Encode> Encode for &'a mut E {
+impl<E: ?Sized + Encode> Encode for &mut E {
     type Item = E::Item;
 
     fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
@@ -88,6 +110,10 @@ impl<'a, E: ?Sized + Encode> Encode for &'a mut E {
     fn is_idle(&self) -> bool {
         (**self).is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        (**self).reset()
+    }
 }
 impl<E: ?Sized + Encode> Encode for Box<E> {
     type Item = E::Item;
@@ -107,6 +133,10 @@ impl<E: ?Sized + Encode> Encode for Box<E> {
     fn is_idle(&self) -> bool {
         (**self).is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        (**self).reset()
+    }
 }
 
 /// This trait indicates that the encoder always knows the exact bytes required to encode remaining items.
@@ -114,7 +144,7 @@ pub trait SizedEncode: Encode {
     /// Returns the exact number of bytes required to encode all the items remaining in the encoder.
     fn exact_requiring_bytes(&self) -> u64;
 }
-impl<'a, E: ?Sized + SizedEncode> SizedEncode for &'a mut E {
+impl<E: ?Sized + SizedEncode> SizedEncode for &mut E {
     fn exact_requiring_bytes(&self) -> u64 {
         (**self).exact_requiring_bytes()
     }
@@ -158,22 +188,21 @@ pub trait EncodeExt: Encode + Sized {
     /// The following code shows the idiomatic way to track encoding errors:
     ///
     /// ```
-    /// use bytecodec::{Encode, EncodeExt, Eos};
+    /// use bytecodec::{Encode, EncodeExt, Eos, ErrorKind};
     /// use bytecodec::fixnum::U8Encoder;
-    /// use trackable::track;
+    /// use trackable::{track, Trackable};
     ///
     /// let encoder = U8Encoder::with_item(7).unwrap();
     /// let mut encoder = encoder.map_err(|e| track!(e, "oops!")); // or track_err!(encoder, "oops!")
     /// let error = track!(encoder.encode(&mut [][..], Eos::new(true))).err().unwrap();
     ///
-    /// assert_eq!(error.to_string(), "\
-    /// UnexpectedEos (cause; assertion failed: `!eos.is_reached()`; \
-    ///                buf.len()=0, size=0, self.offset=0, b.as_ref().len()=1)
-    /// HISTORY:
-    ///   [0] at src/bytes.rs:53
-    ///   [1] at src/fixnum.rs:116
-    ///   [2] at src/encode.rs:9 -- oops!
-    ///   [3] at src/encode.rs:10\n");
+    /// assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+    ///
+    /// // The history records every location the error passed through.
+    /// let events = error.history().unwrap().events();
+    /// let files = events.iter().map(|e| e.file()).collect::<Vec<_>>();
+    /// assert_eq!(files, ["src/bytes.rs", "src/fixnum.rs", "src/encode.rs", "src/encode.rs"]);
+    /// assert_eq!(events[2].message(), "oops!");
     /// ```
     fn map_err<E, F>(self, f: F) -> MapErr<Self, E, F>
     where
@@ -317,6 +346,54 @@ pub trait EncodeExt: Encode + Sized {
         Length::new(self, n)
     }
 
+    /// Creates an encoder that writes `pad_byte` after each item
+    /// so that the number of written bytes becomes a multiple of `alignment`.
+    ///
+    /// # Panics
+    ///
+    /// If `alignment` is `0`, this method will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Encode, EncodeExt};
+    /// use bytecodec::bytes::Utf8Encoder;
+    /// use bytecodec::io::IoEncodeExt;
+    ///
+    /// let mut output = Vec::new();
+    /// let mut encoder = Utf8Encoder::new().align(4, 0);
+    /// encoder.start_encoding("hello").unwrap();
+    /// encoder.encode_all(&mut output).unwrap();
+    /// assert_eq!(output, b"hello\x00\x00\x00");
+    /// ```
+    fn align(self, alignment: u64, pad_byte: u8) -> Align<Self> {
+        Align::new(self, alignment, pad_byte)
+    }
+
+    /// Creates an encoder that writes padding bytes after each item
+    /// so that exactly `total_size` bytes are written per item.
+    ///
+    /// The padding byte is `0` by default and can be changed by `PadTo::set_pad_byte`.
+    /// If an item exceeds `total_size` bytes, an `ErrorKind::InvalidInput` error will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Encode, EncodeExt};
+    /// use bytecodec::bytes::Utf8Encoder;
+    /// use bytecodec::io::IoEncodeExt;
+    ///
+    /// let mut output = Vec::new();
+    /// let mut encoder = Utf8Encoder::new().pad_to(8);
+    /// encoder.set_pad_byte(b' ');
+    /// encoder.start_encoding("hello").unwrap();
+    /// encoder.encode_all(&mut output).unwrap();
+    /// assert_eq!(output, b"hello   ");
+    /// ```
+    fn pad_to(self, total_size: u64) -> PadTo<Self> {
+        PadTo::new(self, total_size)
+    }
+
     /// Takes two encoders and creates a new encoder that encodes both items in sequence.
     ///
     /// This is equivalent to call `TupleEncoder::new((self, other))`.
@@ -346,6 +423,31 @@ pub trait EncodeExt: Encode + Sized {
         Repeat::new(self)
     }
 
+    /// Creates an encoder that encodes the number of items by using `count_encoder`
+    /// and then encodes the items by using `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Encode, EncodeExt};
+    /// use bytecodec::fixnum::{U16beEncoder, U8Encoder};
+    /// use bytecodec::io::IoEncodeExt;
+    ///
+    /// let mut output = Vec::new();
+    /// let mut encoder = U8Encoder::new().count_prefixed(U16beEncoder::new());
+    /// encoder.start_encoding(vec![1, 2, 3].into_iter()).unwrap();
+    /// encoder.encode_all(&mut output).unwrap();
+    /// assert_eq!(output, [0, 3, 1, 2, 3]);
+    /// ```
+    fn count_prefixed<I, N>(self, count_encoder: N) -> CountPrefixed<Repeat<Self, I>, N>
+    where
+        I: ExactSizeIterator<Item = Self::Item>,
+        N: Encode,
+        N::Item: TryFrom<usize>,
+    {
+        CountPrefixed::new(Repeat::new(self), count_encoder, usize::MAX)
+    }
+
     /// Creates an encoder that pre-encodes items when `start_encoding` method is called.
     ///
     /// Although the number of memory copies increases,
@@ -414,6 +516,127 @@ pub trait EncodeExt: Encode + Sized {
         Last::new(self, item)
     }
 
+    /// Creates an encoder that passes the exact bytes of each encoded item to `f`.
+    ///
+    /// The bytes are captured across multiple `encode` calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::EncodeExt;
+    /// use bytecodec::bytes::Utf8Encoder;
+    ///
+    /// let mut captured = Vec::new();
+    /// let mut encoder = Utf8Encoder::new().inspect_bytes(|bytes| captured.push(bytes.to_owned()));
+    /// encoder.encode_into_bytes("foo").unwrap();
+    /// encoder.encode_into_bytes("bar").unwrap();
+    ///
+    /// drop(encoder);
+    /// assert_eq!(captured, [b"foo", b"bar"]);
+    /// ```
+    fn inspect_bytes<F>(self, f: F) -> InspectBytes<Self, F>
+    where
+        F: FnMut(&[u8]),
+    {
+        InspectBytes::new(self, f)
+    }
+
+    /// Creates an encoder that limits the encoding throughput to `bytes_per_second`.
+    ///
+    /// See the documentation of `RateLimited` for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Encode, EncodeExt, Eos};
+    /// use bytecodec::bytes::Utf8Encoder;
+    /// use bytecodec::clock::ManualClock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// let mut encoder = Utf8Encoder::new().rate_limited(4, clock.clone());
+    /// encoder.start_encoding("foobar").unwrap();
+    ///
+    /// let mut output = [0; 6];
+    /// assert_eq!(encoder.encode(&mut output, Eos::new(true)).unwrap(), 4);
+    /// assert_eq!(encoder.encode(&mut output[4..], Eos::new(true)).unwrap(), 0);
+    ///
+    /// clock.advance(Duration::from_millis(500));
+    /// assert_eq!(encoder.encode(&mut output[4..], Eos::new(true)).unwrap(), 2);
+    /// assert_eq!(&output, b"foobar");
+    /// ```
+    fn rate_limited<K: Clock>(self, bytes_per_second: u64, clock: K) -> RateLimited<Self, K> {
+        RateLimited::new(self, bytes_per_second, clock)
+    }
+
+    /// Creates an encoder that records the metrics of the encoding processes to the given recorder.
+    ///
+    /// See the documentation of `Instrumented` for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::EncodeExt;
+    /// use bytecodec::bytes::Utf8Encoder;
+    /// use bytecodec::metrics::Counters;
+    ///
+    /// let counters = Counters::new();
+    /// let mut encoder = Utf8Encoder::new().instrumented(&counters);
+    /// assert_eq!(encoder.encode_into_bytes("foo").unwrap(), b"foo");
+    ///
+    /// assert_eq!(counters.bytes(), 3);
+    /// assert_eq!(counters.items(), 1);
+    /// ```
+    fn instrumented<R: Recorder>(self, recorder: R) -> Instrumented<Self, R> {
+        Instrumented::new(self, recorder, SystemClock::new())
+    }
+
+    /// Creates an encoder that records the metrics of the encoding processes to the given recorder,
+    /// measuring the item times by `clock`.
+    ///
+    /// See the documentation of `Instrumented` for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytecodec::{Encode, EncodeExt, Eos};
+    /// use bytecodec::bytes::Utf8Encoder;
+    /// use bytecodec::clock::ManualClock;
+    /// use bytecodec::metrics::Counters;
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// let counters = Counters::new();
+    /// let mut encoder = Utf8Encoder::new().instrumented_with_clock(&counters, clock.clone());
+    ///
+    /// let mut buf = [0; 6];
+    /// encoder.start_encoding("foobar").unwrap();
+    /// encoder.encode(&mut buf[..3], Eos::new(false)).unwrap();
+    /// clock.advance(Duration::from_millis(5));
+    /// encoder.encode(&mut buf[3..], Eos::new(false)).unwrap();
+    /// assert_eq!(&buf, b"foobar");
+    ///
+    /// assert_eq!(counters.items(), 1);
+    /// assert_eq!(counters.item_time(), Duration::from_millis(5));
+    /// ```
+    fn instrumented_with_clock<R: Recorder, K: Clock>(
+        self,
+        recorder: R,
+        clock: K,
+    ) -> Instrumented<Self, R, K> {
+        Instrumented::new(self, recorder, clock)
+    }
+
+    /// Creates an encoder that emits [tracing] spans and events about the encoding processes.
+    ///
+    /// See the documentation of `Traced` for the details.
+    ///
+    /// [tracing]: https://crates.io/crates/tracing
+    #[cfg(feature = "tracing")]
+    fn traced(self, name: &'static str) -> Traced<Self> {
+        Traced::new(self, name)
+    }
+
     /// Encodes the given item and returns the resulting bytes.
     ///
     /// # Examples
@@ -430,7 +653,7 @@ pub trait EncodeExt: Encode + Sized {
 
         match self.requiring_bytes() {
             ByteCount::Finite(size) => {
-                track_assert!(size <= std::usize::MAX as u64, ErrorKind::Other; size);
+                track_assert!(size <= usize::MAX as u64, ErrorKind::Other; size);
 
                 let mut buf = vec![0; size as usize];
                 track!(self.encode(&mut buf, Eos::new(true)))?;
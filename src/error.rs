@@ -1,9 +1,152 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Deref;
 use trackable::error::{ErrorKind as TrackableErrorKind, ErrorKindExt};
 use trackable::error::{Failure, TrackableError};
+use trackable::{History, Location, Trackable};
 
 /// This crate specific `Error` type.
-#[derive(Debug, Clone, TrackableError)]
-pub struct Error(TrackableError<ErrorKind>);
+///
+/// In addition to the trackable history, a decoding error can carry
+/// the byte offset where the failed decoding step began and
+/// the logical path of the decoders (e.g., `tuple.2 / collect[17] / U32beDecoder`).
+/// These are filled in automatically by the decoders and combinators provided by this crate.
+#[derive(Debug, Clone)]
+pub struct Error {
+    inner: TrackableError<ErrorKind>,
+
+    // Boxed to keep `Result` small; most errors are never annotated.
+    context: Option<Box<Context>>,
+}
+impl Error {
+    /// Returns the byte offset where the failed decoding step began.
+    ///
+    /// The offset is relative to the input of the outermost decoder that annotated this error.
+    /// If the error is returned by `IoDecodeExt` methods, it is the absolute position in the stream.
+    ///
+    /// If an item was decoded successfully but failed to be finished (e.g., invalid UTF-8),
+    /// the offset points to the beginning of the item
+    /// (or the beginning of the input given to the decoder if the item started in an earlier input).
+    pub fn offset(&self) -> Option<u64> {
+        self.context.as_ref().and_then(|c| c.offset)
+    }
+
+    /// Adds `n` to the offset of this error.
+    ///
+    /// This is intended to be called by a decoder that passes `&buf[n..]` to the inner decoder.
+    pub fn add_offset(&mut self, n: u64) {
+        let context = self.context_mut();
+        context.offset = Some(context.offset.unwrap_or(0) + n);
+    }
+
+    /// Adds `n` to the offset of this error and returns the resulting error.
+    pub fn with_offset(mut self, n: u64) -> Self {
+        self.add_offset(n);
+        self
+    }
+
+    /// Returns the logical path of the decoders where this error occurred.
+    ///
+    /// The segments are joined by `" / "` in the order from the outermost one.
+    pub fn path(&self) -> String {
+        self.path_segments().collect::<Vec<_>>().join(" / ")
+    }
+
+    /// Returns an iterator that iterates over the path segments from the outermost one.
+    pub fn path_segments(&self) -> impl Iterator<Item = &str> {
+        self.context
+            .iter()
+            .flat_map(|c| c.path.iter().rev())
+            .map(|s| s.as_ref())
+    }
+
+    /// Prepends a segment to the path of this error.
+    ///
+    /// This is intended to be called by a decoder that received the error from its inner decoder.
+    pub fn push_path<S>(&mut self, segment: S)
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.context_mut().path.push(segment.into());
+    }
+
+    /// Prepends a segment to the path of this error and returns the resulting error.
+    pub fn with_path<S>(mut self, segment: S) -> Self
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.push_path(segment);
+        self
+    }
+
+    fn context_mut(&mut self) -> &mut Context {
+        self.context.get_or_insert_with(Default::default)
+    }
+}
+impl Deref for Error {
+    type Target = TrackableError<ErrorKind>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let context = match self.context {
+            None => return self.inner.fmt(f),
+            Some(ref context) => context,
+        };
+
+        // The context is placed at the end of the first line (i.e., before the history).
+        let inner = self.inner.to_string();
+        let (head, tail) = inner.split_at(inner.find('\n').unwrap_or(inner.len()));
+        write!(f, "{} (", head)?;
+        if let Some(offset) = context.offset {
+            write!(f, "at offset {}", offset)?;
+            if !context.path.is_empty() {
+                write!(f, ", ")?;
+            }
+        }
+        if !context.path.is_empty() {
+            write!(f, "path: {}", self.path())?;
+        }
+        write!(f, "){}", tail)
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner.source()
+    }
+}
+impl Trackable for Error {
+    type Event = Location;
+
+    fn history(&self) -> Option<&History<Self::Event>> {
+        self.inner.history()
+    }
+
+    fn history_mut(&mut self) -> Option<&mut History<Self::Event>> {
+        self.inner.history_mut()
+    }
+}
+impl From<TrackableError<ErrorKind>> for Error {
+    fn from(f: TrackableError<ErrorKind>) -> Self {
+        Error {
+            inner: f,
+            context: None,
+        }
+    }
+}
+impl From<Error> for TrackableError<ErrorKind> {
+    fn from(f: Error) -> Self {
+        f.inner
+    }
+}
+impl From<ErrorKind> for Error {
+    fn from(f: ErrorKind) -> Self {
+        f.error().into()
+    }
+}
 impl From<Failure> for Error {
     fn from(f: Failure) -> Self {
         ErrorKind::Other.takes_over(f).into()
@@ -20,6 +163,12 @@ impl From<std::io::Error> for Error {
     }
 }
 
+#[derive(Debug, Default, Clone)]
+struct Context {
+    offset: Option<u64>,
+    path: Vec<Cow<'static, str>>,
+}
+
 /// Possible error kinds.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorKind {
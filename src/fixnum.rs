@@ -1,62 +1,8 @@
 //! Encoders and decoders for numbers which have fixed length binary representation.
 use crate::bytes::{BytesEncoder, CopyableBytesDecoder};
-use crate::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
-use byteorder::{BigEndian, ByteOrder, LittleEndian};
-
-macro_rules! impl_decode {
-    ($ty:ty, $item:ty) => {
-        impl Decode for $ty {
-            type Item = $item;
-
-            fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
-                track!(self.0.decode(buf, eos))
-            }
-
-            fn finish_decoding(&mut self) -> Result<Self::Item> {
-                track!(self.0.finish_decoding()).map(Self::decode_item)
-            }
-
-            fn requiring_bytes(&self) -> ByteCount {
-                self.0.requiring_bytes()
-            }
-
-            fn is_idle(&self) -> bool {
-                self.0.is_idle()
-            }
-        }
-    };
-}
-
-macro_rules! impl_encode {
-    ($ty:ty, $item:ty) => {
-        impl Encode for $ty {
-            type Item = $item;
-
-            fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
-                track!(self.0.encode(buf, eos))
-            }
-
-            fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
-                let mut b = Default::default();
-                track!(Self::encode_item(item, &mut b))?;
-                track!(self.0.start_encoding(b))
-            }
-
-            fn requiring_bytes(&self) -> ByteCount {
-                self.0.requiring_bytes()
-            }
-
-            fn is_idle(&self) -> bool {
-                self.0.is_idle()
-            }
-        }
-        impl SizedEncode for $ty {
-            fn exact_requiring_bytes(&self) -> u64 {
-                self.0.exact_requiring_bytes()
-            }
-        }
-    };
-}
+use crate::{ByteCount, Decode, Encode, Eos, Error, ErrorKind, Result, SizedEncode};
+use byteorder::ByteOrder as _;
+use std::marker::PhantomData;
 
 /// Decoder which decodes `u8` values.
 ///
@@ -83,7 +29,7 @@ impl U8Decoder {
         b[0]
     }
 }
-impl_decode!(U8Decoder, u8);
+impl_newtype_decode!(U8Decoder, u8);
 
 /// Encoder which encodes `u8` values.
 ///
@@ -113,7 +59,7 @@ impl U8Encoder {
         Ok(())
     }
 }
-impl_encode!(U8Encoder, u8);
+impl_newtype_encode!(U8Encoder, u8, buf);
 
 /// Decoder which decodes `i8` values.
 ///
@@ -140,7 +86,7 @@ impl I8Decoder {
         b[0] as i8
     }
 }
-impl_decode!(I8Decoder, i8);
+impl_newtype_decode!(I8Decoder, i8);
 
 /// Encoder which encodes `i8` values.
 ///
@@ -170,7 +116,7 @@ impl I8Encoder {
         Ok(())
     }
 }
-impl_encode!(I8Encoder, i8);
+impl_newtype_encode!(I8Encoder, i8, buf);
 
 /// Decoder which decodes `u16` values by big-endian byte order.
 ///
@@ -194,10 +140,10 @@ impl U16beDecoder {
     }
 
     fn decode_item(b: [u8; 2]) -> u16 {
-        BigEndian::read_u16(&b)
+        byteorder::BigEndian::read_u16(&b)
     }
 }
-impl_decode!(U16beDecoder, u16);
+impl_newtype_decode!(U16beDecoder, u16);
 
 /// Decoder which decodes `u16` values by little-endian byte order.
 ///
@@ -221,10 +167,10 @@ impl U16leDecoder {
     }
 
     fn decode_item(b: [u8; 2]) -> u16 {
-        LittleEndian::read_u16(&b)
+        byteorder::LittleEndian::read_u16(&b)
     }
 }
-impl_decode!(U16leDecoder, u16);
+impl_newtype_decode!(U16leDecoder, u16);
 
 /// Encoder which encodes `u16` values by big-endian byte order.
 ///
@@ -250,11 +196,11 @@ impl U16beEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: u16, b: &mut [u8; 2]) -> Result<()> {
-        BigEndian::write_u16(b, n);
+        byteorder::BigEndian::write_u16(b, n);
         Ok(())
     }
 }
-impl_encode!(U16beEncoder, u16);
+impl_newtype_encode!(U16beEncoder, u16, buf);
 
 /// Encoder which encodes `u16` values by little-endian byte order.
 ///
@@ -280,11 +226,11 @@ impl U16leEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: u16, b: &mut [u8; 2]) -> Result<()> {
-        LittleEndian::write_u16(b, n);
+        byteorder::LittleEndian::write_u16(b, n);
         Ok(())
     }
 }
-impl_encode!(U16leEncoder, u16);
+impl_newtype_encode!(U16leEncoder, u16, buf);
 
 /// Decoder which decodes `i16` values by big-endian byte order.
 ///
@@ -308,10 +254,10 @@ impl I16beDecoder {
     }
 
     fn decode_item(b: [u8; 2]) -> i16 {
-        BigEndian::read_i16(&b)
+        byteorder::BigEndian::read_i16(&b)
     }
 }
-impl_decode!(I16beDecoder, i16);
+impl_newtype_decode!(I16beDecoder, i16);
 
 /// Decoder which decodes `i16` values by little-endian byte order.
 ///
@@ -335,10 +281,10 @@ impl I16leDecoder {
     }
 
     fn decode_item(b: [u8; 2]) -> i16 {
-        LittleEndian::read_i16(&b)
+        byteorder::LittleEndian::read_i16(&b)
     }
 }
-impl_decode!(I16leDecoder, i16);
+impl_newtype_decode!(I16leDecoder, i16);
 
 /// Encoder which encodes `i16` values by big-endian byte order.
 ///
@@ -364,11 +310,11 @@ impl I16beEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: i16, b: &mut [u8; 2]) -> Result<()> {
-        BigEndian::write_i16(b, n);
+        byteorder::BigEndian::write_i16(b, n);
         Ok(())
     }
 }
-impl_encode!(I16beEncoder, i16);
+impl_newtype_encode!(I16beEncoder, i16, buf);
 
 /// Encoder which encodes `i16` values by little-endian byte order.
 ///
@@ -394,11 +340,11 @@ impl I16leEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: i16, b: &mut [u8; 2]) -> Result<()> {
-        LittleEndian::write_i16(b, n);
+        byteorder::LittleEndian::write_i16(b, n);
         Ok(())
     }
 }
-impl_encode!(I16leEncoder, i16);
+impl_newtype_encode!(I16leEncoder, i16, buf);
 
 /// Decoder which decodes unsigned 24-bit integers by big-endian byte order.
 ///
@@ -424,10 +370,10 @@ impl U24beDecoder {
     }
 
     fn decode_item(b: [u8; 3]) -> u32 {
-        BigEndian::read_u24(&b)
+        byteorder::BigEndian::read_u24(&b)
     }
 }
-impl_decode!(U24beDecoder, u32);
+impl_newtype_decode!(U24beDecoder, u32);
 
 /// Decoder which decodes unsigned 24-bit integers by little-endian byte order.
 ///
@@ -453,10 +399,10 @@ impl U24leDecoder {
     }
 
     fn decode_item(b: [u8; 3]) -> u32 {
-        LittleEndian::read_u24(&b)
+        byteorder::LittleEndian::read_u24(&b)
     }
 }
-impl_decode!(U24leDecoder, u32);
+impl_newtype_decode!(U24leDecoder, u32);
 
 /// Encoder which encodes unsigned 24-bit integers by big-endian byte order.
 ///
@@ -484,11 +430,11 @@ impl U24beEncoder {
 
     fn encode_item(n: u32, b: &mut [u8; 3]) -> Result<()> {
         track_assert!(n <= 0xFF_FFFF, ErrorKind::InvalidInput);
-        BigEndian::write_u24(b, n);
+        byteorder::BigEndian::write_u24(b, n);
         Ok(())
     }
 }
-impl_encode!(U24beEncoder, u32);
+impl_newtype_encode!(U24beEncoder, u32, buf);
 
 /// Encoder which encodes unsigned 24-bit integers by little-endian byte order.
 ///
@@ -516,11 +462,11 @@ impl U24leEncoder {
 
     fn encode_item(n: u32, b: &mut [u8; 3]) -> Result<()> {
         track_assert!(n <= 0xFF_FFFF, ErrorKind::InvalidInput);
-        LittleEndian::write_u24(b, n);
+        byteorder::LittleEndian::write_u24(b, n);
         Ok(())
     }
 }
-impl_encode!(U24leEncoder, u32);
+impl_newtype_encode!(U24leEncoder, u32, buf);
 
 /// Decoder which decodes `u32` values by big-endian byte order.
 ///
@@ -544,10 +490,10 @@ impl U32beDecoder {
     }
 
     fn decode_item(b: [u8; 4]) -> u32 {
-        BigEndian::read_u32(&b)
+        byteorder::BigEndian::read_u32(&b)
     }
 }
-impl_decode!(U32beDecoder, u32);
+impl_newtype_decode!(U32beDecoder, u32);
 
 /// Decoder which decodes `u32` values by little-endian byte order.
 ///
@@ -571,10 +517,10 @@ impl U32leDecoder {
     }
 
     fn decode_item(b: [u8; 4]) -> u32 {
-        LittleEndian::read_u32(&b)
+        byteorder::LittleEndian::read_u32(&b)
     }
 }
-impl_decode!(U32leDecoder, u32);
+impl_newtype_decode!(U32leDecoder, u32);
 
 /// Encoder which encodes `u32` values by big-endian byte order.
 ///
@@ -600,11 +546,11 @@ impl U32beEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: u32, b: &mut [u8; 4]) -> Result<()> {
-        BigEndian::write_u32(b, n);
+        byteorder::BigEndian::write_u32(b, n);
         Ok(())
     }
 }
-impl_encode!(U32beEncoder, u32);
+impl_newtype_encode!(U32beEncoder, u32, buf);
 
 /// Encoder which encodes `u32` values by little-endian byte order.
 ///
@@ -630,11 +576,11 @@ impl U32leEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: u32, b: &mut [u8; 4]) -> Result<()> {
-        LittleEndian::write_u32(b, n);
+        byteorder::LittleEndian::write_u32(b, n);
         Ok(())
     }
 }
-impl_encode!(U32leEncoder, u32);
+impl_newtype_encode!(U32leEncoder, u32, buf);
 
 /// Decoder which decodes `i32` values by big-endian byte order.
 ///
@@ -658,10 +604,10 @@ impl I32beDecoder {
     }
 
     fn decode_item(b: [u8; 4]) -> i32 {
-        BigEndian::read_i32(&b)
+        byteorder::BigEndian::read_i32(&b)
     }
 }
-impl_decode!(I32beDecoder, i32);
+impl_newtype_decode!(I32beDecoder, i32);
 
 /// Decoder which decodes `i32` values by little-endian byte order.
 ///
@@ -685,10 +631,10 @@ impl I32leDecoder {
     }
 
     fn decode_item(b: [u8; 4]) -> i32 {
-        LittleEndian::read_i32(&b)
+        byteorder::LittleEndian::read_i32(&b)
     }
 }
-impl_decode!(I32leDecoder, i32);
+impl_newtype_decode!(I32leDecoder, i32);
 
 /// Encoder which encodes `i32` values by big-endian byte order.
 ///
@@ -714,11 +660,11 @@ impl I32beEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: i32, b: &mut [u8; 4]) -> Result<()> {
-        BigEndian::write_i32(b, n);
+        byteorder::BigEndian::write_i32(b, n);
         Ok(())
     }
 }
-impl_encode!(I32beEncoder, i32);
+impl_newtype_encode!(I32beEncoder, i32, buf);
 
 /// Encoder which encodes `i32` values by little-endian byte order.
 ///
@@ -744,11 +690,11 @@ impl I32leEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: i32, b: &mut [u8; 4]) -> Result<()> {
-        LittleEndian::write_i32(b, n);
+        byteorder::LittleEndian::write_i32(b, n);
         Ok(())
     }
 }
-impl_encode!(I32leEncoder, i32);
+impl_newtype_encode!(I32leEncoder, i32, buf);
 
 /// Decoder which decodes unsigned 40-bit integers by big-endian byte order.
 ///
@@ -774,10 +720,10 @@ impl U40beDecoder {
     }
 
     fn decode_item(b: [u8; 5]) -> u64 {
-        BigEndian::read_uint(&b, b.len())
+        byteorder::BigEndian::read_uint(&b, b.len())
     }
 }
-impl_decode!(U40beDecoder, u64);
+impl_newtype_decode!(U40beDecoder, u64);
 
 /// Decoder which decodes unsigned 40-bit integers by little-endian byte order.
 ///
@@ -803,10 +749,10 @@ impl U40leDecoder {
     }
 
     fn decode_item(b: [u8; 5]) -> u64 {
-        LittleEndian::read_uint(&b, b.len())
+        byteorder::LittleEndian::read_uint(&b, b.len())
     }
 }
-impl_decode!(U40leDecoder, u64);
+impl_newtype_decode!(U40leDecoder, u64);
 
 /// Encoder which encodes unsigned 40-bit integers by big-endian byte order.
 ///
@@ -835,11 +781,11 @@ impl U40beEncoder {
     fn encode_item(n: u64, b: &mut [u8; 5]) -> Result<()> {
         track_assert!(n <= 0xFF_FFFF_FFFF, ErrorKind::InvalidInput);
         let len = b.len();
-        BigEndian::write_uint(b, n, len);
+        byteorder::BigEndian::write_uint(b, n, len);
         Ok(())
     }
 }
-impl_encode!(U40beEncoder, u64);
+impl_newtype_encode!(U40beEncoder, u64, buf);
 
 /// Encoder which encodes unsigned 40-bit integers by little-endian byte order.
 ///
@@ -868,11 +814,11 @@ impl U40leEncoder {
     fn encode_item(n: u64, b: &mut [u8; 5]) -> Result<()> {
         track_assert!(n <= 0xFF_FFFF_FFFF, ErrorKind::InvalidInput);
         let len = b.len();
-        LittleEndian::write_uint(b, n, len);
+        byteorder::LittleEndian::write_uint(b, n, len);
         Ok(())
     }
 }
-impl_encode!(U40leEncoder, u64);
+impl_newtype_encode!(U40leEncoder, u64, buf);
 
 /// Decoder which decodes unsigned 48-bit integers by big-endian byte order.
 ///
@@ -898,10 +844,10 @@ impl U48beDecoder {
     }
 
     fn decode_item(b: [u8; 6]) -> u64 {
-        BigEndian::read_uint(&b, b.len())
+        byteorder::BigEndian::read_uint(&b, b.len())
     }
 }
-impl_decode!(U48beDecoder, u64);
+impl_newtype_decode!(U48beDecoder, u64);
 
 /// Decoder which decodes unsigned 48-bit integers by little-endian byte order.
 ///
@@ -927,10 +873,10 @@ impl U48leDecoder {
     }
 
     fn decode_item(b: [u8; 6]) -> u64 {
-        LittleEndian::read_uint(&b, b.len())
+        byteorder::LittleEndian::read_uint(&b, b.len())
     }
 }
-impl_decode!(U48leDecoder, u64);
+impl_newtype_decode!(U48leDecoder, u64);
 
 /// Encoder which encodes unsigned 48-bit integers by big-endian byte order.
 ///
@@ -959,11 +905,11 @@ impl U48beEncoder {
     fn encode_item(n: u64, b: &mut [u8; 6]) -> Result<()> {
         track_assert!(n <= 0xFFFF_FFFF_FFFF, ErrorKind::InvalidInput);
         let len = b.len();
-        BigEndian::write_uint(b, n, len);
+        byteorder::BigEndian::write_uint(b, n, len);
         Ok(())
     }
 }
-impl_encode!(U48beEncoder, u64);
+impl_newtype_encode!(U48beEncoder, u64, buf);
 
 /// Encoder which encodes unsigned 48-bit integers by little-endian byte order.
 ///
@@ -992,11 +938,11 @@ impl U48leEncoder {
     fn encode_item(n: u64, b: &mut [u8; 6]) -> Result<()> {
         track_assert!(n <= 0xFFFF_FFFF_FFFF, ErrorKind::InvalidInput);
         let len = b.len();
-        LittleEndian::write_uint(b, n, len);
+        byteorder::LittleEndian::write_uint(b, n, len);
         Ok(())
     }
 }
-impl_encode!(U48leEncoder, u64);
+impl_newtype_encode!(U48leEncoder, u64, buf);
 
 /// Decoder which decodes unsigned 56-bit integers by big-endian byte order.
 ///
@@ -1022,10 +968,10 @@ impl U56beDecoder {
     }
 
     fn decode_item(b: [u8; 7]) -> u64 {
-        BigEndian::read_uint(&b, b.len())
+        byteorder::BigEndian::read_uint(&b, b.len())
     }
 }
-impl_decode!(U56beDecoder, u64);
+impl_newtype_decode!(U56beDecoder, u64);
 
 /// Decoder which decodes unsigned 56-bit integers by little-endian byte order.
 ///
@@ -1051,10 +997,10 @@ impl U56leDecoder {
     }
 
     fn decode_item(b: [u8; 7]) -> u64 {
-        LittleEndian::read_uint(&b, b.len())
+        byteorder::LittleEndian::read_uint(&b, b.len())
     }
 }
-impl_decode!(U56leDecoder, u64);
+impl_newtype_decode!(U56leDecoder, u64);
 
 /// Encoder which encodes unsigned 56-bit integers by big-endian byte order.
 ///
@@ -1083,11 +1029,11 @@ impl U56beEncoder {
     fn encode_item(n: u64, b: &mut [u8; 7]) -> Result<()> {
         track_assert!(n <= 0xFF_FFFF_FFFF_FFFF, ErrorKind::InvalidInput);
         let len = b.len();
-        BigEndian::write_uint(b, n, len);
+        byteorder::BigEndian::write_uint(b, n, len);
         Ok(())
     }
 }
-impl_encode!(U56beEncoder, u64);
+impl_newtype_encode!(U56beEncoder, u64, buf);
 
 /// Encoder which encodes unsigned 56-bit integers by little-endian byte order.
 ///
@@ -1116,11 +1062,11 @@ impl U56leEncoder {
     fn encode_item(n: u64, b: &mut [u8; 7]) -> Result<()> {
         track_assert!(n <= 0xFF_FFFF_FFFF_FFFF, ErrorKind::InvalidInput);
         let len = b.len();
-        LittleEndian::write_uint(b, n, len);
+        byteorder::LittleEndian::write_uint(b, n, len);
         Ok(())
     }
 }
-impl_encode!(U56leEncoder, u64);
+impl_newtype_encode!(U56leEncoder, u64, buf);
 
 /// Decoder which decodes `u64` values by big-endian byte order.
 ///
@@ -1144,10 +1090,10 @@ impl U64beDecoder {
     }
 
     fn decode_item(b: [u8; 8]) -> u64 {
-        BigEndian::read_u64(&b)
+        byteorder::BigEndian::read_u64(&b)
     }
 }
-impl_decode!(U64beDecoder, u64);
+impl_newtype_decode!(U64beDecoder, u64);
 
 /// Decoder which decodes `u64` values by little-endian byte order.
 ///
@@ -1171,10 +1117,10 @@ impl U64leDecoder {
     }
 
     fn decode_item(b: [u8; 8]) -> u64 {
-        LittleEndian::read_u64(&b)
+        byteorder::LittleEndian::read_u64(&b)
     }
 }
-impl_decode!(U64leDecoder, u64);
+impl_newtype_decode!(U64leDecoder, u64);
 
 /// Encoder which encodes `u64` values by big-endian byte order.
 ///
@@ -1200,11 +1146,11 @@ impl U64beEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: u64, b: &mut [u8; 8]) -> Result<()> {
-        BigEndian::write_u64(b, n);
+        byteorder::BigEndian::write_u64(b, n);
         Ok(())
     }
 }
-impl_encode!(U64beEncoder, u64);
+impl_newtype_encode!(U64beEncoder, u64, buf);
 
 /// Encoder which encodes `u64` values by big-endian byte order.
 ///
@@ -1230,11 +1176,11 @@ impl U64leEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: u64, b: &mut [u8; 8]) -> Result<()> {
-        LittleEndian::write_u64(b, n);
+        byteorder::LittleEndian::write_u64(b, n);
         Ok(())
     }
 }
-impl_encode!(U64leEncoder, u64);
+impl_newtype_encode!(U64leEncoder, u64, buf);
 
 /// Decoder which decodes `i64` values by big-endian byte order.
 ///
@@ -1258,10 +1204,10 @@ impl I64beDecoder {
     }
 
     fn decode_item(b: [u8; 8]) -> i64 {
-        BigEndian::read_i64(&b)
+        byteorder::BigEndian::read_i64(&b)
     }
 }
-impl_decode!(I64beDecoder, i64);
+impl_newtype_decode!(I64beDecoder, i64);
 
 /// Decoder which decodes `i64` values by little-endian byte order.
 ///
@@ -1285,10 +1231,10 @@ impl I64leDecoder {
     }
 
     fn decode_item(b: [u8; 8]) -> i64 {
-        LittleEndian::read_i64(&b)
+        byteorder::LittleEndian::read_i64(&b)
     }
 }
-impl_decode!(I64leDecoder, i64);
+impl_newtype_decode!(I64leDecoder, i64);
 
 /// Encoder which encodes `i64` values by big-endian byte order.
 ///
@@ -1314,11 +1260,11 @@ impl I64beEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: i64, b: &mut [u8; 8]) -> Result<()> {
-        BigEndian::write_i64(b, n);
+        byteorder::BigEndian::write_i64(b, n);
         Ok(())
     }
 }
-impl_encode!(I64beEncoder, i64);
+impl_newtype_encode!(I64beEncoder, i64, buf);
 
 /// Encoder which encodes `i64` values by little-endian byte order.
 ///
@@ -1344,11 +1290,11 @@ impl I64leEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: i64, b: &mut [u8; 8]) -> Result<()> {
-        LittleEndian::write_i64(b, n);
+        byteorder::LittleEndian::write_i64(b, n);
         Ok(())
     }
 }
-impl_encode!(I64leEncoder, i64);
+impl_newtype_encode!(I64leEncoder, i64, buf);
 
 /// Decoder which decodes `f32` values by big-endian byte order.
 ///
@@ -1372,10 +1318,10 @@ impl F32beDecoder {
     }
 
     fn decode_item(b: [u8; 4]) -> f32 {
-        BigEndian::read_f32(&b)
+        byteorder::BigEndian::read_f32(&b)
     }
 }
-impl_decode!(F32beDecoder, f32);
+impl_newtype_decode!(F32beDecoder, f32);
 
 /// Decoder which decodes `f32` values by little-endian byte order.
 ///
@@ -1399,10 +1345,10 @@ impl F32leDecoder {
     }
 
     fn decode_item(b: [u8; 4]) -> f32 {
-        LittleEndian::read_f32(&b)
+        byteorder::LittleEndian::read_f32(&b)
     }
 }
-impl_decode!(F32leDecoder, f32);
+impl_newtype_decode!(F32leDecoder, f32);
 
 /// Encoder which encodes `f32` values by big-endian byte order.
 ///
@@ -1428,11 +1374,11 @@ impl F32beEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: f32, b: &mut [u8; 4]) -> Result<()> {
-        BigEndian::write_f32(b, n);
+        byteorder::BigEndian::write_f32(b, n);
         Ok(())
     }
 }
-impl_encode!(F32beEncoder, f32);
+impl_newtype_encode!(F32beEncoder, f32, buf);
 
 /// Encoder which encodes `f32` values by little-endian byte order.
 ///
@@ -1458,11 +1404,11 @@ impl F32leEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: f32, b: &mut [u8; 4]) -> Result<()> {
-        LittleEndian::write_f32(b, n);
+        byteorder::LittleEndian::write_f32(b, n);
         Ok(())
     }
 }
-impl_encode!(F32leEncoder, f32);
+impl_newtype_encode!(F32leEncoder, f32, buf);
 
 /// Decoder which decodes `f64` values by big-endian byte order.
 ///
@@ -1486,10 +1432,10 @@ impl F64beDecoder {
     }
 
     fn decode_item(b: [u8; 8]) -> f64 {
-        BigEndian::read_f64(&b)
+        byteorder::BigEndian::read_f64(&b)
     }
 }
-impl_decode!(F64beDecoder, f64);
+impl_newtype_decode!(F64beDecoder, f64);
 
 /// Decoder which decodes `f64` values by little-endian byte order.
 ///
@@ -1513,10 +1459,10 @@ impl F64leDecoder {
     }
 
     fn decode_item(b: [u8; 8]) -> f64 {
-        LittleEndian::read_f64(&b)
+        byteorder::LittleEndian::read_f64(&b)
     }
 }
-impl_decode!(F64leDecoder, f64);
+impl_newtype_decode!(F64leDecoder, f64);
 
 /// Encoder which encodes `f64` values by big-endian byte order.
 ///
@@ -1542,11 +1488,11 @@ impl F64beEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: f64, b: &mut [u8; 8]) -> Result<()> {
-        BigEndian::write_f64(b, n);
+        byteorder::BigEndian::write_f64(b, n);
         Ok(())
     }
 }
-impl_encode!(F64beEncoder, f64);
+impl_newtype_encode!(F64beEncoder, f64, buf);
 
 /// Encoder which encodes `f64` values by little-endian byte order.
 ///
@@ -1572,27 +1518,703 @@ impl F64leEncoder {
 
     #[allow(clippy::unnecessary_wraps)]
     fn encode_item(n: f64, b: &mut [u8; 8]) -> Result<()> {
-        LittleEndian::write_f64(b, n);
+        byteorder::LittleEndian::write_f64(b, n);
+        Ok(())
+    }
+}
+impl_newtype_encode!(F64leEncoder, f64, buf);
+
+/// Decoder which decodes `bool` values.
+///
+/// `0` and `1` are decoded as `false` and `true` respectively.
+/// Other values are rejected as `ErrorKind::InvalidInput`.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Decode;
+/// use bytecodec::fixnum::BoolDecoder;
+/// use bytecodec::io::IoDecodeExt;
+///
+/// let mut decoder = BoolDecoder::new();
+/// assert_eq!(decoder.decode_exact([1].as_ref()).unwrap(), true);
+/// assert!(decoder.decode_exact([2].as_ref()).is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct BoolDecoder(U8Decoder);
+impl BoolDecoder {
+    /// Makes a new `BoolDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for BoolDecoder {
+    type Item = bool;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self
+            .0
+            .decode(buf, eos)
+            .map_err(|e| e.with_path("BoolDecoder")))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let b = track!(self
+            .0
+            .finish_decoding()
+            .map_err(|e| e.with_path("BoolDecoder")))?;
+        match b {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(track!(
+                Error::from(ErrorKind::InvalidInput).with_path("BoolDecoder"),
+                "Not a boolean value";
+                b
+            )),
+        }
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+
+/// Encoder which encodes `bool` values.
+///
+/// `false` and `true` are encoded as `0` and `1` respectively.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::fixnum::BoolEncoder;
+/// use bytecodec::io::IoEncodeExt;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = BoolEncoder::with_item(true).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [1]);
+/// ```
+#[derive(Debug, Default)]
+pub struct BoolEncoder(BytesEncoder<[u8; 1]>);
+impl BoolEncoder {
+    /// Makes a new `BoolEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn encode_item(b: bool, buf: &mut [u8; 1]) -> Result<()> {
+        buf[0] = b as u8;
+        Ok(())
+    }
+}
+impl_newtype_encode!(BoolEncoder, bool, buf);
+
+/// Numeric types which can be handled by `IntDecoder` and `IntEncoder` (and their `Dyn*` variants).
+///
+/// This trait is implemented for the primitive integer types (from `u8` to `i128`),
+/// for the marker types of odd widths (e.g., `U24` and `I56`),
+/// and for the floating point types (`f32` and `f64`).
+/// It is sealed and cannot be implemented outside of this crate.
+pub trait Number: private::Sealed {
+    /// The type of values.
+    type Value;
+
+    /// The number of bytes of the binary representation.
+    const SIZE: usize;
+
+    #[doc(hidden)]
+    fn read(buf: &[u8], byte_order: Endianness) -> Self::Value;
+
+    #[doc(hidden)]
+    fn write(buf: &mut [u8], n: Self::Value, byte_order: Endianness) -> Result<()>;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_number {
+    ($ty:ty, $value:ty, $size:expr, $read:ident, $write:ident) => {
+        impl private::Sealed for $ty {}
+        impl Number for $ty {
+            type Value = $value;
+            const SIZE: usize = $size;
+
+            fn read(buf: &[u8], byte_order: Endianness) -> Self::Value {
+                match byte_order {
+                    Endianness::Big => byteorder::BigEndian::$read(buf),
+                    Endianness::Little => byteorder::LittleEndian::$read(buf),
+                }
+            }
+
+            fn write(buf: &mut [u8], n: Self::Value, byte_order: Endianness) -> Result<()> {
+                match byte_order {
+                    Endianness::Big => byteorder::BigEndian::$write(buf, n),
+                    Endianness::Little => byteorder::LittleEndian::$write(buf, n),
+                }
+                Ok(())
+            }
+        }
+    };
+    ($ty:ty, $value:ty, $size:expr, $read:ident, $write:ident, $min:expr, $max:expr) => {
+        impl private::Sealed for $ty {}
+        impl Number for $ty {
+            type Value = $value;
+            const SIZE: usize = $size;
+
+            fn read(buf: &[u8], byte_order: Endianness) -> Self::Value {
+                let n = match byte_order {
+                    Endianness::Big => byteorder::BigEndian::$read(buf, $size),
+                    Endianness::Little => byteorder::LittleEndian::$read(buf, $size),
+                };
+                n as $value
+            }
+
+            fn write(buf: &mut [u8], n: Self::Value, byte_order: Endianness) -> Result<()> {
+                track_assert!(($min..=$max).contains(&n), ErrorKind::InvalidInput,
+                              "Out of range"; n, $size);
+                match byte_order {
+                    Endianness::Big => byteorder::BigEndian::$write(buf, n.into(), $size),
+                    Endianness::Little => byteorder::LittleEndian::$write(buf, n.into(), $size),
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Marker type for unsigned 24-bit integers (the type of values is `u32`).
+#[derive(Debug)]
+pub struct U24;
+
+/// Marker type for signed 24-bit integers (the type of values is `i32`).
+#[derive(Debug)]
+pub struct I24;
+
+/// Marker type for unsigned 40-bit integers (the type of values is `u64`).
+#[derive(Debug)]
+pub struct U40;
+
+/// Marker type for signed 40-bit integers (the type of values is `i64`).
+#[derive(Debug)]
+pub struct I40;
+
+/// Marker type for unsigned 48-bit integers (the type of values is `u64`).
+#[derive(Debug)]
+pub struct U48;
+
+/// Marker type for signed 48-bit integers (the type of values is `i64`).
+#[derive(Debug)]
+pub struct I48;
+
+/// Marker type for unsigned 56-bit integers (the type of values is `u64`).
+#[derive(Debug)]
+pub struct U56;
+
+/// Marker type for signed 56-bit integers (the type of values is `i64`).
+#[derive(Debug)]
+pub struct I56;
+
+impl private::Sealed for u8 {}
+impl Number for u8 {
+    type Value = u8;
+    const SIZE: usize = 1;
+
+    fn read(buf: &[u8], _byte_order: Endianness) -> Self::Value {
+        buf[0]
+    }
+
+    fn write(buf: &mut [u8], n: Self::Value, _byte_order: Endianness) -> Result<()> {
+        buf[0] = n;
         Ok(())
     }
 }
-impl_encode!(F64leEncoder, f64);
+impl private::Sealed for i8 {}
+impl Number for i8 {
+    type Value = i8;
+    const SIZE: usize = 1;
+
+    fn read(buf: &[u8], _byte_order: Endianness) -> Self::Value {
+        buf[0] as i8
+    }
+
+    fn write(buf: &mut [u8], n: Self::Value, _byte_order: Endianness) -> Result<()> {
+        buf[0] = n as u8;
+        Ok(())
+    }
+}
+impl_number!(u16, u16, 2, read_u16, write_u16);
+impl_number!(i16, i16, 2, read_i16, write_i16);
+impl_number!(u32, u32, 4, read_u32, write_u32);
+impl_number!(i32, i32, 4, read_i32, write_i32);
+impl_number!(u64, u64, 8, read_u64, write_u64);
+impl_number!(i64, i64, 8, read_i64, write_i64);
+impl_number!(u128, u128, 16, read_u128, write_u128);
+impl_number!(i128, i128, 16, read_i128, write_i128);
+impl_number!(f32, f32, 4, read_f32, write_f32);
+impl_number!(f64, f64, 8, read_f64, write_f64);
+impl_number!(U24, u32, 3, read_uint, write_uint, 0, 0xFF_FFFF);
+impl_number!(I24, i32, 3, read_int, write_int, -0x80_0000, 0x7F_FFFF);
+impl_number!(U40, u64, 5, read_uint, write_uint, 0, 0xFF_FFFF_FFFF);
+impl_number!(
+    I40,
+    i64,
+    5,
+    read_int,
+    write_int,
+    -0x80_0000_0000,
+    0x7F_FFFF_FFFF
+);
+impl_number!(U48, u64, 6, read_uint, write_uint, 0, 0xFFFF_FFFF_FFFF);
+impl_number!(
+    I48,
+    i64,
+    6,
+    read_int,
+    write_int,
+    -0x8000_0000_0000,
+    0x7FFF_FFFF_FFFF
+);
+impl_number!(U56, u64, 7, read_uint, write_uint, 0, 0xFF_FFFF_FFFF_FFFF);
+impl_number!(
+    I56,
+    i64,
+    7,
+    read_int,
+    write_int,
+    -0x80_0000_0000_0000,
+    0x7F_FFFF_FFFF_FFFF
+);
+
+/// Byte orders which can be specified statically to `IntDecoder` and `IntEncoder`.
+///
+/// This trait is implemented for `BigEndian` and `LittleEndian` (`NativeEndian` is an alias of either).
+/// It is sealed and cannot be implemented outside of this crate.
+pub trait ByteOrder: private::Sealed {
+    /// The byte order represented by this type.
+    const ENDIANNESS: Endianness;
+}
+
+/// Marker type for big-endian byte order (i.e., network byte order).
+#[derive(Debug)]
+pub struct BigEndian;
+impl private::Sealed for BigEndian {}
+impl ByteOrder for BigEndian {
+    const ENDIANNESS: Endianness = Endianness::Big;
+}
+
+/// Marker type for little-endian byte order.
+#[derive(Debug)]
+pub struct LittleEndian;
+impl private::Sealed for LittleEndian {}
+impl ByteOrder for LittleEndian {
+    const ENDIANNESS: Endianness = Endianness::Little;
+}
+
+/// Marker type for the byte order of the target platform.
+#[cfg(target_endian = "big")]
+pub type NativeEndian = BigEndian;
+
+/// Marker type for the byte order of the target platform.
+#[cfg(target_endian = "little")]
+pub type NativeEndian = LittleEndian;
+
+#[derive(Debug, Clone, Copy)]
+struct IntBytes {
+    bytes: [u8; 16],
+    len: usize,
+}
+impl IntBytes {
+    fn new(len: usize) -> Self {
+        IntBytes {
+            bytes: [0; 16],
+            len,
+        }
+    }
+}
+impl AsRef<[u8]> for IntBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+impl AsMut<[u8]> for IntBytes {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes[..self.len]
+    }
+}
+
+/// Decoder which decodes numbers of the type `T` by the byte order `B`.
+///
+/// `T` is one of the `Number` types, which include the floating point types
+/// (e.g., `IntDecoder<f32, BigEndian>` is equivalent to `F32beDecoder`).
+/// `B` is one of `BigEndian`, `LittleEndian` or `NativeEndian`.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::fixnum::{BigEndian, I24, IntDecoder, LittleEndian};
+/// use bytecodec::io::IoDecodeExt;
+///
+/// let mut decoder = IntDecoder::<u128, BigEndian>::new();
+/// let item = decoder.decode_exact([0xFF; 16].as_ref()).unwrap();
+/// assert_eq!(item, u128::MAX);
+///
+/// let mut decoder = IntDecoder::<I24, LittleEndian>::new();
+/// let item = decoder.decode_exact([0xFE, 0xFF, 0xFF].as_ref()).unwrap();
+/// assert_eq!(item, -2);
+/// ```
+#[derive(Debug)]
+pub struct IntDecoder<T, B> {
+    inner: CopyableBytesDecoder<IntBytes>,
+    _phantom: PhantomData<(T, B)>,
+}
+impl<T: Number, B: ByteOrder> IntDecoder<T, B> {
+    /// Makes a new `IntDecoder` instance.
+    pub fn new() -> Self {
+        IntDecoder {
+            inner: CopyableBytesDecoder::new(IntBytes::new(T::SIZE)),
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<T: Number, B: ByteOrder> Default for IntDecoder<T, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Number, B: ByteOrder> Decode for IntDecoder<T, B> {
+    type Item = T::Value;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self
+            .inner
+            .decode(buf, eos)
+            .map_err(|e| e.with_path("IntDecoder")))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let b = track!(self
+            .inner
+            .finish_decoding()
+            .map_err(|e| e.with_path("IntDecoder")))?;
+        Ok(T::read(b.as_ref(), B::ENDIANNESS))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+
+/// Encoder which encodes numbers of the type `T` by the byte order `B`.
+///
+/// `T` is one of the `Number` types, which include the floating point types
+/// (e.g., `IntEncoder<f32, BigEndian>` is equivalent to `F32beEncoder`).
+/// `B` is one of `BigEndian`, `LittleEndian` or `NativeEndian`.
+///
+/// If `T` is an odd-width type such as `U24`,
+/// values out of the range of the type will be rejected with an `ErrorKind::InvalidInput` error.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::fixnum::{BigEndian, I24, IntEncoder};
+/// use bytecodec::io::IoEncodeExt;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = IntEncoder::<I24, BigEndian>::with_item(-2).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0xFF, 0xFF, 0xFE]);
+///
+/// assert!(IntEncoder::<I24, BigEndian>::with_item(0x80_0000).is_err());
+/// ```
+#[derive(Debug)]
+pub struct IntEncoder<T, B> {
+    inner: BytesEncoder<IntBytes>,
+    _phantom: PhantomData<(T, B)>,
+}
+impl<T: Number, B: ByteOrder> IntEncoder<T, B> {
+    /// Makes a new `IntEncoder` instance.
+    pub fn new() -> Self {
+        IntEncoder {
+            inner: BytesEncoder::new(),
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<T: Number, B: ByteOrder> Default for IntEncoder<T, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Number, B: ByteOrder> Encode for IntEncoder<T, B> {
+    type Item = T::Value;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let mut b = IntBytes::new(T::SIZE);
+        track!(T::write(b.as_mut(), item, B::ENDIANNESS))?;
+        track!(self.inner.start_encoding(b))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+impl<T: Number, B: ByteOrder> SizedEncode for IntEncoder<T, B> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+
+/// Byte order which is selected at runtime.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Big-endian (i.e., network byte order).
+    #[default]
+    Big,
+
+    /// Little-endian.
+    Little,
+}
+impl Endianness {
+    /// The byte order of the target platform.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Endianness = Endianness::Big;
+
+    /// The byte order of the target platform.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Endianness = Endianness::Little;
+}
+
+/// Decoder which decodes numbers of the type `T` by the byte order selected at runtime.
+///
+/// This is useful for formats whose byte order is declared in a header (e.g., TIFF and ELF).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::fixnum::{DynIntDecoder, Endianness};
+/// use bytecodec::io::IoDecodeExt;
+///
+/// let mut decoder = DynIntDecoder::<u16>::new(Endianness::Big);
+/// let item = decoder.decode_exact([0x01, 0x02].as_ref()).unwrap();
+/// assert_eq!(item, 0x0102);
+///
+/// decoder.set_byte_order(Endianness::Little);
+/// let item = decoder.decode_exact([0x01, 0x02].as_ref()).unwrap();
+/// assert_eq!(item, 0x0201);
+/// ```
+#[derive(Debug)]
+pub struct DynIntDecoder<T> {
+    inner: CopyableBytesDecoder<IntBytes>,
+    byte_order: Endianness,
+    _phantom: PhantomData<T>,
+}
+impl<T: Number> DynIntDecoder<T> {
+    /// Makes a new `DynIntDecoder` instance that uses `byte_order`.
+    pub fn new(byte_order: Endianness) -> Self {
+        DynIntDecoder {
+            inner: CopyableBytesDecoder::new(IntBytes::new(T::SIZE)),
+            byte_order,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the byte order used by this decoder.
+    pub fn byte_order(&self) -> Endianness {
+        self.byte_order
+    }
+
+    /// Sets the byte order used by this decoder.
+    ///
+    /// The new byte order is applied from the item being decoded currently.
+    pub fn set_byte_order(&mut self, byte_order: Endianness) {
+        self.byte_order = byte_order;
+    }
+}
+impl<T: Number> Default for DynIntDecoder<T> {
+    fn default() -> Self {
+        Self::new(Endianness::default())
+    }
+}
+impl<T: Number> Decode for DynIntDecoder<T> {
+    type Item = T::Value;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self
+            .inner
+            .decode(buf, eos)
+            .map_err(|e| e.with_path("DynIntDecoder")))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let b = track!(self
+            .inner
+            .finish_decoding()
+            .map_err(|e| e.with_path("DynIntDecoder")))?;
+        Ok(T::read(b.as_ref(), self.byte_order))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+
+/// Encoder which encodes numbers of the type `T` by the byte order selected at runtime.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::fixnum::{DynIntEncoder, Endianness, U24};
+/// use bytecodec::io::IoEncodeExt;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = DynIntEncoder::<U24>::new(Endianness::Little);
+/// encoder.start_encoding(0x01_0203).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0x03, 0x02, 0x01]);
+/// ```
+#[derive(Debug)]
+pub struct DynIntEncoder<T> {
+    inner: BytesEncoder<IntBytes>,
+    byte_order: Endianness,
+    _phantom: PhantomData<T>,
+}
+impl<T: Number> DynIntEncoder<T> {
+    /// Makes a new `DynIntEncoder` instance that uses `byte_order`.
+    pub fn new(byte_order: Endianness) -> Self {
+        DynIntEncoder {
+            inner: BytesEncoder::new(),
+            byte_order,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the byte order used by this encoder.
+    pub fn byte_order(&self) -> Endianness {
+        self.byte_order
+    }
+
+    /// Sets the byte order used by this encoder.
+    ///
+    /// The new byte order is applied from the next item passed to `start_encoding`.
+    pub fn set_byte_order(&mut self, byte_order: Endianness) {
+        self.byte_order = byte_order;
+    }
+}
+impl<T: Number> Default for DynIntEncoder<T> {
+    fn default() -> Self {
+        Self::new(Endianness::default())
+    }
+}
+impl<T: Number> Encode for DynIntEncoder<T> {
+    type Item = T::Value;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let mut b = IntBytes::new(T::SIZE);
+        track!(T::write(b.as_mut(), item, self.byte_order))?;
+        track!(self.inner.start_encoding(b))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+impl<T: Number> SizedEncode for DynIntEncoder<T> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+
+/// Decoder which decodes `f32` values by the byte order selected at runtime.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::fixnum::{DynF32Decoder, Endianness};
+/// use bytecodec::io::IoDecodeExt;
+///
+/// let mut decoder = DynF32Decoder::new(Endianness::Little);
+/// let item = decoder.decode_exact([0x00, 0x00, 0x80, 0x3F].as_ref()).unwrap();
+/// assert_eq!(item, 1.0);
+/// ```
+pub type DynF32Decoder = DynIntDecoder<f32>;
+
+/// Encoder which encodes `f32` values by the byte order selected at runtime.
+pub type DynF32Encoder = DynIntEncoder<f32>;
+
+/// Decoder which decodes `f64` values by the byte order selected at runtime.
+pub type DynF64Decoder = DynIntDecoder<f64>;
+
+/// Encoder which encodes `f64` values by the byte order selected at runtime.
+pub type DynF64Encoder = DynIntEncoder<f64>;
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::io::{IoDecodeExt, IoEncodeExt};
-    use crate::Encode;
+    use crate::{DecodeExt, Encode, EncodeExt};
 
     macro_rules! assert_encode_decode {
-        ($encoder:ident, $decoder:ident, $item:expr, $bytes:expr) => {
+        ($encoder:ty, $decoder:ty, $item:expr, $bytes:expr) => {
             let mut output = Vec::new();
-            let mut encoder = $encoder::new();
+            let mut encoder = <$encoder>::new();
             track_try_unwrap!(encoder.start_encoding($item));
             track_try_unwrap!(encoder.encode_all(&mut output));
             assert_eq!(output, $bytes);
 
-            let mut decoder = $decoder::new();
+            let mut decoder = <$decoder>::new();
             let item = track_try_unwrap!(decoder.decode_exact(&$bytes[..]));
             assert_eq!(item, $item);
         };
@@ -1696,5 +2318,114 @@ mod test {
             -123.456,
             [119, 190, 159, 26, 47, 221, 94, 192]
         );
+        assert_encode_decode!(BoolEncoder, BoolDecoder, false, [0]);
+        assert_encode_decode!(BoolEncoder, BoolDecoder, true, [1]);
+
+        let mut decoder = BoolDecoder::new();
+        let error = decoder.decode_from_bytes(&[2]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.path(), "BoolDecoder");
+    }
+
+    #[test]
+    fn int_codecs_work() {
+        assert_encode_decode!(
+            IntEncoder<u128, LittleEndian>,
+            IntDecoder<u128, LittleEndian>,
+            0x0102,
+            [2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_encode_decode!(
+            IntEncoder<i128, BigEndian>,
+            IntDecoder<i128, BigEndian>,
+            -2,
+            [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE]
+        );
+        assert_encode_decode!(IntEncoder<i8, BigEndian>, IntDecoder<i8, BigEndian>, -1, [0xFF]);
+        assert_encode_decode!(
+            IntEncoder<I24, LittleEndian>,
+            IntDecoder<I24, LittleEndian>,
+            -0x80_0000,
+            [0x00, 0x00, 0x80]
+        );
+        assert_encode_decode!(
+            IntEncoder<I40, BigEndian>,
+            IntDecoder<I40, BigEndian>,
+            -2,
+            [0xFF, 0xFF, 0xFF, 0xFF, 0xFE]
+        );
+        assert_encode_decode!(
+            IntEncoder<U56, BigEndian>,
+            IntDecoder<U56, BigEndian>,
+            0x01_0203_0405_0607,
+            [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07]
+        );
+
+        assert!(IntEncoder::<U24, BigEndian>::with_item(0x100_0000).is_err());
+        assert!(IntEncoder::<I48, BigEndian>::with_item(-0x8000_0000_0001).is_err());
+
+        assert_encode_decode!(
+            IntEncoder<f32, BigEndian>,
+            IntDecoder<f32, BigEndian>,
+            -123.4,
+            [194, 246, 204, 205]
+        );
+
+        assert_eq!(NativeEndian::ENDIANNESS, Endianness::NATIVE);
+        assert_encode_decode!(
+            IntEncoder<u16, NativeEndian>,
+            IntDecoder<u16, NativeEndian>,
+            0x0102,
+            0x0102u16.to_ne_bytes()
+        );
+    }
+
+    #[test]
+    fn dyn_int_codecs_work() {
+        let mut output = Vec::new();
+        let mut encoder = DynIntEncoder::<i32>::new(Endianness::Little);
+        track_try_unwrap!(encoder.start_encoding(-2));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        encoder.set_byte_order(Endianness::Big);
+        track_try_unwrap!(encoder.start_encoding(-2));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE]);
+
+        let mut decoder = DynIntDecoder::<u32>::default();
+        let item = track_try_unwrap!(decoder.decode_exact(&output[..4]));
+        assert_eq!(item, 0xFEFF_FFFF);
+        decoder.set_byte_order(Endianness::NATIVE);
+        let item = track_try_unwrap!(decoder.decode_exact(&output[..4]));
+        assert_eq!(item, u32::from_ne_bytes([0xFE, 0xFF, 0xFF, 0xFF]));
+    }
+
+    #[test]
+    fn dyn_float_codecs_work() {
+        let mut output = Vec::new();
+        let mut encoder = DynF64Encoder::new(Endianness::Little);
+        track_try_unwrap!(encoder.start_encoding(-123.456));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [119, 190, 159, 26, 47, 221, 94, 192]);
+
+        let mut decoder = DynF64Decoder::new(Endianness::Little);
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_exact(&output[..])),
+            -123.456
+        );
+        decoder.set_byte_order(Endianness::Big);
+        output.reverse();
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_exact(&output[..])),
+            -123.456
+        );
+
+        let mut output = Vec::new();
+        let mut encoder = DynF32Encoder::new(Endianness::Big);
+        track_try_unwrap!(encoder.start_encoding(1.5));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [0x3F, 0xC0, 0x00, 0x00]);
+
+        let mut decoder = DynF32Decoder::default();
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&output[..])), 1.5);
     }
 }
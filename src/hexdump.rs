@@ -0,0 +1,77 @@
+//! Hexdump formatter for byte sequences.
+use std::fmt;
+
+const BYTES_PER_LINE: usize = 16;
+
+/// Formatter that shows a byte sequence in the canonical hex+ASCII format (like `hexdump -C`).
+///
+/// Each line is annotated with the offset of its first byte.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::hexdump::HexDump;
+///
+/// let dump = HexDump::with_base_offset(b"Hello, World!\x00\x01\x02\xff", 0x20);
+/// assert_eq!(
+///     dump.to_string(),
+///     "00000020  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 00 01 02  |Hello, World!...|\n\
+///      00000030  ff                                                |.|\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+    base_offset: u64,
+}
+impl<'a> HexDump<'a> {
+    /// Makes a new `HexDump` instance whose offsets start from `0`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self::with_base_offset(bytes, 0)
+    }
+
+    /// Makes a new `HexDump` instance whose offsets start from `base_offset`.
+    ///
+    /// This is useful to show the position of the bytes in the whole stream.
+    pub fn with_base_offset(bytes: &'a [u8], base_offset: u64) -> Self {
+        HexDump { bytes, base_offset }
+    }
+
+    /// Returns the bytes to be dumped.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the offset of the first byte.
+    pub fn base_offset(&self) -> u64 {
+        self.base_offset
+    }
+}
+impl fmt::Display for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, line) in self.bytes.chunks(BYTES_PER_LINE).enumerate() {
+            let offset = self.base_offset + (i * BYTES_PER_LINE) as u64;
+            write!(f, "{:08x} ", offset)?;
+            for j in 0..BYTES_PER_LINE {
+                if j % 8 == 0 {
+                    write!(f, " ")?;
+                }
+                match line.get(j) {
+                    Some(b) => write!(f, "{:02x} ", b)?,
+                    None => write!(f, "   ")?,
+                }
+            }
+            write!(f, " |")?;
+            for &b in line {
+                let c = if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f, "|")?;
+        }
+        Ok(())
+    }
+}
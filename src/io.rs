@@ -1,4 +1,5 @@
 //! I/O (i.e., `Read` and `Write` traits) related module.
+use crate::metrics::{Counters, Recorder};
 use crate::{ByteCount, Decode, Encode, Eos, Error, ErrorKind, Result};
 #[cfg(feature = "tokio-async")]
 use pin_project::pin_project;
@@ -13,12 +14,11 @@ pub trait IoDecodeExt: Decode {
         B: AsRef<[u8]>,
     {
         let eos = Eos::new(buf.stream_state.is_eos());
-        let size = track!(self.decode(&buf.inner.as_ref()[buf.head..buf.tail], eos))?;
-        buf.head += size;
-        if buf.head == buf.tail {
-            buf.head = 0;
-            buf.tail = 0;
-        }
+        let position = buf.position;
+        let size = track!(self
+            .decode(&buf.inner.as_ref()[buf.head..buf.tail], eos)
+            .map_err(|e| e.with_offset(position)))?;
+        buf.consume(size);
         Ok(())
     }
 
@@ -29,6 +29,7 @@ pub trait IoDecodeExt: Decode {
     /// Note that this is a blocking method.
     fn decode_exact<R: Read>(&mut self, mut reader: R) -> Result<Self::Item> {
         let mut buf = [0; 1024];
+        let mut position = 0;
         loop {
             let mut size = match self.requiring_bytes() {
                 ByteCount::Finite(n) => cmp::min(n, buf.len() as u64) as usize,
@@ -42,10 +43,13 @@ pub trait IoDecodeExt: Decode {
                 Eos::new(false)
             };
 
-            let consumed = track!(self.decode(&buf[..size], eos))?;
+            let consumed = track!(self
+                .decode(&buf[..size], eos)
+                .map_err(|e| e.with_offset(position)))?;
             track_assert_eq!(consumed, size, ErrorKind::InconsistentState; self.is_idle(), eos);
+            position += consumed as u64;
             if self.is_idle() {
-                let item = track!(self.finish_decoding())?;
+                let item = track!(self.finish_decoding().map_err(|e| e.with_offset(position)))?;
                 return Ok(item);
             }
         }
@@ -61,6 +65,7 @@ pub trait IoEncodeExt: Encode {
     where
         B: AsMut<[u8]>,
     {
+        buf.compact_if_needed();
         let eos = Eos::new(buf.stream_state.is_eos());
         let size = track!(self.encode(&mut buf.inner.as_mut()[buf.tail..], eos))?;
         buf.tail += size;
@@ -116,6 +121,37 @@ impl StreamState {
     }
 }
 
+/// Policy that decides when a buffer moves its contents to the front.
+///
+/// `ReadBuf` and `WriteBuf` hold their contents in a contiguous region of the inner bytes.
+/// Consumed bytes leave free space in front of the region,
+/// and that space cannot be used for new bytes until the contents are moved (i.e., compacted).
+///
+/// `ReadBuf` applies the policy before it is filled,
+/// and `WriteBuf` applies it before bytes are written to it and after bytes are flushed from it.
+/// `compact` methods move the contents regardless of the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompactionPolicy {
+    /// The free space is reclaimed only when the buffer is completely drained.
+    ///
+    /// This never copies bytes, but an incomplete item left at the end of the buffer
+    /// reduces the room of the buffer until the item is consumed.
+    Drained,
+
+    /// The contents are moved to the front whenever the policy is applied
+    /// and there is free space in front of them.
+    Always,
+
+    /// The contents are moved to the front when the room of the buffer becomes smaller than the given bytes.
+    LowRoom(usize),
+}
+impl Default for CompactionPolicy {
+    /// Returns `CompactionPolicy::Drained` as the default value.
+    fn default() -> Self {
+        CompactionPolicy::Drained
+    }
+}
+
 /// Read buffer.
 #[derive(Debug)]
 pub struct ReadBuf<B> {
@@ -123,6 +159,8 @@ pub struct ReadBuf<B> {
     pub(crate) head: usize,
     pub(crate) tail: usize,
     pub(crate) stream_state: StreamState,
+    pub(crate) compaction_policy: CompactionPolicy,
+    pub(crate) position: u64,
 }
 impl<B: AsRef<[u8]> + AsMut<[u8]>> ReadBuf<B> {
     /// Makes a new `ReadBuf` instance.
@@ -132,6 +170,38 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> ReadBuf<B> {
             head: 0,
             tail: 0,
             stream_state: StreamState::Normal,
+            compaction_policy: CompactionPolicy::default(),
+            position: 0,
+        }
+    }
+
+    /// Returns the total number of bytes consumed from the buffer so far.
+    ///
+    /// This is the position of the first filled byte in the input stream.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the compaction policy of the buffer.
+    pub fn compaction_policy(&self) -> CompactionPolicy {
+        self.compaction_policy
+    }
+
+    /// Sets the compaction policy of the buffer.
+    ///
+    /// The policy is applied before the buffer is filled.
+    pub fn set_compaction_policy(&mut self, policy: CompactionPolicy) {
+        self.compaction_policy = policy;
+    }
+
+    /// Moves the filled bytes to the front of the buffer regardless of the compaction policy.
+    ///
+    /// After this call, `self.room()` will be equal to `self.capacity() - self.len()`.
+    pub fn compact(&mut self) {
+        if self.head != 0 {
+            self.inner.as_mut().copy_within(self.head..self.tail, 0);
+            self.tail -= self.head;
+            self.head = 0;
         }
     }
 
@@ -179,6 +249,7 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> ReadBuf<B> {
     /// - A read operation returned a `WouldBlock` error
     /// - The input stream has reached EOS
     pub fn fill<R: Read>(&mut self, mut reader: R) -> Result<()> {
+        self.compact_if_needed();
         while !self.is_full() {
             match reader.read(&mut self.inner.as_mut()[self.tail..]) {
                 Err(e) => {
@@ -217,16 +288,41 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> ReadBuf<B> {
     pub fn into_inner(self) -> B {
         self.inner
     }
+
+    pub(crate) fn compact_if_needed(&mut self) {
+        match self.compaction_policy {
+            CompactionPolicy::Drained => {}
+            CompactionPolicy::Always => self.compact(),
+            CompactionPolicy::LowRoom(n) => {
+                if self.room() < n {
+                    self.compact();
+                }
+            }
+        }
+    }
 }
-impl<B: AsRef<[u8]> + AsMut<[u8]>> Read for ReadBuf<B> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let size = cmp::min(buf.len(), self.len());
-        (&mut buf[..size]).copy_from_slice(&self.inner.as_ref()[self.head..][..size]);
+impl<B> ReadBuf<B> {
+    pub(crate) fn consume(&mut self, size: usize) {
         self.head += size;
+        self.position += size as u64;
         if self.head == self.tail {
             self.head = 0;
             self.tail = 0;
         }
+    }
+}
+impl ReadBuf<Vec<u8>> {
+    fn resize(&mut self, capacity: usize) {
+        self.compact();
+        self.inner.resize(cmp::max(capacity, self.tail), 0);
+        self.inner.shrink_to_fit();
+    }
+}
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Read for ReadBuf<B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = cmp::min(buf.len(), self.len());
+        buf[..size].copy_from_slice(&self.inner.as_ref()[self.head..][..size]);
+        self.consume(size);
         Ok(size)
     }
 }
@@ -238,6 +334,7 @@ pub struct WriteBuf<B> {
     pub(crate) head: usize,
     pub(crate) tail: usize,
     pub(crate) stream_state: StreamState,
+    pub(crate) compaction_policy: CompactionPolicy,
 }
 impl<B: AsRef<[u8]> + AsMut<[u8]>> WriteBuf<B> {
     /// Makes a new `WriteBuf` instance.
@@ -247,9 +344,23 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> WriteBuf<B> {
             head: 0,
             tail: 0,
             stream_state: StreamState::Normal,
+            compaction_policy: CompactionPolicy::default(),
         }
     }
 
+    /// Returns the compaction policy of the buffer.
+    pub fn compaction_policy(&self) -> CompactionPolicy {
+        self.compaction_policy
+    }
+
+    /// Sets the compaction policy of the buffer.
+    ///
+    /// The policy is applied when bytes are flushed from the buffer and before bytes are written to the buffer.
+    pub fn set_compaction_policy(&mut self, policy: CompactionPolicy) {
+        self.compaction_policy = policy;
+        self.compact_if_needed();
+    }
+
     /// Returns the number of encoded bytes in the buffer.
     pub fn len(&self) -> usize {
         self.tail - self.head
@@ -313,11 +424,7 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> WriteBuf<B> {
                 }
                 Ok(size) => {
                     self.stream_state = StreamState::Normal;
-                    self.head += size;
-                    if self.head == self.tail {
-                        self.head = 0;
-                        self.tail = 0;
-                    }
+                    self.consume(size);
                 }
             }
         }
@@ -338,11 +445,53 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> WriteBuf<B> {
     pub fn into_inner(self) -> B {
         self.inner
     }
+
+    pub(crate) fn consume(&mut self, size: usize) {
+        self.head += size;
+        if self.head == self.tail {
+            self.head = 0;
+            self.tail = 0;
+        } else {
+            self.compact_if_needed();
+        }
+    }
+}
+impl<B: AsMut<[u8]>> WriteBuf<B> {
+    /// Moves the encoded bytes to the front of the buffer regardless of the compaction policy.
+    ///
+    /// After this call, `self.room()` will be equal to `self.capacity() - self.len()`.
+    pub fn compact(&mut self) {
+        if self.head != 0 {
+            self.inner.as_mut().copy_within(self.head..self.tail, 0);
+            self.tail -= self.head;
+            self.head = 0;
+        }
+    }
+
+    pub(crate) fn compact_if_needed(&mut self) {
+        match self.compaction_policy {
+            CompactionPolicy::Drained => {}
+            CompactionPolicy::Always => self.compact(),
+            CompactionPolicy::LowRoom(n) => {
+                if self.inner.as_mut().len() - self.tail < n {
+                    self.compact();
+                }
+            }
+        }
+    }
+}
+impl WriteBuf<Vec<u8>> {
+    fn resize(&mut self, capacity: usize) {
+        self.compact();
+        self.inner.resize(cmp::max(capacity, self.tail), 0);
+        self.inner.shrink_to_fit();
+    }
 }
 impl<B: AsRef<[u8]> + AsMut<[u8]>> Write for WriteBuf<B> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.compact_if_needed();
         let size = cmp::min(buf.len(), self.room());
-        (&mut self.inner.as_mut()[self.tail..][..size]).copy_from_slice(&buf[..size]);
+        self.inner.as_mut()[self.tail..][..size].copy_from_slice(&buf[..size]);
         self.tail += size;
         Ok(size)
     }
@@ -352,7 +501,173 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> Write for WriteBuf<B> {
     }
 }
 
+/// Capacity settings of a buffer owned by `BufferedIo`.
+///
+/// A buffer starts with the minimum capacity.
+/// When it runs out of room (after being compacted according to its `CompactionPolicy`),
+/// its capacity is doubled up to the maximum.
+/// Growing a buffer moves its contents to the front of the new one.
+/// A full write buffer is only grown if flushing it makes no progress
+/// (or if `BufferedIo::reserve_write_buf` requires more room).
+/// And if it has been empty during a number of consecutive I/O operations,
+/// it shrinks back to the minimum capacity.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::{BufferCapacity, BufferedIo};
+///
+/// let capacity = BufferCapacity::growable(1024, 64 * 1024);
+/// let io = BufferedIo::with_capacity(std::io::empty(), capacity, capacity);
+/// assert_eq!(io.read_buf_ref().capacity(), 1024);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BufferCapacity {
+    min: usize,
+    max: usize,
+    shrink_threshold: usize,
+}
+impl BufferCapacity {
+    /// Makes a new `BufferCapacity` instance for fixed size buffers.
+    pub fn fixed(size: usize) -> Self {
+        BufferCapacity {
+            min: size,
+            max: size,
+            shrink_threshold: 0,
+        }
+    }
+
+    /// Makes a new `BufferCapacity` instance for buffers that can grow from `min` to `max` bytes.
+    ///
+    /// The default shrink threshold is `16`.
+    ///
+    /// If `max` is smaller than `min`, `min` is used as the maximum capacity.
+    pub fn growable(min: usize, max: usize) -> Self {
+        BufferCapacity {
+            min,
+            max: cmp::max(min, max),
+            shrink_threshold: 16,
+        }
+    }
+
+    /// Returns the minimum (and initial) capacity of the buffer.
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    /// Returns the maximum capacity of the buffer.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Returns the number of consecutive idle I/O operations after which the buffer shrinks.
+    pub fn shrink_threshold(&self) -> usize {
+        self.shrink_threshold
+    }
+
+    /// Sets the number of consecutive idle I/O operations after which the buffer shrinks.
+    ///
+    /// `0` means that the buffer never shrinks.
+    pub fn set_shrink_threshold(&mut self, n: usize) {
+        self.shrink_threshold = n;
+    }
+
+    fn grown(&self, current: usize, required: usize) -> usize {
+        let mut capacity = cmp::max(current, 1);
+        while capacity < required && capacity < self.max {
+            capacity = capacity.saturating_mul(2);
+        }
+        cmp::min(capacity, self.max)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct BufferSizer {
+    capacity: BufferCapacity,
+    idle_count: usize,
+}
+impl BufferSizer {
+    fn new(capacity: BufferCapacity) -> Self {
+        BufferSizer {
+            capacity,
+            idle_count: 0,
+        }
+    }
+
+    pub(crate) fn before_fill(&mut self, buf: &mut ReadBuf<Vec<u8>>) {
+        if buf.is_full() {
+            buf.compact_if_needed();
+            if buf.is_full() {
+                self.reserve_read(buf, buf.capacity() + 1);
+            }
+        }
+    }
+
+    pub(crate) fn after_fill(&mut self, buf: &mut ReadBuf<Vec<u8>>) {
+        if buf.is_empty() {
+            if self.is_idle_enough() {
+                buf.resize(self.capacity.min);
+            }
+        } else {
+            self.idle_count = 0;
+        }
+    }
+
+    pub(crate) fn before_flush(&mut self, buf: &mut WriteBuf<Vec<u8>>) {
+        if buf.is_empty() {
+            if self.is_idle_enough() {
+                buf.resize(self.capacity.min);
+            }
+        } else {
+            self.idle_count = 0;
+            if buf.is_full() {
+                buf.compact_if_needed();
+            }
+        }
+    }
+
+    pub(crate) fn after_flush(&mut self, buf: &mut WriteBuf<Vec<u8>>, flushed: usize) {
+        // A full buffer is the steady state under sustained writes,
+        // so it is grown only if the stream could not accept any bytes.
+        if flushed == 0 && buf.is_full() {
+            self.reserve_write(buf, buf.capacity() + 1);
+        }
+    }
+
+    fn reserve_read(&mut self, buf: &mut ReadBuf<Vec<u8>>, required: usize) {
+        let capacity = self.capacity.grown(buf.capacity(), required);
+        if capacity > buf.capacity() {
+            buf.resize(capacity);
+        }
+    }
+
+    fn reserve_write(&mut self, buf: &mut WriteBuf<Vec<u8>>, required: usize) {
+        let capacity = self.capacity.grown(buf.capacity(), required);
+        if capacity > buf.capacity() {
+            buf.resize(capacity);
+        }
+    }
+
+    fn is_idle_enough(&mut self) -> bool {
+        if self.capacity.shrink_threshold == 0 {
+            return false;
+        }
+        self.idle_count = self.idle_count.saturating_add(1);
+        self.idle_count >= self.capacity.shrink_threshold
+    }
+}
+
 /// Buffered I/O stream.
+///
+/// The buffers are fixed size by default.
+/// Growable buffers can be used by making the instance via `BufferedIo::with_capacity` method.
+///
+/// The buffers are compacted only according to their `CompactionPolicy`
+/// (`CompactionPolicy::Drained` by default).
+/// So a full fixed size read buffer whose head has been consumed makes no progress
+/// until the rest is consumed, unless another policy is set via `read_buf_mut()`.
+/// The current capacity of each buffer can be observed by calling
+/// `read_buf_ref().capacity()` or `write_buf_ref().capacity()`.
 #[cfg_attr(feature = "tokio-async", pin_project)]
 #[derive(Debug)]
 pub struct BufferedIo<T> {
@@ -360,14 +675,36 @@ pub struct BufferedIo<T> {
     pub(crate) stream: T,
     pub(crate) rbuf: ReadBuf<Vec<u8>>,
     pub(crate) wbuf: WriteBuf<Vec<u8>>,
+    pub(crate) rbuf_sizer: BufferSizer,
+    pub(crate) wbuf_sizer: BufferSizer,
+    pub(crate) read_counters: Counters,
+    pub(crate) write_counters: Counters,
 }
 impl<T: Read + Write> BufferedIo<T> {
     /// Executes an I/O operation on the inner stream.
     ///
     /// "I/O operation" means "filling the read buffer" and "flushing the write buffer".
+    ///
+    /// If the buffers are growable, they are resized before and after the operation as needed.
     pub fn execute_io(&mut self) -> Result<()> {
-        track!(self.rbuf.fill(&mut self.stream))?;
-        track!(self.wbuf.flush(&mut self.stream))?;
+        self.rbuf_sizer.before_fill(&mut self.rbuf);
+        let filled = self.rbuf.len();
+        let result = self.rbuf.fill(&mut self.stream);
+        record_io(
+            &self.read_counters,
+            self.rbuf.len() - filled,
+            result.as_ref().err(),
+        );
+        track!(result)?;
+        self.rbuf_sizer.after_fill(&mut self.rbuf);
+
+        self.wbuf_sizer.before_flush(&mut self.wbuf);
+        let unflushed = self.wbuf.len();
+        let result = self.wbuf.flush(&mut self.stream);
+        let flushed = unflushed - self.wbuf.len();
+        record_io(&self.write_counters, flushed, result.as_ref().err());
+        track!(result)?;
+        self.wbuf_sizer.after_flush(&mut self.wbuf, flushed);
         Ok(())
     }
 }
@@ -375,13 +712,78 @@ impl<T: Read + Write> BufferedIo<T> {
 impl<T> BufferedIo<T> {
     /// Makes a new `BufferedIo` instance.
     pub fn new(stream: T, read_buf_size: usize, write_buf_size: usize) -> Self {
+        Self::with_capacity(
+            stream,
+            BufferCapacity::fixed(read_buf_size),
+            BufferCapacity::fixed(write_buf_size),
+        )
+    }
+
+    /// Makes a new `BufferedIo` instance that has buffers with the given capacity settings.
+    pub fn with_capacity(
+        stream: T,
+        read_buf_capacity: BufferCapacity,
+        write_buf_capacity: BufferCapacity,
+    ) -> Self {
         BufferedIo {
             stream,
-            rbuf: ReadBuf::new(vec![0; read_buf_size]),
-            wbuf: WriteBuf::new(vec![0; write_buf_size]),
+            rbuf: ReadBuf::new(vec![0; read_buf_capacity.min]),
+            wbuf: WriteBuf::new(vec![0; write_buf_capacity.min]),
+            rbuf_sizer: BufferSizer::new(read_buf_capacity),
+            wbuf_sizer: BufferSizer::new(write_buf_capacity),
+            read_counters: Counters::new(),
+            write_counters: Counters::new(),
+        }
+    }
+
+    /// Returns the capacity settings of the read buffer.
+    pub fn read_buf_capacity(&self) -> BufferCapacity {
+        self.rbuf_sizer.capacity
+    }
+
+    /// Returns the capacity settings of the write buffer.
+    pub fn write_buf_capacity(&self) -> BufferCapacity {
+        self.wbuf_sizer.capacity
+    }
+
+    /// Grows the read buffer so that it can hold the given number of bytes in addition to the filled bytes.
+    ///
+    /// Typically, the result of `Decode::requiring_bytes` is passed to this method.
+    /// The capacity never exceeds the maximum of the capacity settings.
+    pub fn reserve_read_buf(&mut self, additional: ByteCount) {
+        if let ByteCount::Finite(n) = additional {
+            let required = self.rbuf.len().saturating_add(n as usize);
+            self.rbuf_sizer.reserve_read(&mut self.rbuf, required);
         }
     }
 
+    /// Grows the write buffer so that it can hold the given number of bytes in addition to the encoded bytes.
+    ///
+    /// Typically, the result of `Encode::requiring_bytes` is passed to this method.
+    /// The capacity never exceeds the maximum of the capacity settings.
+    pub fn reserve_write_buf(&mut self, additional: ByteCount) {
+        if let ByteCount::Finite(n) = additional {
+            let required = self.wbuf.len().saturating_add(n as usize);
+            self.wbuf_sizer.reserve_write(&mut self.wbuf, required);
+        }
+    }
+
+    /// Returns the metrics of the read operations on the inner stream.
+    ///
+    /// A call is recorded for each attempt to fill the read buffer,
+    /// and the number of bytes read from the stream is recorded as bytes.
+    pub fn read_counters(&self) -> &Counters {
+        &self.read_counters
+    }
+
+    /// Returns the metrics of the write operations on the inner stream.
+    ///
+    /// A call is recorded for each attempt to flush the write buffer,
+    /// and the number of bytes written to the stream is recorded as bytes.
+    pub fn write_counters(&self) -> &Counters {
+        &self.write_counters
+    }
+
     /// Returns `true` if the inner stream reaches EOS, otherwise `false`.
     pub fn is_eos(&self) -> bool {
         self.rbuf.stream_state().is_eos() || self.wbuf.stream_state().is_eos()
@@ -429,11 +831,19 @@ impl<T> BufferedIo<T> {
     }
 }
 
+pub(crate) fn record_io(counters: &Counters, bytes: usize, error: Option<&Error>) {
+    counters.record_call();
+    counters.record_bytes(bytes as u64);
+    if let Some(e) = error {
+        counters.record_error(*e.kind());
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::bytes::{Utf8Decoder, Utf8Encoder};
-    use crate::EncodeExt;
+    use crate::{DecodeExt, EncodeExt};
     use std::io::{Read, Write};
 
     #[test]
@@ -446,6 +856,7 @@ mod test {
         let mut decoder = Utf8Decoder::new();
         track_try_unwrap!(decoder.decode_from_read_buf(&mut buf));
         assert_eq!(track_try_unwrap!(decoder.finish_decoding()), "foo");
+        assert_eq!(buf.position(), 3);
     }
 
     #[test]
@@ -476,6 +887,171 @@ mod test {
         assert_eq!(v, b"foo");
     }
 
+    #[test]
+    fn read_buf_compaction_works() {
+        let mut decoder = Utf8Decoder::new().length(3);
+
+        let mut buf = ReadBuf::new(vec![0; 4]);
+        track_try_unwrap!(buf.fill(b"foobar".as_ref()));
+        track_try_unwrap!(decoder.decode_from_read_buf(&mut buf));
+        assert_eq!(track_try_unwrap!(decoder.finish_decoding()), "foo");
+        assert_eq!(buf.len(), 1);
+        assert_eq!(buf.room(), 0);
+        assert!(buf.is_full());
+
+        let mut buf = ReadBuf::new(vec![0; 4]);
+        buf.set_compaction_policy(CompactionPolicy::Always);
+        let mut input = b"foobar".as_ref();
+        track_try_unwrap!(buf.fill(&mut input));
+        track_try_unwrap!(decoder.decode_from_read_buf(&mut buf));
+        assert_eq!(track_try_unwrap!(decoder.finish_decoding()), "foo");
+        assert_eq!(buf.len(), 1);
+        assert_eq!(buf.room(), 0);
+
+        // Consuming bytes never compacts the buffer; filling does.
+        track_try_unwrap!(buf.fill(&mut input));
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.room(), 1);
+        track_try_unwrap!(decoder.decode_from_read_buf(&mut buf));
+        assert_eq!(track_try_unwrap!(decoder.finish_decoding()), "bar");
+        assert_eq!(buf.stream_state(), StreamState::Eos);
+    }
+
+    #[test]
+    fn decode_from_read_buf_does_not_require_as_mut() {
+        fn decode<B: AsRef<[u8]>>(buf: &mut ReadBuf<B>) -> Result<String> {
+            let mut decoder = Utf8Decoder::new().length(3);
+            track!(decoder.decode_from_read_buf(buf))?;
+            track!(decoder.finish_decoding())
+        }
+
+        let mut buf = ReadBuf::new(vec![0; 4]);
+        track_try_unwrap!(buf.fill(b"foo".as_ref()));
+        assert_eq!(track_try_unwrap!(decode(&mut buf)), "foo");
+    }
+
+    #[test]
+    fn write_buf_compaction_works() {
+        let mut buf = WriteBuf::new(vec![0; 4]);
+        buf.set_compaction_policy(CompactionPolicy::LowRoom(2));
+        buf.write_all(b"foo").unwrap();
+
+        let mut output = [0; 2];
+        track_try_unwrap!(buf.flush(&mut output[..]));
+        assert_eq!(buf.len(), 1);
+        assert_eq!(buf.room(), 3);
+
+        let mut encoder = track_try_unwrap!(Utf8Encoder::with_item("bar"));
+        track_try_unwrap!(encoder.encode_to_write_buf(&mut buf));
+        assert!(encoder.is_idle());
+        assert_eq!(&buf.inner_ref()[..buf.len()], b"obar");
+    }
+
+    #[test]
+    fn buffered_io_respects_compaction_policy() {
+        let stream = io::Cursor::new(b"foobar".to_vec());
+        let mut io = BufferedIo::new(stream, 4, 4);
+        track_try_unwrap!(io.execute_io());
+        let mut head = [0; 3];
+        io.read_buf_mut().read_exact(&mut head).unwrap();
+        assert_eq!(&head, b"foo");
+        assert!(io.read_buf_ref().is_full());
+
+        // `Drained` never moves the remaining byte, so nothing can be read.
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().len(), 1);
+        assert!(io.read_buf_ref().is_full());
+
+        io.read_buf_mut()
+            .set_compaction_policy(CompactionPolicy::Always);
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().len(), 3);
+        let mut rest = Vec::new();
+        io.read_buf_mut().read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"bar");
+    }
+
+    #[test]
+    fn growable_buffers_work() {
+        let mut capacity = BufferCapacity::growable(4, 16);
+        capacity.set_shrink_threshold(2);
+        let stream = io::Cursor::new(vec![1; 24]);
+        let mut io = BufferedIo::with_capacity(stream, capacity, capacity);
+        assert_eq!(io.read_buf_ref().capacity(), 4);
+
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().len(), 4);
+        assert_eq!(io.read_buf_ref().capacity(), 4);
+
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().len(), 8);
+        assert_eq!(io.read_buf_ref().capacity(), 8);
+
+        track_try_unwrap!(io.execute_io());
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().len(), 16);
+        assert_eq!(io.read_buf_ref().capacity(), 16);
+
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().len(), 16);
+        assert_eq!(io.read_buf_ref().capacity(), 16);
+
+        let mut buf = Vec::new();
+        io.read_buf_mut().read_to_end(&mut buf).unwrap();
+        assert_eq!(buf.len(), 16);
+
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().len(), 8);
+        io.read_buf_mut().read_to_end(&mut buf).unwrap();
+
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().capacity(), 16);
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.read_buf_ref().capacity(), 4);
+
+        assert_eq!(io.read_counters().calls(), 8);
+        assert_eq!(io.read_counters().bytes(), 24);
+        assert_eq!(io.read_counters().total_errors(), 0);
+
+        io.reserve_write_buf(ByteCount::Finite(10));
+        assert_eq!(io.write_buf_ref().capacity(), 16);
+        io.reserve_write_buf(ByteCount::Finite(100));
+        assert_eq!(io.write_buf_ref().capacity(), 16);
+    }
+
+    struct WouldBlockStream;
+    impl Read for WouldBlockStream {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::WouldBlock.into())
+        }
+    }
+    impl Write for WouldBlockStream {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::WouldBlock.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_buf_grows_only_if_flush_makes_no_progress() {
+        let capacity = BufferCapacity::growable(4, 16);
+        let mut io = BufferedIo::with_capacity(io::Cursor::new(Vec::new()), capacity, capacity);
+        for _ in 0..4 {
+            io.write_buf_mut().write_all(b"abcd").unwrap();
+            assert!(io.write_buf_ref().is_full());
+            track_try_unwrap!(io.execute_io());
+            assert_eq!(io.write_buf_ref().capacity(), 4);
+        }
+
+        let mut io = BufferedIo::with_capacity(WouldBlockStream, capacity, capacity);
+        io.write_buf_mut().write_all(b"abcd").unwrap();
+        track_try_unwrap!(io.execute_io());
+        assert_eq!(io.write_buf_ref().capacity(), 8);
+    }
+
     #[test]
     fn write_to_write_buf_works() {
         let mut buf = WriteBuf::new(vec![0; 1024]);
@@ -1,5 +1,5 @@
 //! I/O (i.e., `Read` and `Write` traits) related module.
-use crate::io::{BufferedIo, ReadBuf, StreamState, WriteBuf};
+use crate::io::{record_io, BufferedIo, ReadBuf, StreamState, WriteBuf};
 use crate::{Error, Result};
 use core::pin::Pin;
 use core::task::{Context, Poll};
@@ -17,6 +17,7 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> ReadBuf<B> {
         mut reader: Pin<&mut R>,
         cx: &mut Context<'_>,
     ) -> Poll<Result<()>> {
+        self.compact_if_needed();
         while !self.is_full() {
             let mut buffer = tokio::io::ReadBuf::new(&mut self.inner.as_mut()[self.tail..]);
             match reader.as_mut().poll_read(cx, &mut buffer) {
@@ -73,11 +74,7 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> WriteBuf<B> {
                 }
                 Poll::Ready(Ok(size)) => {
                     self.stream_state = StreamState::Normal;
-                    self.head += size;
-                    if self.head == self.tail {
-                        self.head = 0;
-                        self.tail = 0;
-                    }
+                    self.consume(size);
                 }
                 Poll::Pending => {
                     self.stream_state = StreamState::WouldBlock;
@@ -95,11 +92,23 @@ impl<T: AsyncRead + AsyncWrite> BufferedIo<T> {
     /// Executes an I/O operation on the inner stream.
     ///
     /// "I/O operation" means "filling the read buffer" and "flushing the write buffer".
+    /// If the buffers are growable, they are resized before and after the operation as needed.
     /// This function returns Poll::Pending when both rbuf and wbuf are not ready for I/O operations.
     pub fn execute_io_poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
         let mut this = self.project();
+        this.rbuf_sizer.before_fill(this.rbuf);
+        let filled = this.rbuf.len();
         let rresult = this.rbuf.poll_fill(this.stream.as_mut(), cx);
+        let read_bytes = this.rbuf.len() - filled;
+        record_io(this.read_counters, read_bytes, poll_error(&rresult));
+        this.rbuf_sizer.after_fill(this.rbuf);
+
+        this.wbuf_sizer.before_flush(this.wbuf);
+        let unflushed = this.wbuf.len();
         let wresult = this.wbuf.poll_flush(this.stream.as_mut(), cx);
+        let written_bytes = unflushed - this.wbuf.len();
+        record_io(this.write_counters, written_bytes, poll_error(&wresult));
+        this.wbuf_sizer.after_flush(this.wbuf, written_bytes);
         if let (&Poll::Pending, &Poll::Pending) = (&rresult, &wresult) {
             // This future will be polled again when either rbuf or wbuf is ready.
             return Poll::Pending;
@@ -117,3 +126,11 @@ impl<T: AsyncRead + AsyncWrite> BufferedIo<T> {
         Poll::Ready(Ok(()))
     }
 }
+
+fn poll_error(result: &Poll<Result<()>>) -> Option<&Error> {
+    if let Poll::Ready(Err(ref e)) = *result {
+        Some(e)
+    } else {
+        None
+    }
+}
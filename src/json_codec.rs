@@ -62,6 +62,10 @@ where
     fn is_idle(&self) -> bool {
         self.0.is_idle()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
 }
 impl<T> Default for JsonDecoder<T>
 where
@@ -126,6 +130,10 @@ where
     fn requiring_bytes(&self) -> ByteCount {
         self.0.requiring_bytes()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
 }
 impl<T> Default for JsonEncoder<T>
 where
@@ -25,10 +25,13 @@
 //! - Trackable errors:
 //!    - By using [trackable] crate, the location where an error occurred can be easily specified
 //!    - See `EncodeExt::map_err` and `DecodeExt::map_err` methods
+//! - Optional [tracing] integration:
+//!    - See `EncodeExt::traced` and `DecodeExt::traced` methods (available if `tracing` feature is enabled)
 //!
 //! [bincode]: https://crates.io/crates/bincode
 //! [serde]: https://crates.io/crates/serde
 //! [serde_json]: https://crates.io/crates/serde_json
+//! [tracing]: https://crates.io/crates/tracing
 //! [trackable]: https://crates.io/crates/trackable
 #![warn(missing_docs)]
 
@@ -51,22 +54,33 @@ pub use error::{Error, ErrorKind};
 #[macro_use]
 mod macros;
 
+pub mod array;
+pub mod backref;
 #[cfg(feature = "bincode_codec")]
 pub mod bincode_codec;
 pub mod bytes;
+pub mod clock;
 pub mod combinator;
 pub mod fixnum;
+pub mod hexdump;
 pub mod io;
 #[cfg(feature = "tokio-async")]
 pub mod io_async;
 #[cfg(feature = "json_codec")]
 pub mod json_codec;
+pub mod map;
 pub mod marker;
+pub mod metrics;
 pub mod monolithic;
+pub mod mux;
+pub mod net;
 pub mod null;
 pub mod padding;
 pub mod slice;
+pub mod sum;
+pub mod time;
 pub mod tuple;
+pub mod xdr;
 
 mod byte_count;
 mod decode;
@@ -44,17 +44,24 @@ macro_rules! bytecodec_try_encode {
 ///
 /// ```ignore
 /// if !$decoder.is_idle() {
-///     $offset += track!($decoder.decode(&$buf[$offset..], $eos))?;
+///     $offset += track!($decoder
+///         .decode(&$buf[$offset..], $eos)
+///         .map_err(|e| e.with_offset($offset as u64)))?;
 ///     if !$decoder.is_idle() {
 ///         return Ok($offset);
 ///     }
 /// }
 /// ```
+///
+/// If the decoder fails, `$offset` is added to the offset of the resulting error
+/// (see `Error::offset`).
 #[macro_export]
 macro_rules! bytecodec_try_decode {
     ($decoder:expr, $offset:expr, $buf:expr, $eos:expr) => {
         if !$decoder.is_idle() {
-            $offset += track!($decoder.decode(&$buf[$offset..], $eos))?;
+            $offset += track!($decoder
+                .decode(&$buf[$offset..], $eos)
+                .map_err(|e| e.with_offset($offset as u64)))?;
             if !$decoder.is_idle() {
                 return Ok($offset);
             }
@@ -62,7 +69,9 @@ macro_rules! bytecodec_try_decode {
     };
     ($decoder:expr, $offset:expr, $buf:expr, $eos:expr, $($track_arg:tt)*) => {
         if !$decoder.is_idle() {
-            $offset += track!($decoder.decode(&$buf[$offset..], $eos), $($track_arg)*)?;
+            $offset += track!($decoder
+                .decode(&$buf[$offset..], $eos)
+                .map_err(|e| e.with_offset($offset as u64)), $($track_arg)*)?;
             if !$decoder.is_idle() {
                 return Ok($offset);
             }
@@ -70,10 +79,136 @@ macro_rules! bytecodec_try_decode {
     };
     ($decoder:expr, $offset:expr, $buf:expr, $eos:expr; $($track_arg:tt)*) => {
         if !$decoder.is_idle() {
-            $offset += track!($decoder.decode(&$buf[$offset..], $eos); $($track_arg)*)?;
+            $offset += track!($decoder
+                .decode(&$buf[$offset..], $eos)
+                .map_err(|e| e.with_offset($offset as u64)); $($track_arg)*)?;
+            if !$decoder.is_idle() {
+                return Ok($offset);
+            }
+        }
+    };
+}
+
+/// Same as `bytecodec_try_decode!` except that `$path` is prepended to the path of the resulting error
+/// (see `Error::path`).
+macro_rules! try_decode_with_path {
+    ($decoder:expr, $offset:expr, $buf:expr, $eos:expr, $path:expr) => {
+        if !$decoder.is_idle() {
+            $offset += track!($decoder
+                .decode(&$buf[$offset..], $eos)
+                .map_err(|e| e.with_offset($offset as u64).with_path($path)))?;
+            if !$decoder.is_idle() {
+                return Ok($offset);
+            }
+        }
+    };
+    ($decoder:expr, $offset:expr, $buf:expr, $eos:expr, $path:expr, $($track_arg:tt)*) => {
+        if !$decoder.is_idle() {
+            $offset += track!($decoder
+                .decode(&$buf[$offset..], $eos)
+                .map_err(|e| e.with_offset($offset as u64).with_path($path)), $($track_arg)*)?;
             if !$decoder.is_idle() {
                 return Ok($offset);
             }
         }
     };
 }
+
+/// Implements `Decode` for a newtype decoder `$ty` that wraps an inner decoder.
+///
+/// The item decoded by the inner decoder is converted by `Self::decode_item`,
+/// which returns `$item` (or `Result<$item>` if `try` is given).
+/// Errors are annotated with the name of `$ty` (see `Error::path`).
+macro_rules! impl_newtype_decode {
+    ($ty:ty, $item:ty) => {
+        impl_newtype_decode!(@impl $ty, $item, |item| Ok::<_, $crate::Error>(Self::decode_item(item)));
+    };
+    ($ty:ty, $item:ty, try) => {
+        impl_newtype_decode!(@impl $ty, $item, Self::decode_item);
+    };
+    (@impl $ty:ty, $item:ty, $decode_item:expr) => {
+        impl $crate::Decode for $ty {
+            type Item = $item;
+
+            fn decode(&mut self, buf: &[u8], eos: $crate::Eos) -> $crate::Result<usize> {
+                track!(self
+                    .0
+                    .decode(buf, eos)
+                    .map_err(|e| e.with_path(stringify!($ty))))
+            }
+
+            fn finish_decoding(&mut self) -> $crate::Result<Self::Item> {
+                let item = track!(self
+                    .0
+                    .finish_decoding()
+                    .map_err(|e| e.with_path(stringify!($ty))))?;
+                track!(($decode_item)(item))
+            }
+
+            fn requiring_bytes(&self) -> $crate::ByteCount {
+                self.0.requiring_bytes()
+            }
+
+            fn is_idle(&self) -> bool {
+                self.0.is_idle()
+            }
+
+            fn reset(&mut self) -> $crate::Result<()> {
+                track!(self.0.reset())
+            }
+        }
+    };
+}
+
+/// Implements `Encode` and `SizedEncode` for a newtype encoder `$ty` that wraps an inner encoder.
+///
+/// The item to be encoded is converted into the inner item by `Self::encode_item`:
+/// - `$ty, $item`: `fn encode_item(item: $item) -> Inner`
+/// - `$ty, $item, try`: `fn encode_item(item: $item) -> Result<Inner>`
+/// - `$ty, $item, buf`: `fn encode_item(item: $item, b: &mut Inner) -> Result<()>`,
+///   where `Inner` is initialized by `Default::default()`
+macro_rules! impl_newtype_encode {
+    ($ty:ty, $item:ty) => {
+        impl_newtype_encode!(@impl $ty, $item, |item| Ok::<_, $crate::Error>(Self::encode_item(item)));
+    };
+    ($ty:ty, $item:ty, try) => {
+        impl_newtype_encode!(@impl $ty, $item, Self::encode_item);
+    };
+    ($ty:ty, $item:ty, buf) => {
+        impl_newtype_encode!(@impl $ty, $item, |item| {
+            let mut b = Default::default();
+            Self::encode_item(item, &mut b).map(|()| b)
+        });
+    };
+    (@impl $ty:ty, $item:ty, $encode_item:expr) => {
+        impl $crate::Encode for $ty {
+            type Item = $item;
+
+            fn encode(&mut self, buf: &mut [u8], eos: $crate::Eos) -> $crate::Result<usize> {
+                track!(self.0.encode(buf, eos))
+            }
+
+            fn start_encoding(&mut self, item: Self::Item) -> $crate::Result<()> {
+                let item = track!(($encode_item)(item))?;
+                track!(self.0.start_encoding(item))
+            }
+
+            fn requiring_bytes(&self) -> $crate::ByteCount {
+                self.0.requiring_bytes()
+            }
+
+            fn is_idle(&self) -> bool {
+                self.0.is_idle()
+            }
+
+            fn reset(&mut self) -> $crate::Result<()> {
+                track!(self.0.reset())
+            }
+        }
+        impl $crate::SizedEncode for $ty {
+            fn exact_requiring_bytes(&self) -> u64 {
+                self.0.exact_requiring_bytes()
+            }
+        }
+    };
+}
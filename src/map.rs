@@ -0,0 +1,467 @@
+//! Encoders and decoders for key-value maps.
+//!
+//! An encoded map consists of the number of entries followed by the key-value pairs.
+use crate::{ByteCount, Decode, Encode, Eos, ErrorKind, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hash};
+use std::mem;
+
+/// The default maximum number of entries allowed in a map decoded by `MapDecoder`.
+pub const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+/// This trait allows for inserting decoded entries into a collection.
+///
+/// `Vec<(K, V)>` preserves the order of the entries,
+/// so it can be used to build any collection which implements `FromIterator`.
+/// Note that it looks up duplicate keys linearly, so decoding a map of `n` entries into it costs `O(n^2)`;
+/// prefer `HashMap` or `BTreeMap` (and convert the result) if a large `MapDecoder::max_entries` is needed.
+pub trait MapCollection: Default {
+    /// The type of the keys.
+    type Key;
+
+    /// The type of the values.
+    type Value;
+
+    /// Returns `true` if the collection contains an entry that has the given key, otherwise `false`.
+    fn contains_key(&self, key: &Self::Key) -> bool;
+
+    /// Inserts the given entry into the collection.
+    ///
+    /// If there is an entry that has the same key, it will be replaced.
+    fn insert_entry(&mut self, key: Self::Key, value: Self::Value);
+}
+impl<K, V, S> MapCollection for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    type Key = K;
+    type Value = V;
+
+    fn contains_key(&self, key: &K) -> bool {
+        HashMap::contains_key(self, key)
+    }
+
+    fn insert_entry(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+}
+impl<K: Ord, V> MapCollection for BTreeMap<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn contains_key(&self, key: &K) -> bool {
+        BTreeMap::contains_key(self, key)
+    }
+
+    fn insert_entry(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+}
+impl<K: PartialEq, V> MapCollection for Vec<(K, V)> {
+    type Key = K;
+    type Value = V;
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.iter().any(|e| e.0 == *key)
+    }
+
+    fn insert_entry(&mut self, key: K, value: V) {
+        if let Some(e) = self.iter_mut().find(|e| e.0 == key) {
+            e.1 = value;
+        } else {
+            self.push((key, value));
+        }
+    }
+}
+
+/// Policy for handling duplicate keys in a decoded map.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DuplicateKeyPolicy {
+    /// Rejects the map as `ErrorKind::InvalidInput` (default).
+    #[default]
+    Error,
+
+    /// Keeps the value of the first entry.
+    FirstWins,
+
+    /// Keeps the value of the last entry.
+    LastWins,
+}
+
+/// Decoder for maps prefixed by the number of entries.
+///
+/// The number of entries is decoded by `N` (an unsigned integer decoder),
+/// and each entry is decoded by `K` (key) and `V` (value).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::fixnum::{U16beDecoder, U8Decoder};
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::map::MapDecoder;
+/// use std::collections::BTreeMap;
+///
+/// let mut decoder: MapDecoder<_, _, _, BTreeMap<_, _>> =
+///     MapDecoder::new(U8Decoder::new(), U8Decoder::new(), U16beDecoder::new());
+/// let map = decoder.decode_exact(&[2, 10, 0, 1, 20, 0, 2][..]).unwrap();
+/// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(10, 1), (20, 2)]);
+/// ```
+#[derive(Debug)]
+pub struct MapDecoder<N, K: Decode, V, C> {
+    count: N,
+    key: K,
+    value: V,
+    entries: C,
+    remaining: Option<usize>,
+    decoded_key: Option<K::Item>,
+    index: usize,
+    max_entries: usize,
+    policy: DuplicateKeyPolicy,
+}
+impl<N, K, V, C> MapDecoder<N, K, V, C>
+where
+    K: Decode,
+    C: Default,
+{
+    /// Makes a new `MapDecoder` instance.
+    ///
+    /// By default, the number of entries is limited to `DEFAULT_MAX_ENTRIES` and duplicate keys are rejected.
+    pub fn new(count_decoder: N, key_decoder: K, value_decoder: V) -> Self {
+        MapDecoder {
+            count: count_decoder,
+            key: key_decoder,
+            value: value_decoder,
+            entries: C::default(),
+            remaining: None,
+            decoded_key: None,
+            index: 0,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            policy: DuplicateKeyPolicy::default(),
+        }
+    }
+
+    /// Returns the maximum number of entries allowed in a map.
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    /// Sets the maximum number of entries allowed in a map.
+    ///
+    /// If the decoded count exceeds this value, an `ErrorKind::InvalidInput` error will be returned
+    /// before decoding any entries.
+    pub fn set_max_entries(&mut self, n: usize) {
+        self.max_entries = n;
+    }
+
+    /// Returns the policy for handling duplicate keys.
+    pub fn duplicate_key_policy(&self) -> DuplicateKeyPolicy {
+        self.policy
+    }
+
+    /// Sets the policy for handling duplicate keys.
+    pub fn set_duplicate_key_policy(&mut self, policy: DuplicateKeyPolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns a reference to the key decoder.
+    pub fn key_decoder_ref(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns a mutable reference to the key decoder.
+    pub fn key_decoder_mut(&mut self) -> &mut K {
+        &mut self.key
+    }
+
+    /// Returns a reference to the value decoder.
+    pub fn value_decoder_ref(&self) -> &V {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the value decoder.
+    pub fn value_decoder_mut(&mut self) -> &mut V {
+        &mut self.value
+    }
+
+    fn path(&self, part: &str) -> String {
+        format!("map[{}].{}", self.index, part)
+    }
+}
+impl<N, K, V, C> Decode for MapDecoder<N, K, V, C>
+where
+    N: Decode,
+    N::Item: Into<u64>,
+    K: Decode,
+    V: Decode,
+    C: MapCollection<Key = K::Item, Value = V::Item>,
+{
+    type Item = C;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        if self.remaining.is_none() {
+            try_decode_with_path!(self.count, offset, buf, eos, "map.count");
+            let n = track!(self.count.finish_decoding())?.into();
+            track_assert!(n <= self.max_entries as u64, ErrorKind::InvalidInput,
+                          "Too many entries"; n, self.max_entries);
+            self.remaining = Some(n as usize);
+        }
+        while self.remaining != Some(0) {
+            if self.decoded_key.is_none() {
+                let start = offset;
+                try_decode_with_path!(self.key, offset, buf, eos, self.path("key"));
+                let key = track!(self
+                    .key
+                    .finish_decoding()
+                    .map_err(|e| e.with_offset(start as u64).with_path(self.path("key"))))?;
+                self.decoded_key = Some(key);
+            }
+
+            let start = offset;
+            try_decode_with_path!(self.value, offset, buf, eos, self.path("value"));
+            let value = track!(self
+                .value
+                .finish_decoding()
+                .map_err(|e| e.with_offset(start as u64).with_path(self.path("value"))))?;
+            let key = self.decoded_key.take().expect("Never fails");
+            if self.entries.contains_key(&key) {
+                match self.policy {
+                    DuplicateKeyPolicy::Error => {
+                        track_panic!(ErrorKind::InvalidInput, "Duplicate key"; self.index)
+                    }
+                    DuplicateKeyPolicy::FirstWins => {}
+                    DuplicateKeyPolicy::LastWins => self.entries.insert_entry(key, value),
+                }
+            } else {
+                self.entries.insert_entry(key, value);
+            }
+            self.remaining = self.remaining.map(|n| n - 1);
+            self.index += 1;
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert_eq!(self.remaining, Some(0), ErrorKind::IncompleteDecoding);
+        self.remaining = None;
+        self.index = 0;
+        Ok(mem::take(&mut self.entries))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        match self.remaining {
+            None => self.count.requiring_bytes(),
+            Some(0) => ByteCount::Finite(0),
+            Some(_) if self.decoded_key.is_none() => self.key.requiring_bytes(),
+            Some(_) => self.value.requiring_bytes(),
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.remaining == Some(0)
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.count.reset())?;
+        track!(self.key.reset())?;
+        track!(self.value.reset())?;
+        self.entries = C::default();
+        self.remaining = None;
+        self.decoded_key = None;
+        self.index = 0;
+        Ok(())
+    }
+}
+impl<N, K, V, C> Default for MapDecoder<N, K, V, C>
+where
+    N: Default,
+    K: Decode + Default,
+    V: Default,
+    C: Default,
+{
+    fn default() -> Self {
+        Self::new(N::default(), K::default(), V::default())
+    }
+}
+
+/// Encoder for maps prefixed by the number of entries.
+///
+/// The number of entries is encoded by `N` (an unsigned integer encoder),
+/// and each entry is encoded by `K` (key) and `V` (value).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::fixnum::{U16beEncoder, U8Encoder};
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::map::MapEncoder;
+/// use std::collections::BTreeMap;
+///
+/// let mut map = BTreeMap::new();
+/// map.insert(10, 1);
+/// map.insert(20, 2);
+///
+/// let mut output = Vec::new();
+/// let mut encoder = MapEncoder::new(U8Encoder::new(), U8Encoder::new(), U16beEncoder::new());
+/// encoder.start_encoding(map).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [2, 10, 0, 1, 20, 0, 2]);
+/// ```
+#[derive(Debug)]
+pub struct MapEncoder<N, K, V, C: IntoIterator> {
+    count: N,
+    key: K,
+    value: V,
+    entries: Option<C::IntoIter>,
+}
+impl<N, K, V, C: IntoIterator> MapEncoder<N, K, V, C> {
+    /// Makes a new `MapEncoder` instance.
+    pub fn new(count_encoder: N, key_encoder: K, value_encoder: V) -> Self {
+        MapEncoder {
+            count: count_encoder,
+            key: key_encoder,
+            value: value_encoder,
+            entries: None,
+        }
+    }
+
+    /// Returns a reference to the key encoder.
+    pub fn key_encoder_ref(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns a mutable reference to the key encoder.
+    pub fn key_encoder_mut(&mut self) -> &mut K {
+        &mut self.key
+    }
+
+    /// Returns a reference to the value encoder.
+    pub fn value_encoder_ref(&self) -> &V {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the value encoder.
+    pub fn value_encoder_mut(&mut self) -> &mut V {
+        &mut self.value
+    }
+}
+impl<N, K, V, C> Encode for MapEncoder<N, K, V, C>
+where
+    N: Encode,
+    N::Item: TryFrom<usize>,
+    K: Encode,
+    V: Encode,
+    C: IntoIterator<Item = (K::Item, V::Item)>,
+    C::IntoIter: ExactSizeIterator,
+{
+    type Item = C;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        bytecodec_try_encode!(self.count, offset, buf, eos);
+        loop {
+            bytecodec_try_encode!(self.key, offset, buf, eos);
+            bytecodec_try_encode!(self.value, offset, buf, eos);
+            if let Some((key, value)) = self.entries.as_mut().and_then(Iterator::next) {
+                track!(self.key.start_encoding(key))?;
+                track!(self.value.start_encoding(value))?;
+            } else {
+                self.entries = None;
+                return Ok(offset);
+            }
+        }
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        let entries = item.into_iter();
+        let n = entries.len();
+        let count = track_assert_some!(N::Item::try_from(n).ok(), ErrorKind::InvalidInput,
+                                       "Too many entries"; n);
+        track!(self.count.start_encoding(count))?;
+        self.entries = Some(entries);
+        Ok(())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.is_idle() {
+            ByteCount::Finite(0)
+        } else {
+            ByteCount::Unknown
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.entries.is_none() && self.count.is_idle() && self.key.is_idle() && self.value.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.count.reset())?;
+        track!(self.key.reset())?;
+        track!(self.value.reset())?;
+        self.entries = None;
+        Ok(())
+    }
+}
+impl<N, K, V, C> Default for MapEncoder<N, K, V, C>
+where
+    N: Default,
+    K: Default,
+    V: Default,
+    C: IntoIterator,
+{
+    fn default() -> Self {
+        Self::new(N::default(), K::default(), V::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixnum::{U16beDecoder, U8Decoder, U8Encoder};
+    use crate::io::{IoDecodeExt, IoEncodeExt};
+
+    type Decoder<C> = MapDecoder<U8Decoder, U8Decoder, U8Decoder, C>;
+
+    #[test]
+    fn duplicate_key_policy_works() {
+        let input = [3, 1, 10, 2, 20, 1, 30];
+
+        let mut decoder = Decoder::<HashMap<_, _>>::default();
+        let error = decoder.decode_exact(&input[..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut decoder = Decoder::<Vec<_>>::default();
+        decoder.set_duplicate_key_policy(DuplicateKeyPolicy::FirstWins);
+        let map = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        assert_eq!(map, [(1, 10), (2, 20)]);
+
+        let mut decoder = Decoder::<BTreeMap<_, _>>::default();
+        decoder.set_duplicate_key_policy(DuplicateKeyPolicy::LastWins);
+        let map = track_try_unwrap!(decoder.decode_exact(&input[..]));
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 30), (2, 20)]);
+    }
+
+    #[test]
+    fn max_entries_works() {
+        let mut decoder: MapDecoder<_, _, _, Vec<_>> =
+            MapDecoder::new(U16beDecoder::new(), U8Decoder::new(), U8Decoder::new());
+        assert_eq!(decoder.max_entries(), DEFAULT_MAX_ENTRIES);
+        let error = decoder.decode_exact(&[0xFF, 0xFF][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        decoder.set_max_entries(1);
+        let error = decoder.decode_exact(&[0, 2][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut output = Vec::new();
+        let mut encoder = MapEncoder::<U8Encoder, U8Encoder, U8Encoder, Vec<_>>::default();
+        assert!(encoder.start_encoding(vec![(0, 0); 256]).is_err());
+        track_try_unwrap!(encoder.start_encoding(vec![(1, 2)]));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [1, 1, 2]);
+    }
+}
@@ -0,0 +1,177 @@
+//! Metrics of encoding/decoding processes.
+//!
+//! Metrics are reported to implementations of the `Recorder` trait.
+//! `Counters` is a simple in-memory implementation of it,
+//! and a bridge to other metrics systems (e.g., Prometheus) can be implemented easily.
+use crate::ErrorKind;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// This trait allows for recording metrics of encoding/decoding processes.
+pub trait Recorder {
+    /// Records an invocation of `decode` or `encode` method (or an I/O operation).
+    fn record_call(&self);
+
+    /// Records the number of bytes consumed by a decoder or produced by an encoder.
+    fn record_bytes(&self, bytes: u64);
+
+    /// Records a completion of an item and the time spent to decode or encode it.
+    fn record_item(&self, elapsed: Duration);
+
+    /// Records an error.
+    fn record_error(&self, kind: ErrorKind);
+}
+impl<R: Recorder + ?Sized> Recorder for &R {
+    fn record_call(&self) {
+        (**self).record_call();
+    }
+
+    fn record_bytes(&self, bytes: u64) {
+        (**self).record_bytes(bytes);
+    }
+
+    fn record_item(&self, elapsed: Duration) {
+        (**self).record_item(elapsed);
+    }
+
+    fn record_error(&self, kind: ErrorKind) {
+        (**self).record_error(kind);
+    }
+}
+impl<R: Recorder + ?Sized> Recorder for Rc<R> {
+    fn record_call(&self) {
+        (**self).record_call();
+    }
+
+    fn record_bytes(&self, bytes: u64) {
+        (**self).record_bytes(bytes);
+    }
+
+    fn record_item(&self, elapsed: Duration) {
+        (**self).record_item(elapsed);
+    }
+
+    fn record_error(&self, kind: ErrorKind) {
+        (**self).record_error(kind);
+    }
+}
+impl<R: Recorder + ?Sized> Recorder for Arc<R> {
+    fn record_call(&self) {
+        (**self).record_call();
+    }
+
+    fn record_bytes(&self, bytes: u64) {
+        (**self).record_bytes(bytes);
+    }
+
+    fn record_item(&self, elapsed: Duration) {
+        (**self).record_item(elapsed);
+    }
+
+    fn record_error(&self, kind: ErrorKind) {
+        (**self).record_error(kind);
+    }
+}
+
+/// In-memory `Recorder` that counts the recorded values.
+///
+/// It can be shared by multiple encoders/decoders via `Arc` or references.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::{DecodeExt, ErrorKind};
+/// use bytecodec::fixnum::U16beDecoder;
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::metrics::Counters;
+/// use std::sync::Arc;
+///
+/// let counters = Arc::new(Counters::new());
+/// let mut decoder = U16beDecoder::new().instrumented(counters.clone());
+///
+/// assert_eq!(decoder.decode_exact(&[0, 1][..]).unwrap(), 1);
+/// assert!(decoder.decode_exact(&[0][..]).is_err());
+///
+/// assert_eq!(counters.bytes(), 3);
+/// assert_eq!(counters.items(), 1);
+/// assert_eq!(counters.errors(ErrorKind::UnexpectedEos), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct Counters {
+    calls: AtomicU64,
+    bytes: AtomicU64,
+    items: AtomicU64,
+    item_nanos: AtomicU64,
+    errors: [AtomicU64; ERROR_KINDS],
+}
+impl Counters {
+    /// Makes a new `Counters` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of recorded calls.
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of recorded bytes.
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of completed items.
+    pub fn items(&self) -> u64 {
+        self.items.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total time spent to decode or encode the completed items.
+    pub fn item_time(&self) -> Duration {
+        Duration::from_nanos(self.item_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Returns the number of recorded errors of the given kind.
+    pub fn errors(&self, kind: ErrorKind) -> u64 {
+        self.errors[error_kind_index(kind)].load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of recorded errors.
+    pub fn total_errors(&self) -> u64 {
+        self.errors.iter().map(|c| c.load(Ordering::Relaxed)).sum()
+    }
+}
+impl Recorder for Counters {
+    fn record_call(&self) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_bytes(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn record_item(&self, elapsed: Duration) {
+        self.items.fetch_add(1, Ordering::Relaxed);
+        self.item_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    fn record_error(&self, kind: ErrorKind) {
+        self.errors[error_kind_index(kind)].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+const ERROR_KINDS: usize = 7;
+
+fn error_kind_index(kind: ErrorKind) -> usize {
+    match kind {
+        ErrorKind::InvalidInput => 0,
+        ErrorKind::InconsistentState => 1,
+        ErrorKind::UnexpectedEos => 2,
+        ErrorKind::EncoderFull => 3,
+        ErrorKind::DecoderTerminated => 4,
+        ErrorKind::IncompleteDecoding => 5,
+        ErrorKind::Other => 6,
+    }
+}
@@ -81,6 +81,12 @@ impl<D: MonolithicDecode> Decode for MonolithicDecoder<D> {
     fn is_idle(&self) -> bool {
         self.item.is_some()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.item = None;
+        self.buf.clear();
+        Ok(())
+    }
 }
 
 /// This trait allows for encoding items monolithically to a destination byte stream.
@@ -167,6 +173,11 @@ impl<E: MonolithicEncode> Encode for MonolithicEncoder<E> {
             self.buf.requiring_bytes()
         }
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.item = None;
+        track!(self.buf.reset())
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,614 @@
+//! Encoder and decoder for multiplexing multiple item streams into a single byte stream.
+//!
+//! A multiplexed stream is a sequence of chunks.
+//! Each chunk consists of the following fields:
+//!
+//! ```text
+//! +--------------------+---------------------+---------------------+
+//! | channel ID (u32be) | body length (u32be) | body (length bytes) |
+//! +--------------------+---------------------+---------------------+
+//! ```
+//!
+//! The body of a chunk is a part of the byte sequence produced by the encoder of the channel.
+//! `MuxEncoder` produces such a stream and `DemuxDecoder` consumes it.
+use crate::combinator::Slice;
+use crate::fixnum::{U32beDecoder, U32beEncoder};
+use crate::tuple::{TupleDecoder, TupleEncoder};
+use crate::{ByteCount, Decode, Encode, Eos, ErrorKind, Result};
+use std::cmp;
+use std::collections::BTreeMap;
+
+/// The default maximum size of a chunk body.
+pub const DEFAULT_MAX_CHUNK_SIZE: u32 = 4096;
+
+/// Encoder that interleaves the outputs of multiple channel encoders into a single byte stream.
+///
+/// Each channel is served one chunk at a time.
+/// Channels with a higher priority are served first,
+/// and channels with the same priority are served in a round-robin manner.
+/// Because the size of a chunk is bounded by `max_chunk_size`,
+/// a large item on a channel does not block small items on the other channels.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::{Encode, EncodeExt};
+/// use bytecodec::bytes::BytesEncoder;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::mux::MuxEncoder;
+///
+/// let mut encoder = MuxEncoder::new();
+/// encoder.set_max_chunk_size(2);
+/// encoder.add_channel(1, BytesEncoder::new());
+/// encoder.add_channel(2, BytesEncoder::new());
+///
+/// encoder.start_encoding((1, b"foo".to_vec())).unwrap();
+/// encoder.start_encoding((2, b"x".to_vec())).unwrap();
+///
+/// let mut output = Vec::new();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(
+///     output,
+///     [
+///         0, 0, 0, 1, 0, 0, 0, 2, b'f', b'o', // channel 1
+///         0, 0, 0, 2, 0, 0, 0, 1, b'x', // channel 2
+///         0, 0, 0, 1, 0, 0, 0, 1, b'o', // channel 1
+///     ]
+/// );
+/// ```
+#[derive(Debug)]
+pub struct MuxEncoder<E> {
+    channels: BTreeMap<u32, Channel<E>>,
+    header: TupleEncoder<(U32beEncoder, U32beEncoder)>,
+    chunk: Option<Chunk>,
+    staged: Vec<u8>,
+    staged_offset: usize,
+    max_chunk_size: u32,
+    last_channel: Option<u32>,
+    suspended: Vec<u32>,
+}
+impl<E: Encode> MuxEncoder<E> {
+    /// Makes a new `MuxEncoder` instance that has no channels.
+    pub fn new() -> Self {
+        MuxEncoder {
+            channels: BTreeMap::new(),
+            header: Default::default(),
+            chunk: None,
+            staged: Vec::new(),
+            staged_offset: 0,
+            max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
+            last_channel: None,
+            suspended: Vec::new(),
+        }
+    }
+
+    /// Returns the maximum size of a chunk body.
+    pub fn max_chunk_size(&self) -> u32 {
+        self.max_chunk_size
+    }
+
+    /// Sets the maximum size of a chunk body.
+    ///
+    /// The new value is applied from the next chunk.
+    /// If `0` is specified, `1` is used instead.
+    pub fn set_max_chunk_size(&mut self, n: u32) {
+        self.max_chunk_size = cmp::max(n, 1);
+    }
+
+    /// Adds a channel that encodes items by using the given encoder.
+    ///
+    /// The priority of the channel is `0`.
+    /// If there is already a channel that has the same ID, it will be replaced and its encoder will be returned.
+    pub fn add_channel(&mut self, channel_id: u32, encoder: E) -> Option<E> {
+        let channel = Channel {
+            encoder: Slice::new(encoder),
+            priority: 0,
+        };
+        self.channels
+            .insert(channel_id, channel)
+            .map(|c| c.encoder.into_inner())
+    }
+
+    /// Removes the channel that has the given ID and returns its encoder.
+    ///
+    /// # Errors
+    ///
+    /// If a chunk of the channel is being encoded, an `ErrorKind::InvalidInput` error will be returned.
+    pub fn remove_channel(&mut self, channel_id: u32) -> Result<Option<E>> {
+        if let Some(ref chunk) = self.chunk {
+            track_assert_ne!(chunk.channel_id, channel_id, ErrorKind::InvalidInput);
+        }
+        Ok(self
+            .channels
+            .remove(&channel_id)
+            .map(|c| c.encoder.into_inner()))
+    }
+
+    /// Sets the priority of the channel that has the given ID.
+    ///
+    /// Channels with a higher priority are served first.
+    ///
+    /// # Errors
+    ///
+    /// If there is no such channel, an `ErrorKind::InvalidInput` error will be returned.
+    pub fn set_channel_priority(&mut self, channel_id: u32, priority: u8) -> Result<()> {
+        let channel = track_assert_some!(
+            self.channels.get_mut(&channel_id),
+            ErrorKind::InvalidInput;
+            channel_id
+        );
+        channel.priority = priority;
+        Ok(())
+    }
+
+    /// Returns a reference to the encoder of the channel that has the given ID.
+    pub fn channel_ref(&self, channel_id: u32) -> Option<&E> {
+        self.channels
+            .get(&channel_id)
+            .map(|c| c.encoder.inner_ref())
+    }
+
+    /// Returns a mutable reference to the encoder of the channel that has the given ID.
+    pub fn channel_mut(&mut self, channel_id: u32) -> Option<&mut E> {
+        self.channels
+            .get_mut(&channel_id)
+            .map(|c| c.encoder.inner_mut())
+    }
+
+    /// Returns an iterator that iterates over the IDs of the channels.
+    pub fn channel_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.channels.keys().cloned()
+    }
+
+    fn next_channel(&self) -> Option<u32> {
+        let skip = &self.suspended;
+        let priority = self
+            .channels
+            .iter()
+            .filter(|(id, c)| !c.encoder.is_idle() && !skip.contains(id))
+            .map(|(_, c)| c.priority)
+            .max()?;
+        let mut candidates = self
+            .channels
+            .iter()
+            .filter(|(id, c)| !c.encoder.is_idle() && c.priority == priority && !skip.contains(id))
+            .map(|(&id, _)| id);
+        let first = candidates.next();
+        if let Some(last) = self.last_channel {
+            if let Some(id) = first.into_iter().chain(candidates).find(|&id| id > last) {
+                return Some(id);
+            }
+        }
+        first
+    }
+
+    fn start_chunk(&mut self, channel_id: u32) -> Result<bool> {
+        let max_chunk_size = self.max_chunk_size;
+        let channel = self.channels.get_mut(&channel_id).expect("Never fails");
+        let (len, staged) = match channel.encoder.requiring_bytes() {
+            ByteCount::Finite(n) => (cmp::min(n, u64::from(max_chunk_size)) as u32, false),
+            _ => {
+                // The staging buffer is reused across chunks to avoid an allocation per chunk
+                self.staged.resize(max_chunk_size as usize, 0);
+                channel
+                    .encoder
+                    .set_consumable_bytes(u64::from(max_chunk_size));
+                let size = track!(channel.encoder.encode(&mut self.staged, Eos::new(false)))?;
+                self.staged.truncate(size);
+                self.staged_offset = 0;
+                (size as u32, true)
+            }
+        };
+        if len == 0 {
+            return Ok(false);
+        }
+
+        if !staged {
+            channel.encoder.set_consumable_bytes(u64::from(len));
+        }
+        track!(self.header.start_encoding((channel_id, len)))?;
+        self.chunk = Some(Chunk {
+            channel_id,
+            remaining_bytes: len,
+            staged,
+        });
+        self.last_channel = Some(channel_id);
+        Ok(true)
+    }
+
+    fn encode_chunk_body(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let chunk = self.chunk.as_mut().expect("Never fails");
+        let size = if chunk.staged {
+            let size = cmp::min(buf.len(), self.staged.len() - self.staged_offset);
+            buf[..size].copy_from_slice(&self.staged[self.staged_offset..][..size]);
+            self.staged_offset += size;
+            size
+        } else {
+            let channel = track_assert_some!(
+                self.channels.get_mut(&chunk.channel_id),
+                ErrorKind::InconsistentState
+            );
+            let size = track!(channel.encoder.encode(buf, eos))?;
+            let expected = cmp::min(buf.len(), chunk.remaining_bytes as usize);
+            track_assert_eq!(size, expected, ErrorKind::InconsistentState,
+                             "The channel encoder has been suspended in the middle of a chunk";
+                             chunk.channel_id);
+            size
+        };
+        chunk.remaining_bytes -= size as u32;
+        if chunk.remaining_bytes == 0 {
+            self.chunk = None;
+        }
+        Ok(size)
+    }
+}
+impl<E: Encode> Encode for MuxEncoder<E> {
+    type Item = (u32, E::Item);
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        self.suspended.clear();
+        loop {
+            if self.chunk.is_some() {
+                bytecodec_try_encode!(self.header, offset, buf, eos);
+                offset += track!(self.encode_chunk_body(&mut buf[offset..], eos))?;
+                if self.chunk.is_some() {
+                    return Ok(offset);
+                }
+            }
+            if offset == buf.len() {
+                return Ok(offset);
+            }
+
+            let channel_id = if let Some(id) = self.next_channel() {
+                id
+            } else {
+                return Ok(offset);
+            };
+            if !track!(self.start_chunk(channel_id))? {
+                // The channel encoder is suspended
+                self.suspended.push(channel_id);
+            }
+        }
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let (channel_id, item) = item;
+        let channel = track_assert_some!(
+            self.channels.get_mut(&channel_id),
+            ErrorKind::InvalidInput;
+            channel_id
+        );
+        track!(channel.encoder.start_encoding(item); channel_id)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.is_idle() {
+            ByteCount::Finite(0)
+        } else {
+            ByteCount::Unknown
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.chunk.is_none() && self.channels.values().all(|c| c.encoder.is_idle())
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        for (channel_id, channel) in &mut self.channels {
+            track!(channel.encoder.reset(); channel_id)?;
+        }
+        track!(self.header.reset())?;
+        self.staged.clear();
+        self.staged_offset = 0;
+        self.chunk = None;
+        self.last_channel = None;
+        Ok(())
+    }
+}
+impl<E: Encode> Default for MuxEncoder<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decoder that routes the chunks of a multiplexed byte stream to the decoders of the channels.
+///
+/// The body of each chunk is fed to the decoder of the corresponding channel as it arrives
+/// (i.e., whole items are never buffered by this decoder),
+/// and a `(channel_id, item)` pair is yielded whenever the channel decoder becomes idle.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Decode;
+/// use bytecodec::fixnum::U8Decoder;
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::mux::DemuxDecoder;
+///
+/// let mut decoder = DemuxDecoder::new();
+/// decoder.add_channel(1, U8Decoder::new());
+/// decoder.add_channel(2, U8Decoder::new());
+///
+/// let mut input = &[0, 0, 0, 2, 0, 0, 0, 2, 10, 20, 0, 0, 0, 1, 0, 0, 0, 1, 30][..];
+/// assert_eq!(decoder.decode_exact(&mut input).unwrap(), (2, 10));
+/// assert_eq!(decoder.decode_exact(&mut input).unwrap(), (2, 20));
+/// assert_eq!(decoder.decode_exact(&mut input).unwrap(), (1, 30));
+/// ```
+#[derive(Debug)]
+pub struct DemuxDecoder<D: Decode> {
+    channels: BTreeMap<u32, Slice<D>>,
+    header: TupleDecoder<(U32beDecoder, U32beDecoder)>,
+    chunk: Option<Chunk>,
+    item: Option<(u32, D::Item)>,
+}
+impl<D: Decode> DemuxDecoder<D> {
+    /// Makes a new `DemuxDecoder` instance that has no channels.
+    pub fn new() -> Self {
+        DemuxDecoder {
+            channels: BTreeMap::new(),
+            header: Default::default(),
+            chunk: None,
+            item: None,
+        }
+    }
+
+    /// Adds a channel that decodes items by using the given decoder.
+    ///
+    /// If there is already a channel that has the same ID, it will be replaced and its decoder will be returned.
+    pub fn add_channel(&mut self, channel_id: u32, decoder: D) -> Option<D> {
+        let mut decoder = Slice::new(decoder);
+        if let Some(ref chunk) = self.chunk {
+            if chunk.channel_id == channel_id {
+                decoder.set_consumable_bytes(u64::from(chunk.remaining_bytes));
+            }
+        }
+        self.channels
+            .insert(channel_id, decoder)
+            .map(Slice::into_inner)
+    }
+
+    /// Removes the channel that has the given ID and returns its decoder.
+    ///
+    /// # Errors
+    ///
+    /// If a chunk of the channel is being decoded, an `ErrorKind::InvalidInput` error will be returned.
+    pub fn remove_channel(&mut self, channel_id: u32) -> Result<Option<D>> {
+        if let Some(ref chunk) = self.chunk {
+            track_assert_ne!(chunk.channel_id, channel_id, ErrorKind::InvalidInput);
+        }
+        Ok(self.channels.remove(&channel_id).map(Slice::into_inner))
+    }
+
+    /// Returns a reference to the decoder of the channel that has the given ID.
+    pub fn channel_ref(&self, channel_id: u32) -> Option<&D> {
+        self.channels.get(&channel_id).map(Slice::inner_ref)
+    }
+
+    /// Returns a mutable reference to the decoder of the channel that has the given ID.
+    pub fn channel_mut(&mut self, channel_id: u32) -> Option<&mut D> {
+        self.channels.get_mut(&channel_id).map(Slice::inner_mut)
+    }
+
+    /// Returns an iterator that iterates over the IDs of the channels.
+    pub fn channel_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.channels.keys().cloned()
+    }
+}
+impl<D: Decode> Decode for DemuxDecoder<D> {
+    type Item = (u32, D::Item);
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        while self.item.is_none() {
+            if self.chunk.is_none() {
+                bytecodec_try_decode!(self.header, offset, buf, eos);
+                let (channel_id, len) = track!(self.header.finish_decoding())?;
+                let decoder = track_assert_some!(
+                    self.channels.get_mut(&channel_id),
+                    ErrorKind::InvalidInput,
+                    "Unknown channel";
+                    channel_id
+                );
+                decoder.set_consumable_bytes(u64::from(len));
+                self.chunk = Some(Chunk {
+                    channel_id,
+                    remaining_bytes: len,
+                    staged: false,
+                });
+            }
+
+            let chunk = self.chunk.as_mut().expect("Never fails");
+            let decoder = track_assert_some!(
+                self.channels.get_mut(&chunk.channel_id),
+                ErrorKind::InconsistentState
+            );
+            let size = track!(decoder
+                .decode(&buf[offset..], eos)
+                .map_err(|e| e.with_offset(offset as u64)); chunk.channel_id)?;
+            offset += size;
+            chunk.remaining_bytes -= size as u32;
+            if decoder.is_idle() {
+                let item = track!(decoder.finish_decoding(); chunk.channel_id)?;
+                self.item = Some((chunk.channel_id, item));
+            }
+            if chunk.remaining_bytes == 0 {
+                self.chunk = None;
+            } else if offset == buf.len() {
+                break;
+            }
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = track_assert_some!(self.item.take(), ErrorKind::IncompleteDecoding);
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.item.is_some() {
+            ByteCount::Finite(0)
+        } else if self.chunk.is_none() {
+            self.header.requiring_bytes()
+        } else {
+            ByteCount::Unknown
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.item.is_some()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        for (channel_id, decoder) in &mut self.channels {
+            track!(decoder.reset(); channel_id)?;
+        }
+        track!(self.header.reset())?;
+        self.chunk = None;
+        self.item = None;
+        Ok(())
+    }
+}
+impl<D: Decode> Default for DemuxDecoder<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+struct Channel<E> {
+    encoder: Slice<E>,
+    priority: u8,
+}
+
+#[derive(Debug)]
+struct Chunk {
+    channel_id: u32,
+    remaining_bytes: u32,
+    staged: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bytes::{BytesEncoder, Utf8Decoder};
+    use crate::fixnum::{U8Decoder, U8Encoder};
+    use crate::io::{IoDecodeExt, IoEncodeExt};
+    use crate::{DecodeExt, EncodeExt};
+
+    #[test]
+    fn mux_encoder_works() {
+        let mut encoder = MuxEncoder::new();
+        encoder.set_max_chunk_size(3);
+        encoder.add_channel(1, BytesEncoder::new());
+        encoder.add_channel(2, BytesEncoder::new());
+
+        track_try_unwrap!(encoder.start_encoding((1, b"foobar".to_vec())));
+        track_try_unwrap!(encoder.start_encoding((2, b"baz".to_vec())));
+        assert!(encoder.start_encoding((2, b"qux".to_vec())).is_err());
+        assert!(encoder.start_encoding((3, b"qux".to_vec())).is_err());
+
+        let mut output = Vec::new();
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(
+            output,
+            [
+                0, 0, 0, 1, 0, 0, 0, 3, b'f', b'o', b'o', //
+                0, 0, 0, 2, 0, 0, 0, 3, b'b', b'a', b'z', //
+                0, 0, 0, 1, 0, 0, 0, 3, b'b', b'a', b'r',
+            ]
+        );
+        assert!(encoder.is_idle());
+    }
+
+    #[test]
+    fn mux_encoder_priority_works() {
+        let mut encoder = MuxEncoder::new();
+        encoder.set_max_chunk_size(1);
+        encoder.add_channel(1, BytesEncoder::new());
+        encoder.add_channel(2, BytesEncoder::new());
+        track_try_unwrap!(encoder.set_channel_priority(2, 1));
+
+        track_try_unwrap!(encoder.start_encoding((1, b"ab".to_vec())));
+        track_try_unwrap!(encoder.start_encoding((2, b"xy".to_vec())));
+
+        let mut output = Vec::new();
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        let bodies = output.chunks(9).map(|c| (c[3], c[8])).collect::<Vec<_>>();
+        assert_eq!(bodies, [(2, b'x'), (2, b'y'), (1, b'a'), (1, b'b')]);
+    }
+
+    #[test]
+    fn mux_encoder_stages_unknown_size_items() {
+        let mut encoder = MuxEncoder::new();
+        encoder.set_max_chunk_size(2);
+        encoder.add_channel(7, U8Encoder::new().repeat());
+
+        track_try_unwrap!(encoder.start_encoding((7, vec![1, 2, 3].into_iter())));
+
+        let mut output = [0; 64];
+        let size = track_try_unwrap!(encoder.encode(&mut output[..4], Eos::new(false)));
+        assert_eq!(size, 4);
+        let size = size + track_try_unwrap!(encoder.encode(&mut output[size..], Eos::new(false)));
+        assert_eq!(
+            &output[..size],
+            [0, 0, 0, 7, 0, 0, 0, 2, 1, 2, 0, 0, 0, 7, 0, 0, 0, 1, 3]
+        );
+        assert!(encoder.is_idle());
+
+        // The staging buffer is reused by subsequent chunks.
+        let staged = encoder.staged.as_ptr();
+        track_try_unwrap!(encoder.start_encoding((7, vec![4, 5, 6].into_iter())));
+        let size = track_try_unwrap!(encoder.encode(&mut output, Eos::new(false)));
+        assert_eq!(
+            &output[..size],
+            [0, 0, 0, 7, 0, 0, 0, 2, 4, 5, 0, 0, 0, 7, 0, 0, 0, 1, 6]
+        );
+        assert_eq!(encoder.staged.as_ptr(), staged);
+    }
+
+    #[test]
+    fn demux_decoder_works() {
+        let mut encoder = MuxEncoder::new();
+        encoder.set_max_chunk_size(2);
+        encoder.add_channel(1, BytesEncoder::new().length(3).pre_encode());
+        encoder.add_channel(2, BytesEncoder::new().length(1).pre_encode());
+        track_try_unwrap!(encoder.start_encoding((1, b"foo".to_vec())));
+        track_try_unwrap!(encoder.start_encoding((2, b"x".to_vec())));
+        let mut output = Vec::new();
+        track_try_unwrap!(encoder.encode_all(&mut output));
+
+        let mut decoder = DemuxDecoder::new();
+        decoder.add_channel(1, Utf8Decoder::new().length(3));
+        decoder.add_channel(2, Utf8Decoder::new().length(1));
+
+        let mut input = &output[..];
+        let item = track_try_unwrap!(decoder.decode_exact(&mut input));
+        assert_eq!(item, (2, "x".to_owned()));
+        let item = track_try_unwrap!(decoder.decode_exact(&mut input));
+        assert_eq!(item, (1, "foo".to_owned()));
+        assert!(input.is_empty());
+
+        track_try_unwrap!(decoder.remove_channel(2));
+        let mut input = &[0, 0, 0, 2, 0, 0, 0, 1, 0][..];
+        assert!(decoder.decode_exact(&mut input).is_err());
+    }
+
+    #[test]
+    fn demux_decoder_handles_multiple_items_in_a_chunk() {
+        let mut decoder = DemuxDecoder::new();
+        decoder.add_channel(3, U8Decoder::new());
+
+        let input = [0, 0, 0, 3, 0, 0, 0, 3, 1, 2, 3];
+        let mut offset = 0;
+        let mut items = Vec::new();
+        while offset < input.len() {
+            offset += track_try_unwrap!(decoder.decode(&input[offset..], Eos::new(false)));
+            if decoder.is_idle() {
+                items.push(track_try_unwrap!(decoder.finish_decoding()));
+            }
+        }
+        assert_eq!(items, [(3, 1), (3, 2), (3, 3)]);
+    }
+}
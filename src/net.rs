@@ -0,0 +1,532 @@
+//! Encoders and decoders for network addresses.
+//!
+//! All multi-byte values are encoded in network byte order (i.e., big-endian).
+use crate::bytes::{BytesEncoder, CopyableBytesDecoder};
+use crate::combinator::Either;
+use crate::fixnum::{U16beDecoder, U16beEncoder, U8Decoder, U8Encoder};
+use crate::sum::{EitherDecoder, EitherEncoder};
+use crate::tuple::{TupleDecoder, TupleEncoder};
+use crate::{ByteCount, Decode, Encode, Eos, Result, SizedEncode};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+/// Decoder which decodes `Ipv4Addr` values.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::net::Ipv4AddrDecoder;
+/// use std::net::Ipv4Addr;
+///
+/// let mut decoder = Ipv4AddrDecoder::new();
+/// let item = decoder.decode_exact([127, 0, 0, 1].as_ref()).unwrap();
+/// assert_eq!(item, Ipv4Addr::LOCALHOST);
+/// ```
+#[derive(Debug, Default)]
+pub struct Ipv4AddrDecoder(CopyableBytesDecoder<[u8; 4]>);
+impl Ipv4AddrDecoder {
+    /// Makes a new `Ipv4AddrDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode_item(b: [u8; 4]) -> Ipv4Addr {
+        Ipv4Addr::from(b)
+    }
+}
+impl_newtype_decode!(Ipv4AddrDecoder, Ipv4Addr);
+
+/// Encoder which encodes `Ipv4Addr` values.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::net::Ipv4AddrEncoder;
+/// use std::net::Ipv4Addr;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = Ipv4AddrEncoder::with_item(Ipv4Addr::LOCALHOST).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [127, 0, 0, 1]);
+/// ```
+#[derive(Debug, Default)]
+pub struct Ipv4AddrEncoder(BytesEncoder<[u8; 4]>);
+impl Ipv4AddrEncoder {
+    /// Makes a new `Ipv4AddrEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn encode_item(item: Ipv4Addr) -> [u8; 4] {
+        item.octets()
+    }
+}
+impl_newtype_encode!(Ipv4AddrEncoder, Ipv4Addr);
+
+/// Decoder which decodes `Ipv6Addr` values.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::net::Ipv6AddrDecoder;
+/// use std::net::Ipv6Addr;
+///
+/// let mut decoder = Ipv6AddrDecoder::new();
+/// let item = decoder.decode_exact(Ipv6Addr::LOCALHOST.octets().as_ref()).unwrap();
+/// assert_eq!(item, Ipv6Addr::LOCALHOST);
+/// ```
+#[derive(Debug, Default)]
+pub struct Ipv6AddrDecoder(CopyableBytesDecoder<[u8; 16]>);
+impl Ipv6AddrDecoder {
+    /// Makes a new `Ipv6AddrDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode_item(b: [u8; 16]) -> Ipv6Addr {
+        Ipv6Addr::from(b)
+    }
+}
+impl_newtype_decode!(Ipv6AddrDecoder, Ipv6Addr);
+
+/// Encoder which encodes `Ipv6Addr` values.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::net::Ipv6AddrEncoder;
+/// use std::net::Ipv6Addr;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = Ipv6AddrEncoder::with_item(Ipv6Addr::LOCALHOST).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+/// ```
+#[derive(Debug, Default)]
+pub struct Ipv6AddrEncoder(BytesEncoder<[u8; 16]>);
+impl Ipv6AddrEncoder {
+    /// Makes a new `Ipv6AddrEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn encode_item(item: Ipv6Addr) -> [u8; 16] {
+        item.octets()
+    }
+}
+impl_newtype_encode!(Ipv6AddrEncoder, Ipv6Addr);
+
+/// Decoder which decodes `SocketAddrV4` values.
+///
+/// A socket address consists of an IPv4 address followed by a 16-bit port number.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::net::SocketAddrV4Decoder;
+/// use std::net::{Ipv4Addr, SocketAddrV4};
+///
+/// let mut decoder = SocketAddrV4Decoder::new();
+/// let item = decoder.decode_exact([127, 0, 0, 1, 0x1F, 0x90].as_ref()).unwrap();
+/// assert_eq!(item, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080));
+/// ```
+#[derive(Debug, Default)]
+pub struct SocketAddrV4Decoder(TupleDecoder<(Ipv4AddrDecoder, U16beDecoder)>);
+impl SocketAddrV4Decoder {
+    /// Makes a new `SocketAddrV4Decoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode_item((ip, port): (Ipv4Addr, u16)) -> SocketAddrV4 {
+        SocketAddrV4::new(ip, port)
+    }
+}
+impl_newtype_decode!(SocketAddrV4Decoder, SocketAddrV4);
+
+/// Encoder which encodes `SocketAddrV4` values.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::net::SocketAddrV4Encoder;
+/// use std::net::{Ipv4Addr, SocketAddrV4};
+///
+/// let mut output = Vec::new();
+/// let item = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080);
+/// let mut encoder = SocketAddrV4Encoder::with_item(item).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [127, 0, 0, 1, 0x1F, 0x90]);
+/// ```
+#[derive(Debug, Default)]
+pub struct SocketAddrV4Encoder(TupleEncoder<(Ipv4AddrEncoder, U16beEncoder)>);
+impl SocketAddrV4Encoder {
+    /// Makes a new `SocketAddrV4Encoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn encode_item(item: SocketAddrV4) -> (Ipv4Addr, u16) {
+        (*item.ip(), item.port())
+    }
+}
+impl_newtype_encode!(SocketAddrV4Encoder, SocketAddrV4);
+
+/// Decoder which decodes `SocketAddrV6` values.
+///
+/// A socket address consists of an IPv6 address followed by a 16-bit port number.
+/// The flow information and the scope ID of decoded addresses are always `0`.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::net::SocketAddrV6Decoder;
+/// use std::net::{Ipv6Addr, SocketAddrV6};
+///
+/// let mut decoder = SocketAddrV6Decoder::new();
+/// let mut input = Ipv6Addr::LOCALHOST.octets().to_vec();
+/// input.extend_from_slice(&[0x1F, 0x90]);
+///
+/// let item = decoder.decode_exact(&input[..]).unwrap();
+/// assert_eq!(item, SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 0));
+/// ```
+#[derive(Debug, Default)]
+pub struct SocketAddrV6Decoder(TupleDecoder<(Ipv6AddrDecoder, U16beDecoder)>);
+impl SocketAddrV6Decoder {
+    /// Makes a new `SocketAddrV6Decoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode_item((ip, port): (Ipv6Addr, u16)) -> SocketAddrV6 {
+        SocketAddrV6::new(ip, port, 0, 0)
+    }
+}
+impl_newtype_decode!(SocketAddrV6Decoder, SocketAddrV6);
+
+/// Encoder which encodes `SocketAddrV6` values.
+///
+/// The flow information and the scope ID of the given addresses are not encoded.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::net::SocketAddrV6Encoder;
+/// use std::net::{Ipv6Addr, SocketAddrV6};
+///
+/// let mut output = Vec::new();
+/// let item = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 0);
+/// let mut encoder = SocketAddrV6Encoder::with_item(item).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(&output[14..], [0, 1, 0x1F, 0x90]);
+/// ```
+#[derive(Debug, Default)]
+pub struct SocketAddrV6Encoder(TupleEncoder<(Ipv6AddrEncoder, U16beEncoder)>);
+impl SocketAddrV6Encoder {
+    /// Makes a new `SocketAddrV6Encoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn encode_item(item: SocketAddrV6) -> (Ipv6Addr, u16) {
+        (*item.ip(), item.port())
+    }
+}
+impl_newtype_encode!(SocketAddrV6Encoder, SocketAddrV6);
+
+/// Decoder which decodes 6-byte MAC addresses.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::net::MacAddrDecoder;
+///
+/// let mut decoder = MacAddrDecoder::new();
+/// let item = decoder.decode_exact([0, 0x1B, 0x44, 0x11, 0x3A, 0xB7].as_ref()).unwrap();
+/// assert_eq!(item, [0, 0x1B, 0x44, 0x11, 0x3A, 0xB7]);
+/// ```
+#[derive(Debug, Default)]
+pub struct MacAddrDecoder(CopyableBytesDecoder<[u8; 6]>);
+impl MacAddrDecoder {
+    /// Makes a new `MacAddrDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode_item(b: [u8; 6]) -> [u8; 6] {
+        b
+    }
+}
+impl_newtype_decode!(MacAddrDecoder, [u8; 6]);
+
+/// Encoder which encodes 6-byte MAC addresses.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::net::MacAddrEncoder;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = MacAddrEncoder::with_item([0, 0x1B, 0x44, 0x11, 0x3A, 0xB7]).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 0x1B, 0x44, 0x11, 0x3A, 0xB7]);
+/// ```
+#[derive(Debug, Default)]
+pub struct MacAddrEncoder(BytesEncoder<[u8; 6]>);
+impl MacAddrEncoder {
+    /// Makes a new `MacAddrEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn encode_item(item: [u8; 6]) -> [u8; 6] {
+        item
+    }
+}
+impl_newtype_encode!(MacAddrEncoder, [u8; 6]);
+
+/// Decoder for `IpAddr` values prefixed by an address family tag.
+///
+/// By default, the tag is a `u8` value and `4` (IPv4) or `6` (IPv6) is used.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::net::IpAddrDecoder;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let mut decoder = IpAddrDecoder::new();
+/// let item = decoder.decode_exact([4, 127, 0, 0, 1].as_ref()).unwrap();
+/// assert_eq!(item, IpAddr::V4(Ipv4Addr::LOCALHOST));
+///
+/// assert!(decoder.decode_exact([5, 127, 0, 0, 1].as_ref()).is_err());
+/// ```
+pub struct IpAddrDecoder<T: Decode = U8Decoder>(EitherDecoder<Ipv4AddrDecoder, Ipv6AddrDecoder, T>);
+impl IpAddrDecoder {
+    /// Makes a new `IpAddrDecoder` instance that uses `4` and `6` as the family tags.
+    pub fn new() -> Self {
+        Self::with_tag_decoder(U8Decoder::new(), (4, 6))
+    }
+}
+impl<T: Decode> IpAddrDecoder<T> {
+    /// Makes a new `IpAddrDecoder` instance that decodes the family tag by using `tag_decoder`.
+    ///
+    /// `tags.0` and `tags.1` are the tag values of IPv4 and IPv6 respectively.
+    pub fn with_tag_decoder(tag_decoder: T, tags: (T::Item, T::Item)) -> Self {
+        IpAddrDecoder(EitherDecoder::with_tag_decoder(
+            Ipv4AddrDecoder::new(),
+            Ipv6AddrDecoder::new(),
+            tag_decoder,
+            tags,
+        ))
+    }
+
+    /// Returns the tag values of IPv4 and IPv6.
+    pub fn tags(&self) -> &(T::Item, T::Item) {
+        self.0.tags()
+    }
+}
+impl<T> Decode for IpAddrDecoder<T>
+where
+    T: Decode,
+    T::Item: PartialEq + fmt::Debug,
+{
+    type Item = IpAddr;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        match track!(self.0.finish_decoding())? {
+            Either::Left(ip) => Ok(IpAddr::V4(ip)),
+            Either::Right(ip) => Ok(IpAddr::V6(ip)),
+        }
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<T> fmt::Debug for IpAddrDecoder<T>
+where
+    T: Decode + fmt::Debug,
+    T::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IpAddrDecoder").field(&self.0).finish()
+    }
+}
+impl Default for IpAddrDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Encoder for `IpAddr` values prefixed by an address family tag.
+///
+/// By default, the tag is a `u8` value and `4` (IPv4) or `6` (IPv6) is used.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::fixnum::U16beEncoder;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::net::IpAddrEncoder;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let mut output = Vec::new();
+/// let mut encoder = IpAddrEncoder::with_tag_encoder(U16beEncoder::new(), (1, 2));
+/// encoder.start_encoding(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 1, 127, 0, 0, 1]);
+/// ```
+pub struct IpAddrEncoder<T: Encode = U8Encoder>(EitherEncoder<Ipv4AddrEncoder, Ipv6AddrEncoder, T>);
+impl IpAddrEncoder {
+    /// Makes a new `IpAddrEncoder` instance that uses `4` and `6` as the family tags.
+    pub fn new() -> Self {
+        Self::with_tag_encoder(U8Encoder::new(), (4, 6))
+    }
+}
+impl<T: Encode> IpAddrEncoder<T> {
+    /// Makes a new `IpAddrEncoder` instance that encodes the family tag by using `tag_encoder`.
+    ///
+    /// `tags.0` and `tags.1` are the tag values of IPv4 and IPv6 respectively.
+    pub fn with_tag_encoder(tag_encoder: T, tags: (T::Item, T::Item)) -> Self {
+        IpAddrEncoder(EitherEncoder::with_tag_encoder(
+            Ipv4AddrEncoder::new(),
+            Ipv6AddrEncoder::new(),
+            tag_encoder,
+            tags,
+        ))
+    }
+
+    /// Returns the tag values of IPv4 and IPv6.
+    pub fn tags(&self) -> &(T::Item, T::Item) {
+        self.0.tags()
+    }
+}
+impl<T> Encode for IpAddrEncoder<T>
+where
+    T: Encode,
+    T::Item: Clone,
+{
+    type Item = IpAddr;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let item = match item {
+            IpAddr::V4(ip) => Either::Left(ip),
+            IpAddr::V6(ip) => Either::Right(ip),
+        };
+        track!(self.0.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<T> SizedEncode for IpAddrEncoder<T>
+where
+    T: SizedEncode,
+    T::Item: Clone,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+impl<T> fmt::Debug for IpAddrEncoder<T>
+where
+    T: Encode + fmt::Debug,
+    T::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IpAddrEncoder").field(&self.0).finish()
+    }
+}
+impl Default for IpAddrEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::{IoDecodeExt, IoEncodeExt};
+    use crate::ErrorKind;
+
+    #[test]
+    fn ip_addr_works() {
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let v6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+
+        let mut output = Vec::new();
+        let mut encoder = IpAddrEncoder::new();
+        track_try_unwrap!(encoder.start_encoding(v4));
+        assert_eq!(encoder.exact_requiring_bytes(), 5);
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        track_try_unwrap!(encoder.start_encoding(v6));
+        assert_eq!(encoder.exact_requiring_bytes(), 17);
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output.len(), 22);
+        assert_eq!(output[5], 6);
+
+        let mut decoder = IpAddrDecoder::new();
+        let mut input = &output[..];
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), v4);
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), v6);
+
+        let error = decoder.decode_exact(&[4, 127, 0][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+    }
+
+    #[test]
+    fn socket_addr_works() {
+        let addr = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0);
+        let mut output = Vec::new();
+        let mut encoder = SocketAddrV6Encoder::new();
+        track_try_unwrap!(encoder.start_encoding(addr));
+        assert_eq!(encoder.exact_requiring_bytes(), 18);
+        track_try_unwrap!(encoder.encode_all(&mut output));
+
+        let mut decoder = SocketAddrV6Decoder::new();
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&output[..])), addr);
+    }
+}
@@ -24,6 +24,10 @@ impl Decode for NullDecoder {
     fn requiring_bytes(&self) -> ByteCount {
         ByteCount::Finite(0)
     }
+
+    fn reset(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Null encoder.
@@ -49,6 +53,10 @@ impl Encode for NullEncoder {
     fn is_idle(&self) -> bool {
         true
     }
+
+    fn reset(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 impl SizedEncode for NullEncoder {
     fn exact_requiring_bytes(&self) -> u64 {
@@ -72,10 +80,10 @@ mod test {
     fn null_encoder_works() {
         let mut encoder = NullEncoder;
         encoder.start_encoding(()).unwrap();
-        assert_eq!(encoder.is_idle(), true);
+        assert!(encoder.is_idle());
 
         let mut buf = [0; 10];
         assert_eq!(encoder.encode(&mut buf[..], Eos::new(true)).ok(), Some(0));
-        assert_eq!(encoder.is_idle(), true);
+        assert!(encoder.is_idle());
     }
 }
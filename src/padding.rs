@@ -65,6 +65,11 @@ impl Decode for PaddingDecoder {
             ByteCount::Infinite
         }
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.eos = false;
+        Ok(())
+    }
 }
 
 /// Encoder for writing padding bytes to output streams.
@@ -116,6 +121,11 @@ impl Encode for PaddingEncoder {
     fn is_idle(&self) -> bool {
         self.padding_byte.is_none()
     }
+
+    fn reset(&mut self) -> Result<()> {
+        self.padding_byte = None;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,786 @@
+//! Encoders and decoders for sum types (i.e., `Either`, `Option` and `Result`).
+//!
+//! An encoded value consists of a discriminant followed by the payload of the variant.
+//! By default, the discriminant is a single byte (`0` for the first variant and `1` for the second one),
+//! but any encoder/decoder and values can be used for the discriminant.
+use crate::combinator::Either;
+use crate::fixnum::{U8Decoder, U8Encoder};
+use crate::null::{NullDecoder, NullEncoder};
+use crate::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
+use std::fmt;
+
+/// Decoder for `Either` values prefixed by a discriminant.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::combinator::Either;
+/// use bytecodec::fixnum::{U16beDecoder, U8Decoder};
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::sum::EitherDecoder;
+///
+/// let mut decoder = EitherDecoder::new(U8Decoder::new(), U16beDecoder::new());
+/// let mut input = &[0, 7, 1, 0, 8][..];
+/// assert_eq!(decoder.decode_exact(&mut input).unwrap(), Either::Left(7));
+/// assert_eq!(decoder.decode_exact(&mut input).unwrap(), Either::Right(8));
+/// ```
+#[derive(Debug)]
+pub struct EitherDecoder<A, B, T: Decode = U8Decoder> {
+    left: A,
+    right: B,
+    tag: T,
+    tags: (T::Item, T::Item),
+    branch: Option<Branch>,
+}
+impl<A, B> EitherDecoder<A, B> {
+    /// Makes a new `EitherDecoder` instance that uses `0` (left) and `1` (right) as the discriminant byte.
+    pub fn new(left: A, right: B) -> Self {
+        Self::with_tag_decoder(left, right, U8Decoder::new(), (0, 1))
+    }
+}
+impl<A, B, T: Decode> EitherDecoder<A, B, T> {
+    /// Makes a new `EitherDecoder` instance that decodes the discriminant by using `tag_decoder`.
+    ///
+    /// `tags.0` and `tags.1` are the discriminant values of the left and the right variants respectively.
+    pub fn with_tag_decoder(left: A, right: B, tag_decoder: T, tags: (T::Item, T::Item)) -> Self {
+        EitherDecoder {
+            left,
+            right,
+            tag: tag_decoder,
+            tags,
+            branch: None,
+        }
+    }
+
+    /// Returns the discriminant values of the left and the right variants.
+    pub fn tags(&self) -> &(T::Item, T::Item) {
+        &self.tags
+    }
+
+    /// Returns a reference to the decoder of the left variant.
+    pub fn left_ref(&self) -> &A {
+        &self.left
+    }
+
+    /// Returns a mutable reference to the decoder of the left variant.
+    pub fn left_mut(&mut self) -> &mut A {
+        &mut self.left
+    }
+
+    /// Returns a reference to the decoder of the right variant.
+    pub fn right_ref(&self) -> &B {
+        &self.right
+    }
+
+    /// Returns a mutable reference to the decoder of the right variant.
+    pub fn right_mut(&mut self) -> &mut B {
+        &mut self.right
+    }
+
+    /// Takes ownership of this instance and returns the decoders of the left and the right variants.
+    pub fn into_inner(self) -> (A, B) {
+        (self.left, self.right)
+    }
+}
+impl<A, B, T> Decode for EitherDecoder<A, B, T>
+where
+    A: Decode,
+    B: Decode,
+    T: Decode,
+    T::Item: PartialEq + fmt::Debug,
+{
+    type Item = Either<A::Item, B::Item>;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        if self.branch.is_none() {
+            bytecodec_try_decode!(self.tag, offset, buf, eos);
+            let tag = track!(self.tag.finish_decoding())?;
+            if tag == self.tags.0 {
+                self.branch = Some(Branch::Left);
+            } else if tag == self.tags.1 {
+                self.branch = Some(Branch::Right);
+            } else {
+                track_panic!(ErrorKind::InvalidInput, "Unknown discriminant"; tag);
+            }
+        }
+        if self.branch == Some(Branch::Left) {
+            bytecodec_try_decode!(self.left, offset, buf, eos);
+        } else {
+            bytecodec_try_decode!(self.right, offset, buf, eos);
+        }
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let item = match self.branch {
+            None => track_panic!(ErrorKind::IncompleteDecoding),
+            Some(Branch::Left) => Either::Left(track!(self.left.finish_decoding())?),
+            Some(Branch::Right) => Either::Right(track!(self.right.finish_decoding())?),
+        };
+        self.branch = None;
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        match self.branch {
+            None => self.tag.requiring_bytes(),
+            Some(Branch::Left) => self.left.requiring_bytes(),
+            Some(Branch::Right) => self.right.requiring_bytes(),
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        match self.branch {
+            None => false,
+            Some(Branch::Left) => self.left.is_idle(),
+            Some(Branch::Right) => self.right.is_idle(),
+        }
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.tag.reset())?;
+        track!(self.left.reset())?;
+        track!(self.right.reset())?;
+        self.branch = None;
+        Ok(())
+    }
+}
+impl<A: Default, B: Default> Default for EitherDecoder<A, B> {
+    fn default() -> Self {
+        Self::new(A::default(), B::default())
+    }
+}
+
+/// Encoder for `Either` values prefixed by a discriminant.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::combinator::Either;
+/// use bytecodec::fixnum::{U16beEncoder, U8Encoder};
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::sum::EitherEncoder;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = EitherEncoder::new(U8Encoder::new(), U16beEncoder::new());
+/// encoder.start_encoding(Either::Right(8)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [1, 0, 8]);
+/// ```
+#[derive(Debug)]
+pub struct EitherEncoder<A, B, T: Encode = U8Encoder> {
+    left: A,
+    right: B,
+    tag: T,
+    tags: (T::Item, T::Item),
+}
+impl<A, B> EitherEncoder<A, B> {
+    /// Makes a new `EitherEncoder` instance that uses `0` (left) and `1` (right) as the discriminant byte.
+    pub fn new(left: A, right: B) -> Self {
+        Self::with_tag_encoder(left, right, U8Encoder::new(), (0, 1))
+    }
+}
+impl<A, B, T: Encode> EitherEncoder<A, B, T> {
+    /// Makes a new `EitherEncoder` instance that encodes the discriminant by using `tag_encoder`.
+    ///
+    /// `tags.0` and `tags.1` are the discriminant values of the left and the right variants respectively.
+    pub fn with_tag_encoder(left: A, right: B, tag_encoder: T, tags: (T::Item, T::Item)) -> Self {
+        EitherEncoder {
+            left,
+            right,
+            tag: tag_encoder,
+            tags,
+        }
+    }
+
+    /// Returns the discriminant values of the left and the right variants.
+    pub fn tags(&self) -> &(T::Item, T::Item) {
+        &self.tags
+    }
+
+    /// Returns a reference to the encoder of the left variant.
+    pub fn left_ref(&self) -> &A {
+        &self.left
+    }
+
+    /// Returns a mutable reference to the encoder of the left variant.
+    pub fn left_mut(&mut self) -> &mut A {
+        &mut self.left
+    }
+
+    /// Returns a reference to the encoder of the right variant.
+    pub fn right_ref(&self) -> &B {
+        &self.right
+    }
+
+    /// Returns a mutable reference to the encoder of the right variant.
+    pub fn right_mut(&mut self) -> &mut B {
+        &mut self.right
+    }
+
+    /// Takes ownership of this instance and returns the encoders of the left and the right variants.
+    pub fn into_inner(self) -> (A, B) {
+        (self.left, self.right)
+    }
+}
+impl<A, B, T> Encode for EitherEncoder<A, B, T>
+where
+    A: Encode,
+    B: Encode,
+    T: Encode,
+    T::Item: Clone,
+{
+    type Item = Either<A::Item, B::Item>;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        bytecodec_try_encode!(self.tag, offset, buf, eos);
+        bytecodec_try_encode!(self.left, offset, buf, eos);
+        bytecodec_try_encode!(self.right, offset, buf, eos);
+        Ok(offset)
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track_assert!(self.is_idle(), ErrorKind::EncoderFull);
+        let tag = match item {
+            Either::Left(item) => {
+                track!(self.left.start_encoding(item))?;
+                self.tags.0.clone()
+            }
+            Either::Right(item) => {
+                track!(self.right.start_encoding(item))?;
+                self.tags.1.clone()
+            }
+        };
+        track!(self.tag.start_encoding(tag))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.tag
+            .requiring_bytes()
+            .add_for_encoding(self.left.requiring_bytes())
+            .add_for_encoding(self.right.requiring_bytes())
+    }
+
+    fn is_idle(&self) -> bool {
+        self.tag.is_idle() && self.left.is_idle() && self.right.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.tag.reset())?;
+        track!(self.left.reset())?;
+        track!(self.right.reset())?;
+        Ok(())
+    }
+}
+impl<A, B, T> SizedEncode for EitherEncoder<A, B, T>
+where
+    A: SizedEncode,
+    B: SizedEncode,
+    T: SizedEncode,
+    T::Item: Clone,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.tag.exact_requiring_bytes()
+            + self.left.exact_requiring_bytes()
+            + self.right.exact_requiring_bytes()
+    }
+}
+impl<A: Default, B: Default> Default for EitherEncoder<A, B> {
+    fn default() -> Self {
+        Self::new(A::default(), B::default())
+    }
+}
+
+/// Decoder for `Option` values prefixed by a presence flag.
+///
+/// Unlike `DecodeExt::omit`, the presence of a value is determined by the input byte sequence.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::fixnum::U8Decoder;
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::sum::OptionDecoder;
+///
+/// let mut decoder = OptionDecoder::new(U8Decoder::new());
+/// let mut input = &[0, 1, 7][..];
+/// assert_eq!(decoder.decode_exact(&mut input).unwrap(), None);
+/// assert_eq!(decoder.decode_exact(&mut input).unwrap(), Some(7));
+/// ```
+pub struct OptionDecoder<D, T: Decode = U8Decoder>(EitherDecoder<NullDecoder, D, T>);
+impl<D> OptionDecoder<D> {
+    /// Makes a new `OptionDecoder` instance that uses `0` (`None`) and `1` (`Some`) as the presence flag.
+    pub fn new(inner: D) -> Self {
+        OptionDecoder(EitherDecoder::new(NullDecoder, inner))
+    }
+}
+impl<D, T: Decode> OptionDecoder<D, T> {
+    /// Makes a new `OptionDecoder` instance that decodes the presence flag by using `tag_decoder`.
+    ///
+    /// `tags.0` and `tags.1` are the flag values of `None` and `Some` respectively.
+    pub fn with_tag_decoder(inner: D, tag_decoder: T, tags: (T::Item, T::Item)) -> Self {
+        OptionDecoder(EitherDecoder::with_tag_decoder(
+            NullDecoder,
+            inner,
+            tag_decoder,
+            tags,
+        ))
+    }
+
+    /// Returns the flag values of `None` and `Some`.
+    pub fn tags(&self) -> &(T::Item, T::Item) {
+        self.0.tags()
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        self.0.right_ref()
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        self.0.right_mut()
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.0.into_inner().1
+    }
+}
+impl<D, T> Decode for OptionDecoder<D, T>
+where
+    D: Decode,
+    T: Decode,
+    T::Item: PartialEq + fmt::Debug,
+{
+    type Item = Option<D::Item>;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        match track!(self.0.finish_decoding())? {
+            Either::Left(()) => Ok(None),
+            Either::Right(item) => Ok(Some(item)),
+        }
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<D, T> fmt::Debug for OptionDecoder<D, T>
+where
+    D: fmt::Debug,
+    T: Decode + fmt::Debug,
+    T::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("OptionDecoder").field(&self.0).finish()
+    }
+}
+impl<D: Default> Default for OptionDecoder<D> {
+    fn default() -> Self {
+        Self::new(D::default())
+    }
+}
+
+/// Encoder for `Option` values prefixed by a presence flag.
+///
+/// Unlike `EncodeExt::optional`, the presence flag is written even if the value is `None`.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::fixnum::U8Encoder;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::sum::OptionEncoder;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = OptionEncoder::new(U8Encoder::new());
+/// encoder.start_encoding(None).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// encoder.start_encoding(Some(7)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 1, 7]);
+/// ```
+pub struct OptionEncoder<E, T: Encode = U8Encoder>(EitherEncoder<NullEncoder, E, T>);
+impl<E> OptionEncoder<E> {
+    /// Makes a new `OptionEncoder` instance that uses `0` (`None`) and `1` (`Some`) as the presence flag.
+    pub fn new(inner: E) -> Self {
+        OptionEncoder(EitherEncoder::new(NullEncoder, inner))
+    }
+}
+impl<E, T: Encode> OptionEncoder<E, T> {
+    /// Makes a new `OptionEncoder` instance that encodes the presence flag by using `tag_encoder`.
+    ///
+    /// `tags.0` and `tags.1` are the flag values of `None` and `Some` respectively.
+    pub fn with_tag_encoder(inner: E, tag_encoder: T, tags: (T::Item, T::Item)) -> Self {
+        OptionEncoder(EitherEncoder::with_tag_encoder(
+            NullEncoder,
+            inner,
+            tag_encoder,
+            tags,
+        ))
+    }
+
+    /// Returns the flag values of `None` and `Some`.
+    pub fn tags(&self) -> &(T::Item, T::Item) {
+        self.0.tags()
+    }
+
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        self.0.right_ref()
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        self.0.right_mut()
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder.
+    pub fn into_inner(self) -> E {
+        self.0.into_inner().1
+    }
+}
+impl<E, T> Encode for OptionEncoder<E, T>
+where
+    E: Encode,
+    T: Encode,
+    T::Item: Clone,
+{
+    type Item = Option<E::Item>;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let item = match item {
+            None => Either::Left(()),
+            Some(item) => Either::Right(item),
+        };
+        track!(self.0.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<E, T> SizedEncode for OptionEncoder<E, T>
+where
+    E: SizedEncode,
+    T: SizedEncode,
+    T::Item: Clone,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+impl<E, T> fmt::Debug for OptionEncoder<E, T>
+where
+    E: fmt::Debug,
+    T: Encode + fmt::Debug,
+    T::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("OptionEncoder").field(&self.0).finish()
+    }
+}
+impl<E: Default> Default for OptionEncoder<E> {
+    fn default() -> Self {
+        Self::new(E::default())
+    }
+}
+
+/// Decoder for `Result` values prefixed by a discriminant.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::bytes::Utf8Decoder;
+/// use bytecodec::fixnum::U8Decoder;
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::sum::ResultDecoder;
+///
+/// let mut decoder = ResultDecoder::new(U8Decoder::new(), Utf8Decoder::new());
+/// assert_eq!(decoder.decode_exact(&[0, 7][..]).unwrap(), Ok(7));
+/// assert_eq!(decoder.decode_exact(&b"\x01oops"[..]).unwrap(), Err("oops".to_owned()));
+/// ```
+pub struct ResultDecoder<O, E, T: Decode = U8Decoder>(EitherDecoder<O, E, T>);
+impl<O, E> ResultDecoder<O, E> {
+    /// Makes a new `ResultDecoder` instance that uses `0` (`Ok`) and `1` (`Err`) as the discriminant byte.
+    pub fn new(ok: O, err: E) -> Self {
+        ResultDecoder(EitherDecoder::new(ok, err))
+    }
+}
+impl<O, E, T: Decode> ResultDecoder<O, E, T> {
+    /// Makes a new `ResultDecoder` instance that decodes the discriminant by using `tag_decoder`.
+    ///
+    /// `tags.0` and `tags.1` are the discriminant values of `Ok` and `Err` respectively.
+    pub fn with_tag_decoder(ok: O, err: E, tag_decoder: T, tags: (T::Item, T::Item)) -> Self {
+        ResultDecoder(EitherDecoder::with_tag_decoder(ok, err, tag_decoder, tags))
+    }
+
+    /// Returns the discriminant values of `Ok` and `Err`.
+    pub fn tags(&self) -> &(T::Item, T::Item) {
+        self.0.tags()
+    }
+
+    /// Returns a reference to the decoder of `Ok` values.
+    pub fn ok_ref(&self) -> &O {
+        self.0.left_ref()
+    }
+
+    /// Returns a mutable reference to the decoder of `Ok` values.
+    pub fn ok_mut(&mut self) -> &mut O {
+        self.0.left_mut()
+    }
+
+    /// Returns a reference to the decoder of `Err` values.
+    pub fn err_ref(&self) -> &E {
+        self.0.right_ref()
+    }
+
+    /// Returns a mutable reference to the decoder of `Err` values.
+    pub fn err_mut(&mut self) -> &mut E {
+        self.0.right_mut()
+    }
+
+    /// Takes ownership of this instance and returns the decoders of `Ok` and `Err` values.
+    pub fn into_inner(self) -> (O, E) {
+        self.0.into_inner()
+    }
+}
+impl<O, E, T> Decode for ResultDecoder<O, E, T>
+where
+    O: Decode,
+    E: Decode,
+    T: Decode,
+    T::Item: PartialEq + fmt::Debug,
+{
+    type Item = std::result::Result<O::Item, E::Item>;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        match track!(self.0.finish_decoding())? {
+            Either::Left(item) => Ok(Ok(item)),
+            Either::Right(item) => Ok(Err(item)),
+        }
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<O, E, T> fmt::Debug for ResultDecoder<O, E, T>
+where
+    O: fmt::Debug,
+    E: fmt::Debug,
+    T: Decode + fmt::Debug,
+    T::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ResultDecoder").field(&self.0).finish()
+    }
+}
+impl<O: Default, E: Default> Default for ResultDecoder<O, E> {
+    fn default() -> Self {
+        Self::new(O::default(), E::default())
+    }
+}
+
+/// Encoder for `Result` values prefixed by a discriminant.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::bytes::Utf8Encoder;
+/// use bytecodec::fixnum::U8Encoder;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::sum::ResultEncoder;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = ResultEncoder::new(U8Encoder::new(), Utf8Encoder::new());
+/// encoder.start_encoding(Err("oops")).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, b"\x01oops");
+/// ```
+pub struct ResultEncoder<O, E, T: Encode = U8Encoder>(EitherEncoder<O, E, T>);
+impl<O, E> ResultEncoder<O, E> {
+    /// Makes a new `ResultEncoder` instance that uses `0` (`Ok`) and `1` (`Err`) as the discriminant byte.
+    pub fn new(ok: O, err: E) -> Self {
+        ResultEncoder(EitherEncoder::new(ok, err))
+    }
+}
+impl<O, E, T: Encode> ResultEncoder<O, E, T> {
+    /// Makes a new `ResultEncoder` instance that encodes the discriminant by using `tag_encoder`.
+    ///
+    /// `tags.0` and `tags.1` are the discriminant values of `Ok` and `Err` respectively.
+    pub fn with_tag_encoder(ok: O, err: E, tag_encoder: T, tags: (T::Item, T::Item)) -> Self {
+        ResultEncoder(EitherEncoder::with_tag_encoder(ok, err, tag_encoder, tags))
+    }
+
+    /// Returns the discriminant values of `Ok` and `Err`.
+    pub fn tags(&self) -> &(T::Item, T::Item) {
+        self.0.tags()
+    }
+
+    /// Returns a reference to the encoder of `Ok` values.
+    pub fn ok_ref(&self) -> &O {
+        self.0.left_ref()
+    }
+
+    /// Returns a mutable reference to the encoder of `Ok` values.
+    pub fn ok_mut(&mut self) -> &mut O {
+        self.0.left_mut()
+    }
+
+    /// Returns a reference to the encoder of `Err` values.
+    pub fn err_ref(&self) -> &E {
+        self.0.right_ref()
+    }
+
+    /// Returns a mutable reference to the encoder of `Err` values.
+    pub fn err_mut(&mut self) -> &mut E {
+        self.0.right_mut()
+    }
+
+    /// Takes ownership of this instance and returns the encoders of `Ok` and `Err` values.
+    pub fn into_inner(self) -> (O, E) {
+        self.0.into_inner()
+    }
+}
+impl<O, E, T> Encode for ResultEncoder<O, E, T>
+where
+    O: Encode,
+    E: Encode,
+    T: Encode,
+    T::Item: Clone,
+{
+    type Item = std::result::Result<O::Item, E::Item>;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let item = match item {
+            Ok(item) => Either::Left(item),
+            Err(item) => Either::Right(item),
+        };
+        track!(self.0.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<O, E, T> SizedEncode for ResultEncoder<O, E, T>
+where
+    O: SizedEncode,
+    E: SizedEncode,
+    T: SizedEncode,
+    T::Item: Clone,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+impl<O, E, T> fmt::Debug for ResultEncoder<O, E, T>
+where
+    O: fmt::Debug,
+    E: fmt::Debug,
+    T: Encode + fmt::Debug,
+    T::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ResultEncoder").field(&self.0).finish()
+    }
+}
+impl<O: Default, E: Default> Default for ResultEncoder<O, E> {
+    fn default() -> Self {
+        Self::new(O::default(), E::default())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Branch {
+    Left,
+    Right,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixnum::{U16beDecoder, U16beEncoder};
+    use crate::io::{IoDecodeExt, IoEncodeExt};
+
+    #[test]
+    fn option_codec_with_custom_tags_works() {
+        let mut output = Vec::new();
+        let mut encoder =
+            OptionEncoder::with_tag_encoder(U8Encoder::new(), U16beEncoder::new(), (0xFFFF, 1));
+        track_try_unwrap!(encoder.start_encoding(Some(7)));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        track_try_unwrap!(encoder.start_encoding(None));
+        assert_eq!(encoder.exact_requiring_bytes(), 2);
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [0, 1, 7, 0xFF, 0xFF]);
+
+        let mut decoder =
+            OptionDecoder::with_tag_decoder(U8Decoder::new(), U16beDecoder::new(), (0xFFFF, 1));
+        let mut input = &output[..];
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), Some(7));
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), None);
+    }
+
+    #[test]
+    fn unknown_discriminant_is_rejected() {
+        let mut decoder = ResultDecoder::new(U8Decoder::new(), U8Decoder::new());
+        let error = decoder.decode_exact(&[2, 0][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+}
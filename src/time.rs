@@ -0,0 +1,851 @@
+//! Encoders and decoders for timestamps and durations.
+use crate::fixnum::{
+    I64beDecoder, I64beEncoder, U32beDecoder, U32beEncoder, U64beDecoder, U64beEncoder,
+};
+use crate::tuple::{TupleDecoder, TupleEncoder};
+use crate::{ByteCount, Decode, Encode, Eos, ErrorKind, Result, SizedEncode};
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The number of seconds from the NTP epoch (1900-01-01) to the Unix epoch (1970-01-01).
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Unit of integer representations of time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeUnit {
+    /// Seconds.
+    #[default]
+    Seconds,
+
+    /// Milliseconds.
+    Milliseconds,
+
+    /// Microseconds.
+    Microseconds,
+
+    /// Nanoseconds.
+    Nanoseconds,
+}
+impl TimeUnit {
+    fn nanos(self) -> u64 {
+        match self {
+            TimeUnit::Seconds => NANOS_PER_SEC,
+            TimeUnit::Milliseconds => 1_000_000,
+            TimeUnit::Microseconds => 1_000,
+            TimeUnit::Nanoseconds => 1,
+        }
+    }
+
+    fn duration_of(self, n: u64) -> Duration {
+        let per_sec = NANOS_PER_SEC / self.nanos();
+        Duration::new(n / per_sec, ((n % per_sec) * self.nanos()) as u32)
+    }
+
+    fn count_in(self, d: Duration) -> Result<u64> {
+        let n = d.as_nanos() / u128::from(self.nanos());
+        let n = track_assert_some!(u64::try_from(n).ok(), ErrorKind::InvalidInput,
+                                   "Out of range"; d, self);
+        Ok(n)
+    }
+}
+
+/// Decoder which decodes `Duration` values represented as integers in the given unit.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::fixnum::U32beDecoder;
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::time::{DurationDecoder, TimeUnit};
+/// use std::time::Duration;
+///
+/// let mut decoder = DurationDecoder::new(U32beDecoder::new(), TimeUnit::Milliseconds);
+/// let item = decoder.decode_exact([0, 0, 0x05, 0xDC].as_ref()).unwrap();
+/// assert_eq!(item, Duration::from_millis(1500));
+/// ```
+#[derive(Debug, Default)]
+pub struct DurationDecoder<D = U64beDecoder> {
+    inner: D,
+    unit: TimeUnit,
+}
+impl<D> DurationDecoder<D> {
+    /// Makes a new `DurationDecoder` instance that decodes integers by using `inner`.
+    pub fn new(inner: D, unit: TimeUnit) -> Self {
+        DurationDecoder { inner, unit }
+    }
+
+    /// Returns the unit of decoded integers.
+    pub fn unit(&self) -> TimeUnit {
+        self.unit
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+impl<D> Decode for DurationDecoder<D>
+where
+    D: Decode,
+    D::Item: Into<u64>,
+{
+    type Item = Duration;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let n = track!(self.inner.finish_decoding())?;
+        Ok(self.unit.duration_of(n.into()))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+
+/// Encoder which encodes `Duration` values as integers in the given unit.
+///
+/// Fractions smaller than the unit are truncated.
+/// If a value does not fit in the item type of the inner encoder,
+/// an `ErrorKind::InvalidInput` error will be returned.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::fixnum::U32beEncoder;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::time::{DurationEncoder, TimeUnit};
+/// use std::time::Duration;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = DurationEncoder::new(U32beEncoder::new(), TimeUnit::Milliseconds);
+/// encoder.start_encoding(Duration::from_micros(1_500_999)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 0, 0x05, 0xDC]);
+///
+/// assert!(encoder.start_encoding(Duration::from_secs(5_000_000)).is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct DurationEncoder<E = U64beEncoder> {
+    inner: E,
+    unit: TimeUnit,
+}
+impl<E> DurationEncoder<E> {
+    /// Makes a new `DurationEncoder` instance that encodes integers by using `inner`.
+    pub fn new(inner: E, unit: TimeUnit) -> Self {
+        DurationEncoder { inner, unit }
+    }
+
+    /// Returns the unit of encoded integers.
+    pub fn unit(&self) -> TimeUnit {
+        self.unit
+    }
+
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+impl<E> Encode for DurationEncoder<E>
+where
+    E: Encode,
+    E::Item: TryFrom<u64>,
+{
+    type Item = Duration;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let n = track!(self.unit.count_in(item))?;
+        let n = track_assert_some!(E::Item::try_from(n).ok(), ErrorKind::InvalidInput,
+                                   "Out of range"; item, self.unit);
+        track!(self.inner.start_encoding(n))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+impl<E> SizedEncode for DurationEncoder<E>
+where
+    E: SizedEncode,
+    E::Item: TryFrom<u64>,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+
+/// Decoder which decodes `SystemTime` values represented as elapsed time since the Unix epoch.
+///
+/// The inner decoder must yield unsigned integers.
+/// Use `SignedUnixTimeDecoder` for signed representations such as `time_t`.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::fixnum::U32beDecoder;
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::time::{TimeUnit, UnixTimeDecoder};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let mut decoder = UnixTimeDecoder::new(U32beDecoder::new(), TimeUnit::Seconds);
+/// let item = decoder.decode_exact([0x5A, 0x00, 0x00, 0x00].as_ref()).unwrap();
+/// assert_eq!(item, UNIX_EPOCH + Duration::from_secs(0x5A00_0000));
+/// ```
+#[derive(Debug, Default)]
+pub struct UnixTimeDecoder<D = U64beDecoder>(DurationDecoder<D>);
+impl<D> UnixTimeDecoder<D> {
+    /// Makes a new `UnixTimeDecoder` instance that decodes integers by using `inner`.
+    pub fn new(inner: D, unit: TimeUnit) -> Self {
+        UnixTimeDecoder(DurationDecoder::new(inner, unit))
+    }
+
+    /// Returns the unit of decoded integers.
+    pub fn unit(&self) -> TimeUnit {
+        self.0.unit()
+    }
+
+    fn decode_item(d: Duration) -> Result<SystemTime> {
+        let t = track_assert_some!(UNIX_EPOCH.checked_add(d), ErrorKind::InvalidInput,
+                                   "Out of range"; d);
+        Ok(t)
+    }
+}
+impl<D> Decode for UnixTimeDecoder<D>
+where
+    D: Decode,
+    D::Item: Into<u64>,
+{
+    type Item = SystemTime;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let d = track!(self.0.finish_decoding())?;
+        track!(Self::decode_item(d))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+
+/// Encoder which encodes `SystemTime` values as elapsed time since the Unix epoch.
+///
+/// Times before the Unix epoch are rejected with an `ErrorKind::InvalidInput` error.
+/// Use `SignedUnixTimeEncoder` to encode them as negative integers.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::fixnum::U64beEncoder;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::time::{TimeUnit, UnixTimeEncoder};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let mut output = Vec::new();
+/// let mut encoder = UnixTimeEncoder::new(U64beEncoder::new(), TimeUnit::Nanoseconds);
+/// encoder.start_encoding(UNIX_EPOCH + Duration::from_nanos(0x0102)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 0, 0, 0, 0, 0, 0x01, 0x02]);
+/// ```
+#[derive(Debug, Default)]
+pub struct UnixTimeEncoder<E = U64beEncoder>(DurationEncoder<E>);
+impl<E> UnixTimeEncoder<E> {
+    /// Makes a new `UnixTimeEncoder` instance that encodes integers by using `inner`.
+    pub fn new(inner: E, unit: TimeUnit) -> Self {
+        UnixTimeEncoder(DurationEncoder::new(inner, unit))
+    }
+
+    /// Returns the unit of encoded integers.
+    pub fn unit(&self) -> TimeUnit {
+        self.0.unit()
+    }
+
+    fn encode_item(t: SystemTime) -> Result<Duration> {
+        let d = track_assert_some!(t.duration_since(UNIX_EPOCH).ok(), ErrorKind::InvalidInput,
+                                   "Before the Unix epoch"; t);
+        Ok(d)
+    }
+}
+impl<E> Encode for UnixTimeEncoder<E>
+where
+    E: Encode,
+    E::Item: TryFrom<u64>,
+{
+    type Item = SystemTime;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let d = track!(Self::encode_item(item))?;
+        track!(self.0.start_encoding(d))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<E> SizedEncode for UnixTimeEncoder<E>
+where
+    E: SizedEncode,
+    E::Item: TryFrom<u64>,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+
+/// Decoder which decodes `SystemTime` values represented as signed elapsed time since the Unix epoch.
+///
+/// Negative integers (e.g., `time_t` values before 1970) are decoded as times before the epoch.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::fixnum::I32beDecoder;
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::time::{SignedUnixTimeDecoder, TimeUnit};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let mut decoder = SignedUnixTimeDecoder::new(I32beDecoder::new(), TimeUnit::Seconds);
+/// let item = decoder.decode_exact([0xFF, 0xFF, 0xFF, 0xFE].as_ref()).unwrap();
+/// assert_eq!(item, UNIX_EPOCH - Duration::from_secs(2));
+/// ```
+#[derive(Debug, Default)]
+pub struct SignedUnixTimeDecoder<D = I64beDecoder> {
+    inner: D,
+    unit: TimeUnit,
+}
+impl<D> SignedUnixTimeDecoder<D> {
+    /// Makes a new `SignedUnixTimeDecoder` instance that decodes integers by using `inner`.
+    pub fn new(inner: D, unit: TimeUnit) -> Self {
+        SignedUnixTimeDecoder { inner, unit }
+    }
+
+    /// Returns the unit of decoded integers.
+    pub fn unit(&self) -> TimeUnit {
+        self.unit
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+impl<D> Decode for SignedUnixTimeDecoder<D>
+where
+    D: Decode,
+    D::Item: Into<i64>,
+{
+    type Item = SystemTime;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let n = track!(self.inner.finish_decoding())?.into();
+        let d = self.unit.duration_of(n.unsigned_abs());
+        let t = if n < 0 {
+            UNIX_EPOCH.checked_sub(d)
+        } else {
+            UNIX_EPOCH.checked_add(d)
+        };
+        let t = track_assert_some!(t, ErrorKind::InvalidInput, "Out of range"; n, self.unit);
+        Ok(t)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+
+/// Encoder which encodes `SystemTime` values as signed elapsed time since the Unix epoch.
+///
+/// Times before the Unix epoch are encoded as negative integers.
+/// Fractions smaller than the unit are truncated toward the epoch.
+/// If a value does not fit in the item type of the inner encoder,
+/// an `ErrorKind::InvalidInput` error will be returned.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::fixnum::I32beEncoder;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::time::{SignedUnixTimeEncoder, TimeUnit};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let mut output = Vec::new();
+/// let mut encoder = SignedUnixTimeEncoder::new(I32beEncoder::new(), TimeUnit::Seconds);
+/// encoder.start_encoding(UNIX_EPOCH - Duration::from_secs(2)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0xFF, 0xFF, 0xFF, 0xFE]);
+/// ```
+#[derive(Debug, Default)]
+pub struct SignedUnixTimeEncoder<E = I64beEncoder> {
+    inner: E,
+    unit: TimeUnit,
+}
+impl<E> SignedUnixTimeEncoder<E> {
+    /// Makes a new `SignedUnixTimeEncoder` instance that encodes integers by using `inner`.
+    pub fn new(inner: E, unit: TimeUnit) -> Self {
+        SignedUnixTimeEncoder { inner, unit }
+    }
+
+    /// Returns the unit of encoded integers.
+    pub fn unit(&self) -> TimeUnit {
+        self.unit
+    }
+
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+impl<E> Encode for SignedUnixTimeEncoder<E>
+where
+    E: Encode,
+    E::Item: TryFrom<i64>,
+{
+    type Item = SystemTime;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let n = match item.duration_since(UNIX_EPOCH) {
+            Ok(d) => i64::try_from(track!(self.unit.count_in(d))?).ok(),
+            Err(e) => {
+                let n = track!(self.unit.count_in(e.duration()))?;
+                0i64.checked_sub_unsigned(n)
+            }
+        };
+        let n = n.and_then(|n| E::Item::try_from(n).ok());
+        let n = track_assert_some!(n, ErrorKind::InvalidInput, "Out of range"; item, self.unit);
+        track!(self.inner.start_encoding(n))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+impl<E> SizedEncode for SignedUnixTimeEncoder<E>
+where
+    E: SizedEncode,
+    E::Item: TryFrom<i64>,
+{
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+
+fn ntp_epoch() -> Result<SystemTime> {
+    let epoch = UNIX_EPOCH.checked_sub(Duration::from_secs(NTP_UNIX_OFFSET_SECS));
+    let epoch = track_assert_some!(epoch, ErrorKind::Other, "NTP epoch is unrepresentable");
+    Ok(epoch)
+}
+
+/// Decoder which decodes NTP timestamps (64-bit long format).
+///
+/// The first 32 bits are seconds since 1900-01-01 and the last 32 bits are the fraction of a second.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::time::NtpTimestampDecoder;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let mut decoder = NtpTimestampDecoder::new();
+/// let input = [0x83, 0xAA, 0x7E, 0x80, 0x80, 0x00, 0x00, 0x00];
+/// let item = decoder.decode_exact(input.as_ref()).unwrap();
+/// assert_eq!(item, UNIX_EPOCH + Duration::from_millis(500));
+/// ```
+#[derive(Debug, Default)]
+pub struct NtpTimestampDecoder(U64beDecoder);
+impl NtpTimestampDecoder {
+    /// Makes a new `NtpTimestampDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode_item(n: u64) -> Result<SystemTime> {
+        let secs = n >> 32;
+        let nanos = ((n & 0xFFFF_FFFF) * NANOS_PER_SEC) >> 32;
+        let d = Duration::new(secs, nanos as u32);
+        let t = track_assert_some!(track!(ntp_epoch())?.checked_add(d), ErrorKind::InvalidInput,
+                                   "Out of range"; d);
+        Ok(t)
+    }
+}
+impl_newtype_decode!(NtpTimestampDecoder, SystemTime, try);
+
+/// Encoder which encodes NTP timestamps (64-bit long format).
+///
+/// Times before 1900-01-01 or after the end of NTP era 0 (2036-02-07)
+/// are rejected with an `ErrorKind::InvalidInput` error.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::time::NtpTimestampEncoder;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let mut output = Vec::new();
+/// let item = UNIX_EPOCH + Duration::from_millis(500);
+/// let mut encoder = NtpTimestampEncoder::with_item(item).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0x83, 0xAA, 0x7E, 0x80, 0x80, 0x00, 0x00, 0x00]);
+/// ```
+#[derive(Debug, Default)]
+pub struct NtpTimestampEncoder(U64beEncoder);
+impl NtpTimestampEncoder {
+    /// Makes a new `NtpTimestampEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn encode_item(t: SystemTime) -> Result<u64> {
+        let d = t.duration_since(track!(ntp_epoch())?).ok();
+        let d = track_assert_some!(d, ErrorKind::InvalidInput, "Before the NTP epoch"; t);
+        track_assert!(d.as_secs() <= 0xFFFF_FFFF, ErrorKind::InvalidInput, "Out of range"; t);
+        let fraction = (u64::from(d.subsec_nanos()) << 32) / NANOS_PER_SEC;
+        Ok((d.as_secs() << 32) | fraction)
+    }
+}
+impl_newtype_encode!(NtpTimestampEncoder, SystemTime, try);
+
+/// Decoder which decodes NTP short format values as `Duration`.
+///
+/// The first 16 bits are seconds and the last 16 bits are the fraction of a second.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::time::NtpShortDecoder;
+/// use std::time::Duration;
+///
+/// let mut decoder = NtpShortDecoder::new();
+/// let item = decoder.decode_exact([0x00, 0x01, 0x40, 0x00].as_ref()).unwrap();
+/// assert_eq!(item, Duration::from_millis(1250));
+/// ```
+#[derive(Debug, Default)]
+pub struct NtpShortDecoder(U32beDecoder);
+impl NtpShortDecoder {
+    /// Makes a new `NtpShortDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn decode_item(n: u32) -> Result<Duration> {
+        let secs = u64::from(n >> 16);
+        let nanos = (u64::from(n & 0xFFFF) * NANOS_PER_SEC) >> 16;
+        Ok(Duration::new(secs, nanos as u32))
+    }
+}
+impl_newtype_decode!(NtpShortDecoder, Duration, try);
+
+/// Encoder which encodes `Duration` values in NTP short format.
+///
+/// Durations longer than or equal to 65536 seconds are rejected with an `ErrorKind::InvalidInput` error.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::time::NtpShortEncoder;
+/// use std::time::Duration;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = NtpShortEncoder::with_item(Duration::from_millis(1250)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0x00, 0x01, 0x40, 0x00]);
+/// ```
+#[derive(Debug, Default)]
+pub struct NtpShortEncoder(U32beEncoder);
+impl NtpShortEncoder {
+    /// Makes a new `NtpShortEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn encode_item(d: Duration) -> Result<u32> {
+        track_assert!(d.as_secs() <= 0xFFFF, ErrorKind::InvalidInput, "Out of range"; d);
+        let fraction = (u64::from(d.subsec_nanos()) << 16) / NANOS_PER_SEC;
+        Ok(((d.as_secs() << 16) | fraction) as u32)
+    }
+}
+impl_newtype_encode!(NtpShortEncoder, Duration, try);
+
+/// Decoder which decodes `Duration` values represented as a pair of
+/// 64-bit seconds and 32-bit nanoseconds.
+///
+/// If the nanoseconds part is not less than `1_000_000_000`,
+/// an `ErrorKind::InvalidInput` error will be returned.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::time::SecsNanosDecoder;
+/// use std::time::Duration;
+///
+/// let mut decoder = SecsNanosDecoder::new();
+/// let input = [0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 5];
+/// let item = decoder.decode_exact(input.as_ref()).unwrap();
+/// assert_eq!(item, Duration::new(3, 5));
+/// ```
+#[derive(Debug, Default)]
+pub struct SecsNanosDecoder(TupleDecoder<(U64beDecoder, U32beDecoder)>);
+impl SecsNanosDecoder {
+    /// Makes a new `SecsNanosDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decode_item((secs, nanos): (u64, u32)) -> Result<Duration> {
+        track_assert!(u64::from(nanos) < NANOS_PER_SEC, ErrorKind::InvalidInput,
+                      "Out of range"; secs, nanos);
+        Ok(Duration::new(secs, nanos))
+    }
+}
+impl_newtype_decode!(SecsNanosDecoder, Duration, try);
+
+/// Encoder which encodes `Duration` values as a pair of 64-bit seconds and 32-bit nanoseconds.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::time::SecsNanosEncoder;
+/// use std::time::Duration;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = SecsNanosEncoder::with_item(Duration::new(3, 5)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 5]);
+/// ```
+#[derive(Debug, Default)]
+pub struct SecsNanosEncoder(TupleEncoder<(U64beEncoder, U32beEncoder)>);
+impl SecsNanosEncoder {
+    /// Makes a new `SecsNanosEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn encode_item(d: Duration) -> Result<(u64, u32)> {
+        Ok((d.as_secs(), d.subsec_nanos()))
+    }
+}
+impl_newtype_encode!(SecsNanosEncoder, Duration, try);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixnum::{I32beDecoder, I32beEncoder, U32beDecoder, U32beEncoder};
+    use crate::io::{IoDecodeExt, IoEncodeExt};
+
+    #[test]
+    fn unix_time_works() {
+        let t = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        for &unit in &[
+            TimeUnit::Seconds,
+            TimeUnit::Milliseconds,
+            TimeUnit::Microseconds,
+            TimeUnit::Nanoseconds,
+        ] {
+            let mut output = Vec::new();
+            let mut encoder = UnixTimeEncoder::new(U64beEncoder::new(), unit);
+            track_try_unwrap!(encoder.start_encoding(t));
+            track_try_unwrap!(encoder.encode_all(&mut output));
+
+            let mut decoder = UnixTimeDecoder::new(U64beDecoder::new(), unit);
+            let decoded = track_try_unwrap!(decoder.decode_exact(&output[..]));
+            let error = t.duration_since(decoded).unwrap();
+            assert!(error.as_nanos() < u128::from(unit.nanos()));
+        }
+
+        let mut encoder = UnixTimeEncoder::new(U32beEncoder::new(), TimeUnit::Milliseconds);
+        let error = encoder.start_encoding(t).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(1);
+        let error = encoder.start_encoding(before_epoch).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut decoder = UnixTimeDecoder::new(U32beDecoder::new(), TimeUnit::Seconds);
+        let item = track_try_unwrap!(decoder.decode_exact(&[0xFF; 4][..]));
+        assert_eq!(item, UNIX_EPOCH + Duration::from_secs(0xFFFF_FFFF));
+    }
+
+    #[test]
+    fn signed_unix_time_works() {
+        for &secs in &[-1_000_000_000i64, -1, 0, 1_700_000_000] {
+            let t = if secs < 0 {
+                UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+            } else {
+                UNIX_EPOCH + Duration::from_secs(secs as u64)
+            };
+
+            let mut output = Vec::new();
+            let mut encoder = SignedUnixTimeEncoder::new(I64beEncoder::new(), TimeUnit::Seconds);
+            track_try_unwrap!(encoder.start_encoding(t));
+            track_try_unwrap!(encoder.encode_all(&mut output));
+            assert_eq!(output, secs.to_be_bytes());
+
+            let mut decoder = SignedUnixTimeDecoder::new(I32beDecoder::new(), TimeUnit::Seconds);
+            let item = track_try_unwrap!(decoder.decode_exact(&(secs as i32).to_be_bytes()[..]));
+            assert_eq!(item, t);
+        }
+
+        let before_epoch = UNIX_EPOCH - Duration::from_millis(1500);
+        let mut encoder = SignedUnixTimeEncoder::new(I32beEncoder::new(), TimeUnit::Seconds);
+        track_try_unwrap!(encoder.start_encoding(before_epoch));
+        let mut output = Vec::new();
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, (-1i32).to_be_bytes());
+
+        let too_early = UNIX_EPOCH - Duration::from_secs(1 << 31) - Duration::from_secs(1);
+        let error = encoder.start_encoding(too_early).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn ntp_time_works() {
+        let t = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut output = Vec::new();
+        let mut encoder = NtpTimestampEncoder::new();
+        track_try_unwrap!(encoder.start_encoding(t));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+
+        let mut decoder = NtpTimestampDecoder::new();
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&output[..])), t);
+
+        let after_era = UNIX_EPOCH + Duration::from_secs(0x1_0000_0000 - NTP_UNIX_OFFSET_SECS);
+        let error = encoder.start_encoding(after_era).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut encoder = NtpShortEncoder::new();
+        let error = encoder
+            .start_encoding(Duration::from_secs(0x1_0000))
+            .err()
+            .unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut decoder = SecsNanosDecoder::new();
+        let input = [0, 0, 0, 0, 0, 0, 0, 0, 0x3B, 0x9A, 0xCA, 0x00];
+        let error = decoder.decode_exact(&input[..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+}
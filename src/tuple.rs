@@ -1,8 +1,10 @@
 //! Encoders and decoders for tuples.
 use crate::{ByteCount, Decode, Encode, Eos, Result, SizedEncode};
+use std::fmt;
 
 /// Decoder for tuples.
-#[derive(Debug, Default)]
+///
+/// Tuples of up to 16 decoders are supported.
 pub struct TupleDecoder<D> {
     inner: D,
 }
@@ -28,8 +30,41 @@ impl<D> TupleDecoder<D> {
     }
 }
 
+// `Debug` and `Default` are implemented manually
+// because the standard library only implements them for tuples of up to 12 elements.
+struct DebugFields<'a>(&'a [&'a dyn fmt::Debug]);
+impl fmt::Debug for DebugFields<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut t = f.debug_tuple("");
+        for field in self.0 {
+            t.field(field);
+        }
+        t.finish()
+    }
+}
+
+macro_rules! impl_debug_and_default {
+    ($name:ident, [$($t:ident),*], [$($i:tt),*]) => {
+        impl<$($t: fmt::Debug),*> fmt::Debug for $name<($($t),*,)> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("inner", &DebugFields(&[$(&self.inner.$i),*]))
+                    .finish()
+            }
+        }
+        impl<$($t: Default),*> Default for $name<($($t),*,)> {
+            fn default() -> Self {
+                $name {
+                    inner: ($($t::default()),*,),
+                }
+            }
+        }
+    };
+}
+
 macro_rules! impl_decode {
     ([$($t:ident),*],[$($i:tt),*]) => {
+        impl_debug_and_default!(TupleDecoder, [$($t),*], [$($i),*]);
         impl<$($t),*> Decode for TupleDecoder<($($t),*,)>
         where
             $($t: Decode),*
@@ -38,13 +73,15 @@ macro_rules! impl_decode {
 
             fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
                 let mut offset = 0;
-                $(bytecodec_try_decode!(self.inner.$i, offset, buf, eos, "i={}", $i);)*
+                $(try_decode_with_path!(self.inner.$i, offset, buf, eos, concat!("tuple.", $i), "i={}", $i);)*
                 Ok(offset)
             }
 
             fn finish_decoding(&mut self) -> Result<Self::Item> {
                 Ok((
-                    $(track!(self.inner.$i.finish_decoding(), "i={}", $i)?),*,
+                    $(track!(self.inner.$i
+                        .finish_decoding()
+                        .map_err(|e| e.with_path(concat!("tuple.", $i))), "i={}", $i)?),*,
                 ))
             }
 
@@ -55,6 +92,11 @@ macro_rules! impl_decode {
             fn is_idle(&self) -> bool {
                 $(self.inner.$i.is_idle())&&*
             }
+
+            fn reset(&mut self) -> Result<()> {
+                $(track!(self.inner.$i.reset(), "i={}", $i)?;)*
+                Ok(())
+            }
         }
     }
 }
@@ -65,9 +107,42 @@ impl_decode!([D0, D1, D2, D3, D4], [0, 1, 2, 3, 4]);
 impl_decode!([D0, D1, D2, D3, D4, D5], [0, 1, 2, 3, 4, 5]);
 impl_decode!([D0, D1, D2, D3, D4, D5, D6], [0, 1, 2, 3, 4, 5, 6]);
 impl_decode!([D0, D1, D2, D3, D4, D5, D6, D7], [0, 1, 2, 3, 4, 5, 6, 7]);
+impl_decode!(
+    [D0, D1, D2, D3, D4, D5, D6, D7, D8],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8]
+);
+impl_decode!(
+    [D0, D1, D2, D3, D4, D5, D6, D7, D8, D9],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+);
+impl_decode!(
+    [D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+);
+impl_decode!(
+    [D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+impl_decode!(
+    [D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+);
+impl_decode!(
+    [D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12, D13],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+);
+impl_decode!(
+    [D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12, D13, D14],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+);
+impl_decode!(
+    [D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12, D13, D14, D15],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+);
 
 /// Encoder for tuples.
-#[derive(Debug, Default)]
+///
+/// Tuples of up to 16 encoders are supported.
 pub struct TupleEncoder<E> {
     inner: E,
 }
@@ -95,6 +170,7 @@ impl<E> TupleEncoder<E> {
 
 macro_rules! impl_encode {
     ([$($t:ident),*],[$($i:tt),*]) => {
+        impl_debug_and_default!(TupleEncoder, [$($t),*], [$($i),*]);
         impl<$($t),*> Encode for TupleEncoder<($($t),*,)>
         where
             $($t: Encode),*
@@ -119,6 +195,11 @@ macro_rules! impl_encode {
             fn is_idle(&self) -> bool {
                 $(self.inner.$i.is_idle())&&*
             }
+
+            fn reset(&mut self) -> Result<()> {
+                $(track!(self.inner.$i.reset(), "i={}", $i)?;)*
+                Ok(())
+            }
         }
         impl<$($t),*> SizedEncode for TupleEncoder<($($t),*,)>
         where
@@ -137,6 +218,38 @@ impl_encode!([E0, E1, E2, E3, E4], [0, 1, 2, 3, 4]);
 impl_encode!([E0, E1, E2, E3, E4, E5], [0, 1, 2, 3, 4, 5]);
 impl_encode!([E0, E1, E2, E3, E4, E5, E6], [0, 1, 2, 3, 4, 5, 6]);
 impl_encode!([E0, E1, E2, E3, E4, E5, E6, E7], [0, 1, 2, 3, 4, 5, 6, 7]);
+impl_encode!(
+    [E0, E1, E2, E3, E4, E5, E6, E7, E8],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8]
+);
+impl_encode!(
+    [E0, E1, E2, E3, E4, E5, E6, E7, E8, E9],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+);
+impl_encode!(
+    [E0, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+);
+impl_encode!(
+    [E0, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+);
+impl_encode!(
+    [E0, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+);
+impl_encode!(
+    [E0, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+);
+impl_encode!(
+    [E0, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13, E14],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+);
+impl_encode!(
+    [E0, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13, E14, E15],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+);
 
 #[cfg(test)]
 mod test {
@@ -161,4 +274,82 @@ mod test {
         encoder.encode_all(&mut buf).unwrap();
         assert_eq!(buf, [0, 1]);
     }
+
+    #[test]
+    fn large_tuple_works() {
+        let u8e = U8Encoder::new;
+        let mut encoder = TupleEncoder::new((
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+            u8e(),
+        ));
+        let item = (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+        track_try_unwrap!(encoder.start_encoding(item));
+        assert_eq!(encoder.exact_requiring_bytes(), 16);
+        assert_eq!(encoder.requiring_bytes(), ByteCount::Finite(16));
+
+        let mut buf = Vec::new();
+        track_try_unwrap!(encoder.encode_all(&mut buf));
+        assert_eq!(buf, (0..16).collect::<Vec<u8>>());
+
+        let u8d = U8Decoder::new;
+        let mut decoder = TupleDecoder::new((
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+            u8d(),
+        ));
+        let (d0, d1, d2, d3, d4, d5, d6, d7, d8, d9, d10, d11, d12, d13, d14, d15) =
+            track_try_unwrap!(decoder.decode_exact(&buf[..]));
+        assert_eq!(
+            [d0, d1, d2, d3, d4, d5, d6, d7, d8, d9, d10, d11, d12, d13, d14, d15],
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+        );
+        assert!(format!("{:?}", decoder).starts_with("TupleDecoder { inner: (U8Decoder("));
+
+        let encoder = TupleEncoder::<(
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+            U8Encoder,
+        )>::default();
+        assert!(encoder.is_idle());
+    }
 }
@@ -0,0 +1,1261 @@
+//! Encoders and decoders for XDR ([RFC 4506]) data types.
+//!
+//! All XDR items are encoded in big-endian byte order and padded to a multiple of four bytes.
+//! Encoders always write zero bytes as padding, but decoders skip padding without validating it,
+//! so non-zero padding bytes (which [RFC 4506] §4.9 forbids) are accepted.
+//!
+//! Since the maximum sizes (`<m>`) of variable-length types are specific to each protocol,
+//! decoders apply conservative default limits instead
+//! (`DEFAULT_MAX_LEN` bytes for opaque data and strings,
+//! and `bytecodec::combinator::DEFAULT_MAX_ITEMS` elements for arrays).
+//! Use `set_max_len` or `set_max_items` to adjust them to the protocol.
+//!
+//! | XDR type                  | Decoder / Encoder                                   |
+//! |---------------------------|-----------------------------------------------------|
+//! | `int`, `unsigned int`     | `IntDecoder`, `UnsignedIntDecoder`, ...             |
+//! | `hyper`, `unsigned hyper` | `HyperDecoder`, `UnsignedHyperDecoder`, ...         |
+//! | `float`, `double`         | `FloatDecoder`, `DoubleDecoder`, ...                |
+//! | `bool`                    | `BoolDecoder`, `BoolEncoder`                        |
+//! | `opaque[n]`               | `FixedOpaqueDecoder`, `FixedOpaqueEncoder`          |
+//! | `opaque<m>`               | `OpaqueDecoder`, `OpaqueEncoder`                    |
+//! | `string<m>`               | `StringDecoder`, `StringEncoder`                    |
+//! | `type[n]`                 | `bytecodec::array::ArrayDecoder`, `ArrayEncoder`    |
+//! | `type<m>`                 | `VarArrayDecoder`, `VarArrayEncoder`                |
+//! | `*type` (optional-data)   | `OptionalDecoder`, `OptionalEncoder`                |
+//! | `union switch`            | `UnionDecoder`, `UnionEncoder`                      |
+//!
+//! [RFC 4506]: https://tools.ietf.org/html/rfc4506
+use crate::bytes::{BytesEncoder, CopyableBytesDecoder, RemainingBytesDecoder};
+use crate::combinator::{Align, CollectN, CountPrefixed, Length, Repeat};
+use crate::fixnum::{
+    F32beDecoder, F32beEncoder, F64beDecoder, F64beEncoder, I32beDecoder, I32beEncoder,
+    I64beDecoder, I64beEncoder, U32beDecoder, U32beEncoder, U64beDecoder, U64beEncoder,
+};
+use crate::sum::{OptionDecoder, OptionEncoder};
+use crate::tuple::TupleEncoder;
+use crate::{ByteCount, Decode, DecodeExt, Encode, EncodeExt, Eos, ErrorKind, Result};
+
+/// The default maximum length (in bytes) of data decoded by `OpaqueDecoder` and `StringDecoder`.
+pub const DEFAULT_MAX_LEN: u32 = 1024 * 1024;
+use crate::{SizedEncode, TaggedDecode};
+use trackable::error::ErrorKindExt;
+
+/// Decoder for XDR signed integers (`int`).
+pub type IntDecoder = I32beDecoder;
+
+/// Encoder for XDR signed integers (`int`).
+pub type IntEncoder = I32beEncoder;
+
+/// Decoder for XDR unsigned integers (`unsigned int`).
+pub type UnsignedIntDecoder = U32beDecoder;
+
+/// Encoder for XDR unsigned integers (`unsigned int`).
+pub type UnsignedIntEncoder = U32beEncoder;
+
+/// Decoder for XDR signed hyper integers (`hyper`).
+pub type HyperDecoder = I64beDecoder;
+
+/// Encoder for XDR signed hyper integers (`hyper`).
+pub type HyperEncoder = I64beEncoder;
+
+/// Decoder for XDR unsigned hyper integers (`unsigned hyper`).
+pub type UnsignedHyperDecoder = U64beDecoder;
+
+/// Encoder for XDR unsigned hyper integers (`unsigned hyper`).
+pub type UnsignedHyperEncoder = U64beEncoder;
+
+/// Decoder for XDR single-precision floating-point numbers (`float`).
+pub type FloatDecoder = F32beDecoder;
+
+/// Encoder for XDR single-precision floating-point numbers (`float`).
+pub type FloatEncoder = F32beEncoder;
+
+/// Decoder for XDR double-precision floating-point numbers (`double`).
+pub type DoubleDecoder = F64beDecoder;
+
+/// Encoder for XDR double-precision floating-point numbers (`double`).
+pub type DoubleEncoder = F64beEncoder;
+
+/// Decoder for XDR booleans (`bool`).
+///
+/// Values other than `0` and `1` are rejected as `ErrorKind::InvalidInput`.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::xdr::BoolDecoder;
+///
+/// let mut decoder = BoolDecoder::new();
+/// assert_eq!(decoder.decode_exact([0, 0, 0, 1].as_ref()).unwrap(), true);
+/// assert!(decoder.decode_exact([0, 0, 0, 2].as_ref()).is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct BoolDecoder(U32beDecoder);
+impl BoolDecoder {
+    /// Makes a new `BoolDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Decode for BoolDecoder {
+    type Item = bool;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        match track!(self.0.finish_decoding())? {
+            0 => Ok(false),
+            1 => Ok(true),
+            n => track_panic!(ErrorKind::InvalidInput, "Not a boolean value"; n),
+        }
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+
+/// Encoder for XDR booleans (`bool`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::xdr::BoolEncoder;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = BoolEncoder::with_item(true).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 0, 0, 1]);
+/// ```
+#[derive(Debug, Default)]
+pub struct BoolEncoder(U32beEncoder);
+impl BoolEncoder {
+    /// Makes a new `BoolEncoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Encode for BoolEncoder {
+    type Item = bool;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.0.start_encoding(u32::from(item)))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl SizedEncode for BoolEncoder {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+
+/// Decoder for XDR fixed-length opaque data (`opaque[N]`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::xdr::FixedOpaqueDecoder;
+///
+/// let mut decoder = FixedOpaqueDecoder::<3>::new();
+/// let item = decoder.decode_exact(b"foo\x00".as_ref()).unwrap();
+/// assert_eq!(item, *b"foo");
+/// ```
+#[derive(Debug)]
+pub struct FixedOpaqueDecoder<const N: usize>(Align<CopyableBytesDecoder<[u8; N]>>);
+impl<const N: usize> FixedOpaqueDecoder<N> {
+    /// Makes a new `FixedOpaqueDecoder` instance.
+    pub fn new() -> Self {
+        FixedOpaqueDecoder(Align::new(CopyableBytesDecoder::new([0; N]), 4, 0))
+    }
+}
+impl<const N: usize> Default for FixedOpaqueDecoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<const N: usize> Decode for FixedOpaqueDecoder<N> {
+    type Item = [u8; N];
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track!(self.0.finish_decoding())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+
+/// Encoder for XDR fixed-length opaque data (`opaque[N]`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::xdr::FixedOpaqueEncoder;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = FixedOpaqueEncoder::with_item(*b"foo").unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, b"foo\x00");
+/// ```
+#[derive(Debug)]
+pub struct FixedOpaqueEncoder<const N: usize>(Align<BytesEncoder<[u8; N]>>);
+impl<const N: usize> FixedOpaqueEncoder<N> {
+    /// Makes a new `FixedOpaqueEncoder` instance.
+    pub fn new() -> Self {
+        FixedOpaqueEncoder(Align::new(BytesEncoder::new(), 4, 0))
+    }
+}
+impl<const N: usize> Default for FixedOpaqueEncoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<const N: usize> Encode for FixedOpaqueEncoder<N> {
+    type Item = [u8; N];
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.0.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<const N: usize> SizedEncode for FixedOpaqueEncoder<N> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+
+/// Decoder for XDR variable-length opaque data (`opaque<m>`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::xdr::OpaqueDecoder;
+///
+/// let mut decoder = OpaqueDecoder::new();
+/// decoder.set_max_len(8);
+///
+/// let item = decoder.decode_exact(b"\x00\x00\x00\x05hello\x00\x00\x00".as_ref()).unwrap();
+/// assert_eq!(item, b"hello");
+///
+/// let input = b"\x00\x00\x00\x09helloworld\x00\x00\x00";
+/// assert!(decoder.decode_exact(input.as_ref()).is_err());
+/// ```
+#[derive(Debug)]
+pub struct OpaqueDecoder {
+    len: U32beDecoder,
+    bytes: Align<Length<RemainingBytesDecoder>>,
+    max_len: u32,
+    started: bool,
+}
+impl OpaqueDecoder {
+    /// Makes a new `OpaqueDecoder` instance.
+    pub fn new() -> Self {
+        OpaqueDecoder {
+            len: U32beDecoder::new(),
+            bytes: Align::new(RemainingBytesDecoder::new().length(0), 4, 0),
+            max_len: DEFAULT_MAX_LEN,
+            started: false,
+        }
+    }
+
+    /// Returns the maximum length of data allowed to be decoded.
+    pub fn max_len(&self) -> u32 {
+        self.max_len
+    }
+
+    /// Sets the maximum length of data allowed to be decoded.
+    ///
+    /// The default value is `DEFAULT_MAX_LEN`.
+    pub fn set_max_len(&mut self, n: u32) {
+        self.max_len = n;
+    }
+}
+impl Default for OpaqueDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Decode for OpaqueDecoder {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        if !self.started {
+            bytecodec_try_decode!(self.len, offset, buf, eos);
+            let n = track!(self.len.finish_decoding())?;
+            track_assert!(n <= self.max_len, ErrorKind::InvalidInput,
+                          "Too long data"; n, self.max_len);
+            track!(self.bytes.inner_mut().set_expected_bytes(u64::from(n)))?;
+            self.started = true;
+        }
+        bytecodec_try_decode!(self.bytes, offset, buf, eos);
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(self.started, ErrorKind::IncompleteDecoding);
+        let bytes = track!(self.bytes.finish_decoding())?;
+        self.started = false;
+        Ok(bytes)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.started {
+            self.bytes.requiring_bytes()
+        } else {
+            self.len.requiring_bytes()
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.started && self.bytes.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.len.reset())?;
+        track!(self.bytes.reset())?;
+        self.started = false;
+        Ok(())
+    }
+}
+
+/// Encoder for XDR variable-length opaque data (`opaque<m>`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::xdr::OpaqueEncoder;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = OpaqueEncoder::new();
+/// encoder.start_encoding(b"hello").unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, b"\x00\x00\x00\x05hello\x00\x00\x00");
+/// ```
+#[derive(Debug)]
+pub struct OpaqueEncoder<B = Vec<u8>> {
+    inner: TupleEncoder<(U32beEncoder, Align<BytesEncoder<B>>)>,
+    max_len: u32,
+}
+impl<B> OpaqueEncoder<B> {
+    /// Makes a new `OpaqueEncoder` instance.
+    pub fn new() -> Self {
+        OpaqueEncoder {
+            inner: TupleEncoder::new((U32beEncoder::new(), Align::new(BytesEncoder::new(), 4, 0))),
+            max_len: u32::MAX,
+        }
+    }
+
+    /// Returns the maximum length of data allowed to be encoded.
+    pub fn max_len(&self) -> u32 {
+        self.max_len
+    }
+
+    /// Sets the maximum length of data allowed to be encoded.
+    ///
+    /// The default value is `u32::MAX`.
+    pub fn set_max_len(&mut self, n: u32) {
+        self.max_len = n;
+    }
+}
+impl<B> Default for OpaqueEncoder<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<B: AsRef<[u8]>> Encode for OpaqueEncoder<B> {
+    type Item = B;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.inner.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        let n = item.as_ref().len();
+        track_assert!(n <= self.max_len as usize, ErrorKind::InvalidInput,
+                      "Too long data"; n, self.max_len);
+        track!(self.inner.start_encoding((n as u32, item)))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.inner.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.inner.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.inner.reset())
+    }
+}
+impl<B: AsRef<[u8]>> SizedEncode for OpaqueEncoder<B> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.inner.exact_requiring_bytes()
+    }
+}
+
+/// Decoder for XDR strings (`string<m>`).
+///
+/// Decoded strings must be valid UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::xdr::StringDecoder;
+///
+/// let mut decoder = StringDecoder::new();
+/// let item = decoder.decode_exact(b"\x00\x00\x00\x03foo\x00".as_ref()).unwrap();
+/// assert_eq!(item, "foo");
+/// ```
+#[derive(Debug, Default)]
+pub struct StringDecoder(OpaqueDecoder);
+impl StringDecoder {
+    /// Makes a new `StringDecoder` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the maximum length (in bytes) of strings allowed to be decoded.
+    pub fn max_len(&self) -> u32 {
+        self.0.max_len()
+    }
+
+    /// Sets the maximum length (in bytes) of strings allowed to be decoded.
+    ///
+    /// The default value is `DEFAULT_MAX_LEN`.
+    pub fn set_max_len(&mut self, n: u32) {
+        self.0.set_max_len(n);
+    }
+}
+impl Decode for StringDecoder {
+    type Item = String;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        let b = track!(self.0.finish_decoding())?;
+        let s = track!(String::from_utf8(b).map_err(|e| ErrorKind::InvalidInput.cause(e)))?;
+        Ok(s)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+
+#[derive(Debug)]
+struct StrBytes<S>(S);
+impl<S: AsRef<str>> AsRef<[u8]> for StrBytes<S> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref().as_bytes()
+    }
+}
+
+/// Encoder for XDR strings (`string<m>`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::EncodeExt;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::xdr::StringEncoder;
+///
+/// let mut output = Vec::new();
+/// let mut encoder = StringEncoder::with_item("foo").unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, b"\x00\x00\x00\x03foo\x00");
+/// ```
+#[derive(Debug, Default)]
+pub struct StringEncoder<S = String>(OpaqueEncoder<StrBytes<S>>);
+impl<S> StringEncoder<S> {
+    /// Makes a new `StringEncoder` instance.
+    pub fn new() -> Self {
+        StringEncoder(OpaqueEncoder::new())
+    }
+
+    /// Returns the maximum length (in bytes) of strings allowed to be encoded.
+    pub fn max_len(&self) -> u32 {
+        self.0.max_len()
+    }
+
+    /// Sets the maximum length (in bytes) of strings allowed to be encoded.
+    ///
+    /// The default value is `u32::MAX`.
+    pub fn set_max_len(&mut self, n: u32) {
+        self.0.set_max_len(n);
+    }
+}
+impl<S: AsRef<str>> Encode for StringEncoder<S> {
+    type Item = S;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.0.start_encoding(StrBytes(item)))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<S: AsRef<str>> SizedEncode for StringEncoder<S> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+
+/// Decoder for XDR variable-length arrays (`type<m>`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::xdr::{IntDecoder, VarArrayDecoder};
+///
+/// let mut decoder = VarArrayDecoder::<_, Vec<_>>::new(IntDecoder::new());
+/// let item = decoder.decode_exact([0, 0, 0, 2, 0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF].as_ref()).unwrap();
+/// assert_eq!(item, [1, -1]);
+/// ```
+#[derive(Debug)]
+pub struct VarArrayDecoder<D, T>(CountPrefixed<CollectN<D, T>, U32beDecoder>);
+impl<D, T> VarArrayDecoder<D, T>
+where
+    D: Decode,
+    T: Default + Extend<D::Item>,
+{
+    /// Makes a new `VarArrayDecoder` instance that decodes each element by using `inner`.
+    pub fn new(inner: D) -> Self {
+        VarArrayDecoder(inner.count_prefixed(U32beDecoder::new()))
+    }
+
+    /// Returns the maximum number of elements allowed to be decoded.
+    pub fn max_items(&self) -> usize {
+        self.0.max_items()
+    }
+
+    /// Sets the maximum number of elements allowed to be decoded.
+    ///
+    /// The default value is `bytecodec::combinator::DEFAULT_MAX_ITEMS`.
+    pub fn set_max_items(&mut self, n: usize) {
+        self.0.set_max_items(n);
+    }
+
+    /// Returns a reference to the element decoder.
+    pub fn inner_ref(&self) -> &D {
+        self.0.inner_ref().inner_ref()
+    }
+
+    /// Returns a mutable reference to the element decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        self.0.inner_mut().inner_mut()
+    }
+}
+impl<D, T> Decode for VarArrayDecoder<D, T>
+where
+    D: Decode,
+    T: Default + Extend<D::Item>,
+{
+    type Item = T;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track!(self.0.finish_decoding())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+
+/// Encoder for XDR variable-length arrays (`type<m>`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::xdr::{IntEncoder, VarArrayEncoder};
+///
+/// let mut output = Vec::new();
+/// let mut encoder = VarArrayEncoder::new(IntEncoder::new());
+/// encoder.start_encoding(vec![1, -1].into_iter()).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 0, 0, 2, 0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF]);
+/// ```
+#[derive(Debug)]
+pub struct VarArrayEncoder<E, I>(CountPrefixed<Repeat<E, I>, U32beEncoder>);
+impl<E, I> VarArrayEncoder<E, I>
+where
+    E: Encode,
+    I: ExactSizeIterator<Item = E::Item>,
+{
+    /// Makes a new `VarArrayEncoder` instance that encodes each element by using `inner`.
+    pub fn new(inner: E) -> Self {
+        VarArrayEncoder(inner.count_prefixed(U32beEncoder::new()))
+    }
+
+    /// Returns the maximum number of elements allowed to be encoded.
+    pub fn max_items(&self) -> usize {
+        self.0.max_items()
+    }
+
+    /// Sets the maximum number of elements allowed to be encoded.
+    pub fn set_max_items(&mut self, n: usize) {
+        self.0.set_max_items(n);
+    }
+}
+impl<E, I> Encode for VarArrayEncoder<E, I>
+where
+    E: Encode,
+    I: ExactSizeIterator<Item = E::Item>,
+{
+    type Item = I;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.0.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+
+/// Decoder for XDR optional-data (`*type`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::io::IoDecodeExt;
+/// use bytecodec::xdr::{IntDecoder, OptionalDecoder};
+///
+/// let mut decoder = OptionalDecoder::new(IntDecoder::new());
+/// assert_eq!(decoder.decode_exact([0, 0, 0, 0].as_ref()).unwrap(), None);
+///
+/// let item = decoder.decode_exact([0, 0, 0, 1, 0, 0, 0, 7].as_ref()).unwrap();
+/// assert_eq!(item, Some(7));
+/// ```
+#[derive(Debug)]
+pub struct OptionalDecoder<D>(OptionDecoder<D, U32beDecoder>);
+impl<D> OptionalDecoder<D> {
+    /// Makes a new `OptionalDecoder` instance.
+    pub fn new(inner: D) -> Self {
+        OptionalDecoder(OptionDecoder::with_tag_decoder(
+            inner,
+            U32beDecoder::new(),
+            (0, 1),
+        ))
+    }
+
+    /// Returns a reference to the inner decoder.
+    pub fn inner_ref(&self) -> &D {
+        self.0.inner_ref()
+    }
+
+    /// Returns a mutable reference to the inner decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        self.0.inner_mut()
+    }
+
+    /// Takes ownership of this instance and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.0.into_inner()
+    }
+}
+impl<D: Decode> Decode for OptionalDecoder<D> {
+    type Item = Option<D::Item>;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        track!(self.0.decode(buf, eos))
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track!(self.0.finish_decoding())
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<D: Default> Default for OptionalDecoder<D> {
+    fn default() -> Self {
+        Self::new(D::default())
+    }
+}
+
+/// Encoder for XDR optional-data (`*type`).
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::xdr::{IntEncoder, OptionalEncoder};
+///
+/// let mut output = Vec::new();
+/// let mut encoder = OptionalEncoder::new(IntEncoder::new());
+/// encoder.start_encoding(Some(7)).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, [0, 0, 0, 1, 0, 0, 0, 7]);
+/// ```
+#[derive(Debug)]
+pub struct OptionalEncoder<E>(OptionEncoder<E, U32beEncoder>);
+impl<E> OptionalEncoder<E> {
+    /// Makes a new `OptionalEncoder` instance.
+    pub fn new(inner: E) -> Self {
+        OptionalEncoder(OptionEncoder::with_tag_encoder(
+            inner,
+            U32beEncoder::new(),
+            (0, 1),
+        ))
+    }
+
+    /// Returns a reference to the inner encoder.
+    pub fn inner_ref(&self) -> &E {
+        self.0.inner_ref()
+    }
+
+    /// Returns a mutable reference to the inner encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        self.0.inner_mut()
+    }
+
+    /// Takes ownership of this instance and returns the inner encoder.
+    pub fn into_inner(self) -> E {
+        self.0.into_inner()
+    }
+}
+impl<E: Encode> Encode for OptionalEncoder<E> {
+    type Item = Option<E::Item>;
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.0.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<E: SizedEncode> SizedEncode for OptionalEncoder<E> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+impl<E: Default> Default for OptionalEncoder<E> {
+    fn default() -> Self {
+        Self::new(E::default())
+    }
+}
+
+/// Decoder for XDR discriminated unions (`union switch`).
+///
+/// This decodes a discriminant by using `T` (`int` by default),
+/// and then passes it to `TaggedDecode::start_decoding` of the arm decoder `D`
+/// before decoding the arm.
+#[derive(Debug)]
+pub struct UnionDecoder<D, T = I32beDecoder> {
+    discriminant: T,
+    arm: D,
+    started: bool,
+}
+impl<D> UnionDecoder<D> {
+    /// Makes a new `UnionDecoder` instance that decodes `int` discriminants.
+    pub fn new(arm: D) -> Self {
+        Self::with_discriminant_decoder(arm, I32beDecoder::new())
+    }
+}
+impl<D, T> UnionDecoder<D, T> {
+    /// Makes a new `UnionDecoder` instance that decodes discriminants by using `discriminant_decoder`.
+    pub fn with_discriminant_decoder(arm: D, discriminant_decoder: T) -> Self {
+        UnionDecoder {
+            discriminant: discriminant_decoder,
+            arm,
+            started: false,
+        }
+    }
+
+    /// Returns a reference to the arm decoder.
+    pub fn inner_ref(&self) -> &D {
+        &self.arm
+    }
+
+    /// Returns a mutable reference to the arm decoder.
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.arm
+    }
+
+    /// Takes ownership of this instance and returns the arm decoder.
+    pub fn into_inner(self) -> D {
+        self.arm
+    }
+}
+impl<D, T> Decode for UnionDecoder<D, T>
+where
+    D: TaggedDecode,
+    T: Decode<Item = D::Tag>,
+{
+    type Item = D::Item;
+
+    fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+        let mut offset = 0;
+        if !self.started {
+            bytecodec_try_decode!(self.discriminant, offset, buf, eos);
+            let tag = track!(self.discriminant.finish_decoding())?;
+            track!(self.arm.start_decoding(tag))?;
+            self.started = true;
+        }
+        bytecodec_try_decode!(self.arm, offset, buf, eos);
+        Ok(offset)
+    }
+
+    fn finish_decoding(&mut self) -> Result<Self::Item> {
+        track_assert!(self.started, ErrorKind::IncompleteDecoding);
+        let item = track!(self.arm.finish_decoding())?;
+        self.started = false;
+        Ok(item)
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        if self.started {
+            self.arm.requiring_bytes()
+        } else {
+            self.discriminant.requiring_bytes()
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.started && self.arm.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.discriminant.reset())?;
+        track!(self.arm.reset())?;
+        self.started = false;
+        Ok(())
+    }
+}
+
+/// Encoder for XDR discriminated unions (`union switch`).
+///
+/// Items are pairs of a discriminant and an arm value.
+///
+/// # Examples
+///
+/// ```
+/// use bytecodec::Encode;
+/// use bytecodec::io::IoEncodeExt;
+/// use bytecodec::xdr::{StringEncoder, UnionEncoder};
+///
+/// let mut output = Vec::new();
+/// let mut encoder = UnionEncoder::new(StringEncoder::new());
+/// encoder.start_encoding((2, "foo")).unwrap();
+/// encoder.encode_all(&mut output).unwrap();
+/// assert_eq!(output, b"\x00\x00\x00\x02\x00\x00\x00\x03foo\x00");
+/// ```
+#[derive(Debug)]
+pub struct UnionEncoder<E, T = I32beEncoder>(TupleEncoder<(T, E)>);
+impl<E> UnionEncoder<E> {
+    /// Makes a new `UnionEncoder` instance that encodes `int` discriminants.
+    pub fn new(arm: E) -> Self {
+        Self::with_discriminant_encoder(arm, I32beEncoder::new())
+    }
+}
+impl<E, T> UnionEncoder<E, T> {
+    /// Makes a new `UnionEncoder` instance that encodes discriminants by using `discriminant_encoder`.
+    pub fn with_discriminant_encoder(arm: E, discriminant_encoder: T) -> Self {
+        UnionEncoder(TupleEncoder::new((discriminant_encoder, arm)))
+    }
+
+    /// Returns a reference to the arm encoder.
+    pub fn inner_ref(&self) -> &E {
+        &self.0.inner_ref().1
+    }
+
+    /// Returns a mutable reference to the arm encoder.
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.0.inner_mut().1
+    }
+
+    /// Takes ownership of this instance and returns the arm encoder.
+    pub fn into_inner(self) -> E {
+        self.0.into_inner().1
+    }
+}
+impl<E: Encode, T: Encode> Encode for UnionEncoder<E, T> {
+    type Item = (T::Item, E::Item);
+
+    fn encode(&mut self, buf: &mut [u8], eos: Eos) -> Result<usize> {
+        track!(self.0.encode(buf, eos))
+    }
+
+    fn start_encoding(&mut self, item: Self::Item) -> Result<()> {
+        track!(self.0.start_encoding(item))
+    }
+
+    fn requiring_bytes(&self) -> ByteCount {
+        self.0.requiring_bytes()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.is_idle()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        track!(self.0.reset())
+    }
+}
+impl<E: SizedEncode, T: SizedEncode> SizedEncode for UnionEncoder<E, T> {
+    fn exact_requiring_bytes(&self) -> u64 {
+        self.0.exact_requiring_bytes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::combinator::{Either, DEFAULT_MAX_ITEMS};
+    use crate::io::{IoDecodeExt, IoEncodeExt};
+
+    #[derive(Debug, Default)]
+    struct ArmDecoder {
+        int: IntDecoder,
+        string: StringDecoder,
+        tag: Option<i32>,
+    }
+    impl Decode for ArmDecoder {
+        type Item = Either<i32, String>;
+
+        fn decode(&mut self, buf: &[u8], eos: Eos) -> Result<usize> {
+            match self.tag {
+                Some(0) => track!(self.int.decode(buf, eos)),
+                Some(_) => track!(self.string.decode(buf, eos)),
+                None => track_panic!(ErrorKind::Other, "Not started"),
+            }
+        }
+
+        fn finish_decoding(&mut self) -> Result<Self::Item> {
+            match self.tag.take() {
+                Some(0) => track!(self.int.finish_decoding()).map(Either::Left),
+                Some(_) => track!(self.string.finish_decoding()).map(Either::Right),
+                None => track_panic!(ErrorKind::IncompleteDecoding),
+            }
+        }
+
+        fn requiring_bytes(&self) -> ByteCount {
+            match self.tag {
+                Some(0) => self.int.requiring_bytes(),
+                Some(_) => self.string.requiring_bytes(),
+                None => ByteCount::Finite(0),
+            }
+        }
+
+        fn is_idle(&self) -> bool {
+            match self.tag {
+                Some(0) => self.int.is_idle(),
+                Some(_) => self.string.is_idle(),
+                None => true,
+            }
+        }
+    }
+    impl TaggedDecode for ArmDecoder {
+        type Tag = i32;
+
+        fn start_decoding(&mut self, tag: Self::Tag) -> Result<()> {
+            track_assert!(tag == 0 || tag == 1, ErrorKind::InvalidInput; tag);
+            self.tag = Some(tag);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn union_works() {
+        let mut output = Vec::new();
+        let mut encoder = UnionEncoder::new(StringEncoder::new());
+        track_try_unwrap!(encoder.start_encoding((1, "hello")));
+        assert_eq!(encoder.exact_requiring_bytes(), 16);
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        output.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 7]);
+
+        let mut decoder = UnionDecoder::new(ArmDecoder::default());
+        let mut input = &output[..];
+        let item = track_try_unwrap!(decoder.decode_exact(&mut input));
+        assert_eq!(item, Either::Right("hello".to_owned()));
+        let item = track_try_unwrap!(decoder.decode_exact(&mut input));
+        assert_eq!(item, Either::Left(7));
+
+        let error = decoder.decode_exact(&[0, 0, 0, 2][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn opaque_works() {
+        for n in 0..9 {
+            let data = vec![0xAB; n];
+            let mut output = Vec::new();
+            let mut encoder = OpaqueEncoder::new();
+            track_try_unwrap!(encoder.start_encoding(data.clone()));
+            track_try_unwrap!(encoder.encode_all(&mut output));
+            assert_eq!(output.len() % 4, 0);
+            assert_eq!(output.len(), 4 + n.div_ceil(4) * 4);
+
+            let mut decoder = OpaqueDecoder::new();
+            output.extend_from_slice(&[0, 0, 0, 0]);
+            let mut input = &output[..];
+            assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), data);
+            assert!(track_try_unwrap!(decoder.decode_exact(&mut input)).is_empty());
+        }
+
+        let mut decoder = StringDecoder::new();
+        assert_eq!(decoder.max_len(), DEFAULT_MAX_LEN);
+        let error = decoder.decode_exact(&[0xFF; 4][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let mut encoder = StringEncoder::new();
+        encoder.set_max_len(2);
+        let error = encoder.start_encoding("foo").err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn bool_works() {
+        let mut decoder = BoolDecoder::new();
+        let mut input = &[0, 0, 0, 0, 0, 0, 0, 1][..];
+        assert!(!track_try_unwrap!(decoder.decode_exact(&mut input)));
+        assert!(track_try_unwrap!(decoder.decode_exact(&mut input)));
+
+        let error = decoder.decode_exact(&[0, 0, 0, 2][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+        let error = decoder
+            .decode_exact(&[0xFF, 0xFF, 0xFF, 0xFF][..])
+            .err()
+            .unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn fixed_opaque_works() {
+        let mut output = Vec::new();
+        let mut encoder = FixedOpaqueEncoder::new();
+        track_try_unwrap!(encoder.start_encoding(*b"hello"));
+        assert_eq!(encoder.exact_requiring_bytes(), 8);
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, b"hello\x00\x00\x00");
+
+        let mut decoder = FixedOpaqueDecoder::<5>::new();
+        let item = track_try_unwrap!(decoder.decode_exact(&output[..]));
+        assert_eq!(item, *b"hello");
+
+        // No padding is needed if the size is a multiple of four
+        let mut output = Vec::new();
+        let mut encoder = FixedOpaqueEncoder::new();
+        track_try_unwrap!(encoder.start_encoding(*b"abcd"));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, b"abcd");
+
+        let mut decoder = FixedOpaqueDecoder::<4>::new();
+        let mut input = &b"abcdefgh"[..];
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_exact(&mut input)),
+            *b"abcd"
+        );
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_exact(&mut input)),
+            *b"efgh"
+        );
+    }
+
+    #[test]
+    fn truncated_padding_is_rejected() {
+        let mut decoder = FixedOpaqueDecoder::<3>::new();
+        let error = decoder.decode_exact(&b"foo"[..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+
+        let mut decoder = OpaqueDecoder::new();
+        let error = decoder
+            .decode_exact(&b"\x00\x00\x00\x05hello\x00"[..])
+            .err()
+            .unwrap();
+        assert_eq!(*error.kind(), ErrorKind::UnexpectedEos);
+    }
+
+    #[test]
+    fn var_array_works() {
+        let mut output = Vec::new();
+        let mut encoder = VarArrayEncoder::new(StringEncoder::new());
+        track_try_unwrap!(encoder.start_encoding(vec!["a", "bcd"].into_iter()));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(
+            output,
+            b"\x00\x00\x00\x02\x00\x00\x00\x01a\x00\x00\x00\x00\x00\x00\x03bcd\x00"
+        );
+
+        let mut decoder = VarArrayDecoder::<_, Vec<_>>::new(StringDecoder::new());
+        assert_eq!(decoder.max_items(), DEFAULT_MAX_ITEMS);
+        let error = decoder.decode_exact(&[0xFF; 4][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        let item = track_try_unwrap!(decoder.decode_exact(&output[..]));
+        assert_eq!(item, ["a", "bcd"]);
+
+        let item = track_try_unwrap!(decoder.decode_exact(&[0, 0, 0, 0][..]));
+        assert!(item.is_empty());
+
+        decoder.set_max_items(1);
+        let error = decoder.decode_exact(&output[..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+
+        encoder.set_max_items(1);
+        let error = encoder
+            .start_encoding(vec!["a", "bcd"].into_iter())
+            .err()
+            .unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn optional_works() {
+        let mut output = Vec::new();
+        let mut encoder = OptionalEncoder::new(IntEncoder::new());
+        track_try_unwrap!(encoder.start_encoding(None));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        track_try_unwrap!(encoder.start_encoding(Some(-2)));
+        track_try_unwrap!(encoder.encode_all(&mut output));
+        assert_eq!(output, [0, 0, 0, 0, 0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFE]);
+
+        let mut decoder = OptionalDecoder::new(IntDecoder::new());
+        let mut input = &output[..];
+        assert_eq!(track_try_unwrap!(decoder.decode_exact(&mut input)), None);
+        assert_eq!(
+            track_try_unwrap!(decoder.decode_exact(&mut input)),
+            Some(-2)
+        );
+
+        let error = decoder.decode_exact(&[0, 0, 0, 2][..]).err().unwrap();
+        assert_eq!(*error.kind(), ErrorKind::InvalidInput);
+    }
+}